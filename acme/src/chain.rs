@@ -0,0 +1,268 @@
+/// A PEM-encoded certificate chain issued by the ACME server
+///
+/// CAs may offer several chains for the same certificate, for example one ending with a
+/// certificate cross-signed by an older root for compatibility with legacy clients.
+///
+/// <https://datatracker.ietf.org/doc/html/rfc8555#section-7.4.2>
+#[derive(Clone, Debug)]
+pub struct CertificateChain {
+    pem: String,
+}
+
+impl CertificateChain {
+    pub(crate) fn new(pem: String) -> Self {
+        Self {
+            pem,
+        }
+    }
+
+    /// Get the PEM-encoded chain, starting with the end-entity certificate
+    pub fn pem(&self) -> &str {
+        &self.pem
+    }
+
+    /// Get the PEM-encoded chain, starting with the end-entity certificate
+    pub fn into_pem(self) -> String {
+        self.pem
+    }
+
+    /// Get the issuer common name of each certificate of the chain, starting with the
+    /// end-entity certificate
+    ///
+    /// Certificates that can't be parsed or don't have an issuer common name are skipped.
+    pub fn issuers(&self) -> Vec<String> {
+        pem_certificates(&self.pem)
+            .filter_map(|der| issuer_common_name(&der))
+            .collect()
+    }
+
+    /// Get the issuer common name of the topmost certificate of the chain
+    ///
+    /// This is the name used by certbot's `--preferred-chain` to select a chain, such as
+    /// `ISRG Root X1`.
+    pub fn root_issuer(&self) -> Option<String> {
+        pem_certificates(&self.pem)
+            .last()
+            .and_then(|der| issuer_common_name(&der))
+    }
+}
+
+const PEM_CERTIFICATE_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_CERTIFICATE_END: &str = "-----END CERTIFICATE-----";
+
+/// Iterate over the DER-encoded certificates of a PEM bundle
+fn pem_certificates(pem: &str) -> impl Iterator<Item = Vec<u8>> + '_ {
+    pem.split(PEM_CERTIFICATE_BEGIN).skip(1).filter_map(|block| {
+        let (base64, _) = block.split_once(PEM_CERTIFICATE_END)?;
+        let base64 = base64.bytes().filter(|b| !b.is_ascii_whitespace()).collect::<Vec<_>>();
+        base64::decode(&base64).ok()
+    })
+}
+
+const DER_SEQUENCE: u8 = 0x30;
+const DER_SET: u8 = 0x31;
+const DER_OBJECT_IDENTIFIER: u8 = 0x06;
+const DER_CONTEXT_SPECIFIC_0: u8 = 0xa0;
+/// 2.5.4.3
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+
+/// Extract the common name of the issuer of a DER-encoded X.509 certificate
+///
+/// <https://datatracker.ietf.org/doc/html/rfc5280#section-4.1>
+fn issuer_common_name(der: &[u8]) -> Option<String> {
+    let (certificate, _) = der_expect(der, DER_SEQUENCE)?;
+    let (tbs_certificate, _) = der_expect(certificate, DER_SEQUENCE)?;
+
+    // the version is optional and tagged [0], the serial number follows
+    let (tag, _, mut rest) = der_read(tbs_certificate)?;
+    if tag == DER_CONTEXT_SPECIFIC_0 {
+        (_, _, rest) = der_read(rest)?;
+    }
+    // signature algorithm
+    let (_, _, rest) = der_read(rest)?;
+    let (mut issuer, _) = der_expect(rest, DER_SEQUENCE)?;
+
+    while !issuer.is_empty() {
+        let (mut relative_name, rest) = der_expect(issuer, DER_SET)?;
+        issuer = rest;
+
+        while !relative_name.is_empty() {
+            let (attribute, rest) = der_expect(relative_name, DER_SEQUENCE)?;
+            relative_name = rest;
+
+            let (oid, value) = der_expect(attribute, DER_OBJECT_IDENTIFIER)?;
+            if oid == OID_COMMON_NAME {
+                let (_, value, _) = der_read(value)?;
+                return Some(String::from_utf8_lossy(value).into_owned());
+            }
+        }
+    }
+
+    None
+}
+
+/// Read a DER TLV of the expected tag, returning its value and the remaining input
+fn der_expect(input: &[u8], expected_tag: u8) -> Option<(&[u8], &[u8])> {
+    match der_read(input)? {
+        (tag, value, rest) if tag == expected_tag => Some((value, rest)),
+        _ => None,
+    }
+}
+
+/// Read a DER TLV, returning its tag, its value and the remaining input
+fn der_read(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, input) = input.split_first()?;
+    let (&first, mut input) = input.split_first()?;
+
+    let len = if first < 0x80 {
+        first as usize
+    } else {
+        let len_bytes = (first & 0x7f) as usize;
+        if len_bytes == 0 || len_bytes > size_of::<usize>() || input.len() < len_bytes {
+            return None;
+        }
+        let (len, rest) = input.split_at(len_bytes);
+        input = rest;
+        len.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize)
+    };
+
+    if input.len() < len {
+        return None;
+    }
+    let (value, rest) = input.split_at(len);
+    Some((tag, value, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `CN=example.com` issued by `CN=Test Intermediate`, issued by `O=Test, CN=Test Root`
+    const CHAIN: &str = "-----BEGIN CERTIFICATE-----
+MIIBZjCCAQugAwIBAgIBAzAKBggqhkjOPQQDAjAcMRowGAYDVQQDDBFUZXN0IElu
+dGVybWVkaWF0ZTAgFw0yNjEwMTkwMDIzNDRaGA8yMTI2MDkyNTAwMjM0NFowFjEU
+MBIGA1UEAwwLZXhhbXBsZS5jb20wWTATBgcqhkjOPQIBBggqhkjOPQMBBwNCAATT
+4050ptxl6iJYDCU+hIao6yVwmr2Y3dsNOBeNPwv1ePDTitsCJeUXgfM/0l6OG5jr
+UVmYOW+In9j0vhTGwDYuo0IwQDAdBgNVHQ4EFgQUGN/R1ul52ChZJwj1LVDJjpDS
+uBAwHwYDVR0jBBgwFoAUwM4uIOWhvsfvxYHPy89VeFEJi78wCgYIKoZIzj0EAwID
+SQAwRgIhAI89cKm3gFEB58zyim5kvf3NLiZ97fK3GWf/itG7cjwOAiEAl81gf0Ku
+E/qwY058adQeyK7oTRDuvgMMjHUELushm60=
+-----END CERTIFICATE-----
+-----BEGIN CERTIFICATE-----
+MIIBhDCCASmgAwIBAgIBAjAKBggqhkjOPQQDAjAjMQ0wCwYDVQQKDARUZXN0MRIw
+EAYDVQQDDAlUZXN0IFJvb3QwIBcNMjYxMDE5MDAyMzQ0WhgPMjEyNjA5MjUwMDIz
+NDRaMBwxGjAYBgNVBAMMEVRlc3QgSW50ZXJtZWRpYXRlMFkwEwYHKoZIzj0CAQYI
+KoZIzj0DAQcDQgAEo39ZQjav09rokUAUaElBzTFRdsXHPWrbQkvqx02K9dfKel4+
+JUT+kFA+ms1B8011e4AFfRYElMApl1DnRdP9nqNTMFEwDwYDVR0TAQH/BAUwAwEB
+/zAdBgNVHQ4EFgQUwM4uIOWhvsfvxYHPy89VeFEJi78wHwYDVR0jBBgwFoAUu9Qt
+VquK79PFXiLsXYuxblYxcuIwCgYIKoZIzj0EAwIDSQAwRgIhAOaORUqgCJVCVAeY
+VM6umoieZC0NS+Ud95BdGd0ZDpzKAiEA88npO10gtaSdQ746czMCxtm3a+ZzX0oE
+p7g44Qru0iM=
+-----END CERTIFICATE-----
+";
+
+    fn tlv(tag: u8, value: &[u8]) -> Vec<u8> {
+        let mut der = vec![tag];
+        match value.len() {
+            len @ 0..0x80 => der.push(len as u8),
+            len @ 0x80..0x100 => der.extend([0x81, len as u8]),
+            len => der.extend([0x82, (len >> 8) as u8, len as u8]),
+        }
+        der.extend_from_slice(value);
+        der
+    }
+
+    /// A certificate skeleton without the optional version, with the given issuer attributes
+    fn v1_certificate(attributes: &[(&[u8], &str)]) -> Vec<u8> {
+        let issuer = attributes
+            .iter()
+            .flat_map(|(oid, value)| {
+                let attribute = [tlv(DER_OBJECT_IDENTIFIER, oid), tlv(0x0c, value.as_bytes())].concat();
+                tlv(DER_SET, &tlv(DER_SEQUENCE, &attribute))
+            })
+            .collect::<Vec<_>>();
+        let algorithm = tlv(DER_OBJECT_IDENTIFIER, &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02]);
+        let tbs_certificate = [
+            tlv(0x02, &[1]),
+            tlv(DER_SEQUENCE, &algorithm),
+            tlv(DER_SEQUENCE, &issuer),
+        ]
+        .concat();
+        tlv(DER_SEQUENCE, &tlv(DER_SEQUENCE, &tbs_certificate))
+    }
+
+    #[test]
+    fn chain_issuers() {
+        let chain = CertificateChain::new(CHAIN.to_owned());
+        assert_eq!(chain.issuers(), ["Test Intermediate", "Test Root"]);
+        assert_eq!(chain.root_issuer().as_deref(), Some("Test Root"));
+
+        let leaf = CertificateChain::new(CHAIN.split_inclusive(PEM_CERTIFICATE_END).next().unwrap().to_owned());
+        assert_eq!(leaf.issuers(), ["Test Intermediate"]);
+        assert_eq!(leaf.root_issuer().as_deref(), Some("Test Intermediate"));
+    }
+
+    #[test]
+    fn chain_without_certificates() {
+        let empty = CertificateChain::new(String::new());
+        assert!(empty.issuers().is_empty());
+        assert_eq!(empty.root_issuer(), None);
+
+        // a truncated block without an end marker is ignored
+        let truncated = CertificateChain::new(CHAIN[..CHAIN.len() - 200].to_owned());
+        assert_eq!(truncated.issuers(), ["Test Intermediate"]);
+
+        let invalid = format!("{PEM_CERTIFICATE_BEGIN}\n!!!!\n{PEM_CERTIFICATE_END}\n{CHAIN}");
+        let invalid = CertificateChain::new(invalid);
+        assert_eq!(invalid.issuers(), ["Test Intermediate", "Test Root"]);
+    }
+
+    #[test]
+    fn issuer_of_malformed_certificates() {
+        let leaf = pem_certificates(CHAIN).next().unwrap();
+        assert_eq!(issuer_common_name(&leaf).as_deref(), Some("Test Intermediate"));
+        for len in [0, 1, 2, 4, 40, leaf.len() - 1] {
+            assert_eq!(issuer_common_name(&leaf[..len]), None, "truncated to {len} bytes");
+        }
+
+        let mut wrong_tag = leaf.clone();
+        wrong_tag[0] = DER_SET;
+        assert_eq!(issuer_common_name(&wrong_tag), None);
+
+        let v1 = v1_certificate(&[(&[0x55, 0x04, 0x0a], "Test"), (OID_COMMON_NAME, "V1 Root")]);
+        assert_eq!(issuer_common_name(&v1).as_deref(), Some("V1 Root"));
+
+        let without_common_name = v1_certificate(&[(&[0x55, 0x04, 0x0a], "Test")]);
+        assert_eq!(issuer_common_name(&without_common_name), None);
+    }
+
+    #[test]
+    fn der_lengths() {
+        assert_eq!(der_read(&[0x04, 0x02, 1, 2, 3]), Some((0x04, &[1, 2][..], &[3][..])));
+        assert_eq!(der_read(&[0x04, 0x00]), Some((0x04, &[][..], &[][..])));
+
+        let long = tlv(0x04, &[7; 0x180]);
+        assert_eq!(long[1..4], [0x82, 0x01, 0x80]);
+        let (tag, value, rest) = der_read(&long).unwrap();
+        assert_eq!((tag, value.len(), rest.len()), (0x04, 0x180, 0));
+
+        // empty input, missing length, truncated value
+        assert_eq!(der_read(&[]), None);
+        assert_eq!(der_read(&[0x04]), None);
+        assert_eq!(der_read(&[0x04, 0x03, 1, 2]), None);
+        // indefinite length, truncated length, length wider than usize
+        assert_eq!(der_read(&[0x04, 0x80, 0x00, 0x00]), None);
+        assert_eq!(der_read(&[0x04, 0x82, 0x01]), None);
+        assert_eq!(der_read(&[0x04, 0x89, 0, 0, 0, 0, 0, 0, 0, 0, 1]), None);
+        assert_eq!(der_read(&long[..long.len() - 1]), None);
+    }
+
+    #[test]
+    fn der_tags() {
+        let der = [tlv(DER_SEQUENCE, &[1]), tlv(DER_SET, &[2])].concat();
+        assert_eq!(der_expect(&der, DER_SEQUENCE), Some((&[1][..], &der[3..])));
+        assert_eq!(der_expect(&der, DER_SET), None);
+        assert_eq!(der_expect(&der[3..], DER_SET), Some((&[2][..], &[][..])));
+        assert_eq!(der_expect(&[], DER_SEQUENCE), None);
+    }
+}
@@ -3,7 +3,11 @@
 #![warn(unreachable_pub)]
 #![warn(missing_docs)]
 
-use std::{fmt, sync::Arc};
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+};
 
 // use hyper::client::connect::Connect;
 // #[cfg(feature = "hyper-rustls")]
@@ -17,10 +21,14 @@ use aws_lc_rs::{
 };
 use reqwest::{
     Method, Response, StatusCode,
-    header::{CONTENT_TYPE, LOCATION},
+    header::{CONTENT_TYPE, LINK, LOCATION},
 };
 use serde::{Serialize, de::DeserializeOwned};
 
+mod chain;
+pub use chain::CertificateChain;
+#[cfg(feature = "test-server")]
+pub mod test_server;
 mod types;
 pub use types::{
     AccountCredentials,
@@ -36,7 +44,9 @@ pub use types::{
     OrderState,
     OrderStatus,
     Problem,
+    ProblemType,
     RevocationReason,
+    Subproblem,
     ZeroSsl, // RevocationRequest
 };
 use types::{
@@ -53,7 +63,6 @@ use types::{
 /// <https://datatracker.ietf.org/doc/html/rfc8555#section-7.1.3>
 pub struct Order {
     account: Arc<AccountInner>,
-    nonce: Option<String>,
     url: String,
     state: OrderState,
 }
@@ -77,7 +86,7 @@ impl Order {
     pub async fn authorizations(&mut self) -> Result<Vec<Authorization>, Error> {
         let mut authorizations = Vec::with_capacity(self.state.authorizations.len());
         for url in &self.state.authorizations {
-            authorizations.push(self.account.get(&mut self.nonce, url).await?);
+            authorizations.push(self.account.get(url).await?);
         }
         Ok(authorizations)
     }
@@ -98,10 +107,9 @@ impl Order {
     pub async fn finalize(&mut self, csr_der: &[u8]) -> Result<(), Error> {
         let rsp = self
             .account
-            .post(Some(&FinalizeRequest::new(csr_der)), self.nonce.take(), &self.state.finalize)
+            .post(Some(&FinalizeRequest::new(csr_der)), &self.state.finalize)
             .await?;
 
-        self.nonce = nonce_from_response(&rsp);
         self.state = Problem::check::<OrderState>(rsp).await?;
         Ok(())
     }
@@ -114,9 +122,66 @@ impl Order {
     /// the certificate from the server and return it as a `String`. If the order contains
     /// an error or ends up in any state other than `valid` or `processing`, return an error.
     pub async fn certificate(&mut self) -> Result<Option<String>, Error> {
+        let Some(cert_url) = self.certificate_url().await? else {
+            return Ok(None);
+        };
+
+        let (chain, _) = self.download_chain(&cert_url).await?;
+        Ok(Some(chain.into_pem()))
+    }
+
+    /// Get the default certificate chain followed by the alternate chains offered by the server
+    ///
+    /// Alternate chains are advertised with `Link: rel="alternate"` headers on the certificate
+    /// resource. This polls the order like [`Order::certificate()`] and returns `Ok(None)` if
+    /// the order is still processing.
+    ///
+    /// <https://datatracker.ietf.org/doc/html/rfc8555#section-7.4.2>
+    pub async fn certificate_chains(&mut self) -> Result<Option<Vec<CertificateChain>>, Error> {
+        let Some(cert_url) = self.certificate_url().await? else {
+            return Ok(None);
+        };
+
+        let (default_chain, alternate_urls) = self.download_chain(&cert_url).await?;
+        let mut chains = Vec::with_capacity(1 + alternate_urls.len());
+        chains.push(default_chain);
+        for url in alternate_urls {
+            chains.push(self.download_chain(&url).await?.0);
+        }
+        Ok(Some(chains))
+    }
+
+    /// Get the certificate chain whose topmost certificate was issued by `issuer_common_name`
+    ///
+    /// Like certbot's `--preferred-chain`, this falls back to the default chain if no chain
+    /// matches. Alternate chains are only downloaded if the default chain doesn't match.
+    pub async fn certificate_with_preferred_chain(
+        &mut self,
+        issuer_common_name: &str,
+    ) -> Result<Option<String>, Error> {
+        let Some(cert_url) = self.certificate_url().await? else {
+            return Ok(None);
+        };
+
+        let (default_chain, alternate_urls) = self.download_chain(&cert_url).await?;
+        if default_chain.root_issuer().as_deref() == Some(issuer_common_name) {
+            return Ok(Some(default_chain.into_pem()));
+        }
+
+        for url in alternate_urls {
+            let (chain, _) = self.download_chain(&url).await?;
+            if chain.root_issuer().as_deref() == Some(issuer_common_name) {
+                return Ok(Some(chain.into_pem()));
+            }
+        }
+
+        Ok(Some(default_chain.into_pem()))
+    }
+
+    /// Poll the order until it is no longer processing and get its certificate URL
+    async fn certificate_url(&mut self) -> Result<Option<String>, Error> {
         if matches!(self.state.status, OrderStatus::Processing) {
-            let rsp = self.account.post(None::<&Empty>, self.nonce.take(), &self.url).await?;
-            self.nonce = nonce_from_response(&rsp);
+            let rsp = self.account.post(None::<&Empty>, &self.url).await?;
             self.state = Problem::check::<OrderState>(rsp).await?;
         }
 
@@ -128,44 +193,39 @@ impl Order {
             return Err(Error::Str("invalid order state"));
         }
 
-        let cert_url = match &self.state.certificate {
-            Some(cert_url) => cert_url,
-            None => return Err(Error::Str("no certificate URL found")),
-        };
+        match &self.state.certificate {
+            Some(cert_url) => Ok(Some(cert_url.clone())),
+            None => Err(Error::Str("no certificate URL found")),
+        }
+    }
 
-        let rsp = self.account.post(None::<&Empty>, self.nonce.take(), cert_url).await?;
+    /// Download a certificate chain, returning it with the URLs of the alternate chains
+    async fn download_chain(&self, url: &str) -> Result<(CertificateChain, Vec<String>), Error> {
+        let rsp = self.account.post(None::<&Empty>, url).await?;
+        let alternate_urls = alternate_links(&rsp);
 
-        // let body = rsp.bytes().await?;
         let body = Problem::from_response(rsp).await?;
-        Ok(Some(
-            String::from_utf8(body.to_vec()).map_err(|_| "unable to decode certificate as UTF-8")?,
-        ))
+        let pem = String::from_utf8(body).map_err(|_| "unable to decode certificate as UTF-8")?;
+        Ok((CertificateChain::new(pem), alternate_urls))
     }
 
     /// Notify the server that the given challenge is ready to be completed
     ///
     /// `challenge_url` should be the `Challenge::url` field.
     pub async fn set_challenge_ready(&mut self, challenge_url: &str) -> Result<(), Error> {
-        let rsp = self
-            .account
-            .post(Some(&Empty {}), self.nonce.take(), challenge_url)
-            .await?;
-
-        self.nonce = nonce_from_response(&rsp);
+        let rsp = self.account.post(Some(&Empty {}), challenge_url).await?;
         let _ = Problem::check::<Challenge>(rsp).await?;
         Ok(())
     }
 
     /// Get the current state of the given challenge
     pub async fn challenge(&mut self, challenge_url: &str) -> Result<Challenge, Error> {
-        self.account.get(&mut self.nonce, challenge_url).await
+        self.account.get(challenge_url).await
     }
 
     /// Refresh the current state of the order
     pub async fn refresh(&mut self) -> Result<&OrderState, Error> {
-        let rsp = self.account.post(None::<&Empty>, self.nonce.take(), &self.url).await?;
-
-        self.nonce = nonce_from_response(&rsp);
+        let rsp = self.account.post(None::<&Empty>, &self.url).await?;
         self.state = Problem::check::<OrderState>(rsp).await?;
         Ok(&self.state)
     }
@@ -284,9 +344,7 @@ impl Account {
                 .transpose()?,
         };
 
-        let rsp = client
-            .post(Some(&payload), None, &key, &client.urls.new_account)
-            .await?;
+        let rsp = client.post(Some(&payload), &key, &client.urls.new_account).await?;
 
         let account_url = rsp
             .headers()
@@ -324,12 +382,8 @@ impl Account {
     ///
     /// Returns an [`Order`] instance. Use the [`Order::state()`] method to inspect its state.
     pub async fn new_order<'a>(&'a self, order: &NewOrder<'_>) -> Result<Order, Error> {
-        let rsp = self
-            .inner
-            .post(Some(order), None, &self.inner.client.urls.new_order)
-            .await?;
+        let rsp = self.inner.post(Some(order), &self.inner.client.urls.new_order).await?;
 
-        let nonce = nonce_from_response(&rsp);
         let order_url = rsp
             .headers()
             .get(LOCATION)
@@ -338,7 +392,6 @@ impl Account {
 
         Ok(Order {
             account: self.inner.clone(),
-            nonce,
             // Order of fields matters! We return errors from Problem::check
             // before emitting an error if there is no order url. Or the
             // simple no url error hides the causing error in `Problem::check`.
@@ -378,25 +431,19 @@ impl AccountInner {
             key: Key::from_pkcs8_der(credentials.key_pkcs8.as_ref())?,
             client: match (credentials.directory, credentials.urls) {
                 (Some(server_url), _) => Client::new(&server_url, http).await?,
-                (None, Some(urls)) => Client { http, urls },
+                (None, Some(urls)) => Client::with_urls(http, urls),
                 (None, None) => return Err("no server URLs found".into()),
             },
         })
     }
 
-    async fn get<T: DeserializeOwned>(&self, nonce: &mut Option<String>, url: &str) -> Result<T, Error> {
-        let rsp = self.post(None::<&Empty>, nonce.take(), url).await?;
-        *nonce = nonce_from_response(&rsp);
+    async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, Error> {
+        let rsp = self.post(None::<&Empty>, url).await?;
         Problem::check(rsp).await
     }
 
-    async fn post(
-        &self,
-        payload: Option<&impl Serialize>,
-        nonce: Option<String>,
-        url: &str,
-    ) -> Result<Response, Error> {
-        self.client.post(payload, nonce, self, url).await
+    async fn post(&self, payload: Option<&impl Serialize>, url: &str) -> Result<Response, Error> {
+        self.client.post(payload, self, url).await
     }
 }
 
@@ -421,48 +468,63 @@ impl Signer for AccountInner {
 struct Client {
     http: reqwest::Client,
     urls: DirectoryUrls,
+    nonces: NoncePool,
 }
 
 impl Client {
     async fn new(server_url: &str, http: reqwest::Client) -> Result<Self, Error> {
-        // let req = Request::new(Method::GET, )
-        //     .body(Body::empty())
-        //     .unwrap();
         let res = http.get(server_url).send().await?;
-
-        // .request(req).await?;
         let urls = res.json().await?;
-        Ok(Client { http, urls })
-    }
-
-    async fn post(
-        &self,
-        payload: Option<&impl Serialize>,
-        nonce: Option<String>,
-        signer: &impl Signer,
-        url: &str,
-    ) -> Result<Response, Error> {
-        let nonce = self.nonce(nonce).await?;
-        let body = JoseJson::new(payload, signer.header(Some(&nonce), url), signer)?;
-
-        let request = self
-            .http
-            .request(Method::POST, url)
-            .header(CONTENT_TYPE, JOSE_JSON)
-            .json(&body)
-            .build()?;
-        // let request = RequestBuilder::()
-        //     .method(Method::POST)
-        //     .uri(url)
-        //     .header(CONTENT_TYPE, JOSE_JSON)
-        //     .body(Body::from(serde_json::to_vec(&body)?))
-        //     .unwrap();
-
-        Ok(self.http.execute(request).await?)
-    }
-
-    async fn nonce(&self, nonce: Option<String>) -> Result<String, Error> {
-        if let Some(nonce) = nonce {
+        Ok(Client::with_urls(http, urls))
+    }
+
+    fn with_urls(http: reqwest::Client, urls: DirectoryUrls) -> Self {
+        Client {
+            http,
+            urls,
+            nonces: NoncePool::default(),
+        }
+    }
+
+    /// Sign and send a request, retrying with a fresh nonce when the server rejects ours
+    ///
+    /// Error responses are turned into an [`Error::Api`], so the returned response is
+    /// always successful.
+    ///
+    /// <https://datatracker.ietf.org/doc/html/rfc8555#section-6.5>
+    async fn post(&self, payload: Option<&impl Serialize>, signer: &impl Signer, url: &str) -> Result<Response, Error> {
+        let mut attempt = 0;
+        loop {
+            let nonce = self.nonce().await?;
+            let body = JoseJson::new(payload, signer.header(Some(&nonce), url), signer)?;
+
+            let request = self
+                .http
+                .request(Method::POST, url)
+                .header(CONTENT_TYPE, JOSE_JSON)
+                .json(&body)
+                .build()?;
+
+            let rsp = self.http.execute(request).await?;
+            if let Some(nonce) = nonce_from_response(&rsp) {
+                self.nonces.push(nonce);
+            }
+
+            let status = rsp.status();
+            if status.is_success() || status.is_redirection() {
+                return Ok(rsp);
+            }
+
+            let problem = Problem::from_error_body(status.as_u16(), &rsp.bytes().await?);
+            attempt += 1;
+            if !problem.is(&ProblemType::BadNonce) || attempt > MAX_BAD_NONCE_RETRIES {
+                return Err(problem.into());
+            }
+        }
+    }
+
+    async fn nonce(&self) -> Result<String, Error> {
+        if let Some(nonce) = self.nonces.pop() {
             return Ok(nonce);
         }
 
@@ -482,6 +544,28 @@ impl Client {
     }
 }
 
+/// Nonces handed out by the server in `Replay-Nonce` headers, waiting to be used
+///
+/// Every response carries a fresh nonce, so keeping a few of them around saves a round-trip
+/// to the newNonce resource for most requests, including concurrent ones.
+#[derive(Default)]
+struct NoncePool(Mutex<VecDeque<String>>);
+
+impl NoncePool {
+    fn push(&self, nonce: String) {
+        let mut nonces = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        // the oldest nonces are the most likely to have expired on the server
+        if nonces.len() >= NONCE_POOL_CAPACITY {
+            nonces.pop_front();
+        }
+        nonces.push_back(nonce);
+    }
+
+    fn pop(&self) -> Option<String> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner()).pop_back()
+    }
+}
+
 impl fmt::Debug for Client {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Client")
@@ -639,6 +723,55 @@ fn nonce_from_response(res: &Response) -> Option<String> {
         .map(|header| header.to_str().unwrap_or_default().to_string())
 }
 
+/// Get the absolute URLs of the `Link: <url>;rel="alternate"` headers of a response
+///
+/// <https://datatracker.ietf.org/doc/html/rfc8288#section-3>
+fn alternate_links(res: &Response) -> Vec<String> {
+    res.headers()
+        .get_all(LINK)
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|header| links_with_rel(header, "alternate"))
+        .filter_map(|link| res.url().join(link).ok())
+        .map(String::from)
+        .collect()
+}
+
+fn links_with_rel<'a>(header: &'a str, rel: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    split_links(header).filter_map(move |link| {
+        let (target, params) = link.trim().strip_prefix('<')?.split_once('>')?;
+        params
+            .split(';')
+            .filter_map(|param| param.split_once('='))
+            .any(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("rel")
+                    && value
+                        .trim()
+                        .trim_matches('"')
+                        .split_ascii_whitespace()
+                        .any(|value| value.eq_ignore_ascii_case(rel))
+            })
+            .then_some(target)
+    })
+}
+
+/// Split a `Link` header into its links, ignoring commas in targets and quoted parameters
+///
+/// <https://datatracker.ietf.org/doc/html/rfc8288#section-3>
+fn split_links(header: &str) -> impl Iterator<Item = &str> {
+    let (mut in_target, mut in_quotes) = (false, false);
+    header.split(move |c| {
+        match c {
+            '<' if !in_quotes => in_target = true,
+            '>' if !in_quotes => in_target = false,
+            '"' if !in_target => in_quotes = !in_quotes,
+            ',' => return !in_target && !in_quotes,
+            _ => {}
+        }
+        false
+    })
+}
+
 #[cfg(feature = "hyper-rustls")]
 struct DefaultClient(hyper::Client<hyper_rustls::HttpsConnector<HttpConnector>>);
 
@@ -688,6 +821,8 @@ impl Default for DefaultClient {
 
 const JOSE_JSON: &str = "application/jose+json";
 const REPLAY_NONCE: &str = "Replay-Nonce";
+const NONCE_POOL_CAPACITY: usize = 8;
+const MAX_BAD_NONCE_RETRIES: usize = 3;
 
 #[cfg(test)]
 mod tests {
@@ -706,4 +841,70 @@ mod tests {
         Account::from_credentials(serde_json::from_str::<AccountCredentials>(CREDENTIALS)?).await?;
         Ok(())
     }
+
+    #[test]
+    fn deserialize_problem() -> Result<(), Error> {
+        const PROBLEM: &str = r#"{
+            "type": "urn:ietf:params:acme:error:rejectedIdentifier",
+            "detail": "Some identifiers were rejected",
+            "status": 400,
+            "subproblems": [
+                {
+                    "type": "urn:ietf:params:acme:error:caa",
+                    "detail": "CAA record forbids issuance",
+                    "identifier": { "type": "dns", "value": "example.com" }
+                },
+                {
+                    "type": "urn:example:acme:error:custom",
+                    "detail": "Custom error"
+                }
+            ]
+        }"#;
+        let problem = serde_json::from_str::<Problem>(PROBLEM)?;
+        assert!(problem.is(&ProblemType::RejectedIdentifier));
+        assert_eq!(problem.status, Some(400));
+        assert_eq!(problem.subproblems.len(), 2);
+        assert_eq!(problem.subproblems[0].r#type, Some(ProblemType::Caa));
+        assert!(matches!(&problem.subproblems[0].identifier, Some(Identifier::Dns(name)) if name == "example.com"));
+        assert_eq!(
+            problem.subproblems[1].r#type,
+            Some(ProblemType::Other("urn:example:acme:error:custom".to_owned()))
+        );
+        assert_eq!(ProblemType::BadNonce.to_string(), "urn:ietf:params:acme:error:badNonce");
+        Ok(())
+    }
+
+    #[test]
+    fn problem_from_invalid_body() {
+        let problem = Problem::from_error_body(503, b"<html>Service Unavailable</html>");
+        assert_eq!(problem.status, Some(503));
+        assert_eq!(problem.r#type, None);
+    }
+
+    #[test]
+    fn parse_link_header() {
+        let header = r#"<https://example.com/cert/1/1>;rel="alternate", <https://example.com/dir>;rel="index", </cert/1/2>; rel="alternate up""#;
+        assert_eq!(
+            links_with_rel(header, "alternate").collect::<Vec<_>>(),
+            ["https://example.com/cert/1/1", "/cert/1/2"]
+        );
+
+        let header = r#"<https://example.com/cert?id=1,2>; rel="alternate"; title="a, b", <https://example.com/cert/a,b>; rel=alternate"#;
+        assert_eq!(
+            links_with_rel(header, "alternate").collect::<Vec<_>>(),
+            ["https://example.com/cert?id=1,2", "https://example.com/cert/a,b"]
+        );
+    }
+
+    #[test]
+    fn nonce_pool() {
+        let pool = NoncePool::default();
+        assert_eq!(pool.pop(), None);
+        for i in 0..NONCE_POOL_CAPACITY + 2 {
+            pool.push(i.to_string());
+        }
+        assert_eq!(pool.pop().as_deref(), Some("9"));
+        assert_eq!(pool.0.lock().unwrap().len(), NONCE_POOL_CAPACITY - 1);
+        assert_eq!(pool.0.lock().unwrap().front().map(String::as_str), Some("2"));
+    }
 }
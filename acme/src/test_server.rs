@@ -0,0 +1,930 @@
+//! In-process ACME server for offline testing, in the spirit of Pebble.
+//!
+//! The [`TestServer`] listens on a random port on the loopback interface and implements the
+//! subset of RFC 8555 used by this crate: directory, newNonce, newAccount, newOrder,
+//! authorizations, challenges, finalize and certificate download. Request signatures and nonces
+//! are verified like a real CA would, but challenges are always considered valid as soon as the
+//! client announces them ready, and certificates are issued by a throwaway CA generated when the
+//! server starts. Each certificate is offered with an alternate chain in which the CA is
+//! cross-signed by a second root, so preferred-chain selection can be tested too.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), acme::Error> {
+//! let server = acme::test_server::TestServer::start().await?;
+//! let new_account = acme::NewAccount {
+//!     contact: &[],
+//!     terms_of_service_agreed: true,
+//!     only_return_existing: false,
+//! };
+//! let (account, _) =
+//!     acme::Account::create_with_http(&new_account, &server.directory_url(), None, reqwest::Client::new()).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use aws_lc_rs::{
+    rand::{SecureRandom, SystemRandom},
+    signature::{ECDSA_P256_SHA256_FIXED, UnparsedPublicKey},
+};
+use bytes::Bytes;
+use hyper::{
+    Method, Request, Response, StatusCode,
+    body::Incoming,
+    header::{CONTENT_TYPE, HeaderName, LINK, LOCATION},
+    server::conn::http1,
+    service::service_fn,
+};
+use hyper_utils::{
+    http_body_util::{BodyExt, Full},
+    rt::TokioIo,
+};
+use rcgen::{BasicConstraints, CertificateParams, CertificateSigningRequestParams, DnType, IsCa, KeyPair, SanType};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{Error, ProblemType, REPLAY_NONCE};
+
+const PEM_CERTIFICATE_CHAIN: &str = "application/pem-certificate-chain";
+const PROBLEM_JSON: &str = "application/problem+json";
+const CA_COMMON_NAME: &str = "acme test server CA";
+const ALTERNATE_ROOT_COMMON_NAME: &str = "acme test server alternate root";
+/// How long to wait before accepting connections again after an error
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+/// An in-process ACME server issuing certificates from a throwaway CA
+///
+/// The server stops when the `TestServer` is dropped.
+pub struct TestServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    task: JoinHandle<()>,
+}
+
+impl TestServer {
+    /// Start a new server listening on a random port of `127.0.0.1`
+    pub async fn start() -> Result<Self, Error> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(State::new(format!("http://{addr}"))?));
+
+        let server_state = state.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    // e.g. too many open files: wait for connections to be closed instead of
+                    // spinning on the error
+                    Err(_) => {
+                        tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+                        continue;
+                    }
+                };
+
+                let state = server_state.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |req| handle(state.clone(), req));
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            task,
+        })
+    }
+
+    /// Get the URL of the directory resource, to be passed to [`Account::create_with_http()`]
+    ///
+    /// [`Account::create_with_http()`]: crate::Account::create_with_http()
+    pub fn directory_url(&self) -> String {
+        format!("http://{}/directory", self.addr)
+    }
+
+    /// Get the PEM-encoded certificate of the CA issuing the certificates
+    pub fn ca_certificate_pem(&self) -> String {
+        self.state().ca.pem()
+    }
+
+    /// Get the common name of the self-signed root ending the default chains
+    pub fn root_common_name(&self) -> &'static str {
+        CA_COMMON_NAME
+    }
+
+    /// Get the common name of the root which cross-signed the CA in the alternate chains
+    pub fn alternate_root_common_name(&self) -> &'static str {
+        ALTERNATE_ROOT_COMMON_NAME
+    }
+
+    /// Reject the nonces of the next `count` requests with a `badNonce` error, even if valid
+    pub fn reject_nonces(&self, count: usize) {
+        self.state().nonces_to_reject = count;
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct State {
+    base_url: String,
+    rng: SystemRandom,
+    ca: rcgen::Certificate,
+    ca_key: KeyPair,
+    /// The CA certificate, cross-signed by another root
+    cross_signed_ca: rcgen::Certificate,
+    next_id: u64,
+    nonces: HashSet<String>,
+    nonces_to_reject: usize,
+    /// Public keys of the accounts, indexed by account URL
+    accounts: HashMap<String, Vec<u8>>,
+    orders: HashMap<u64, ServerOrder>,
+    authorizations: HashMap<u64, ServerAuthorization>,
+    challenges: HashMap<u64, ServerChallenge>,
+    certificates: HashMap<u64, ServerCertificate>,
+}
+
+struct ServerOrder {
+    account: String,
+    status: &'static str,
+    identifiers: Vec<String>,
+    authorizations: Vec<u64>,
+    certificate: Option<u64>,
+}
+
+struct ServerAuthorization {
+    order: u64,
+    identifier: String,
+    status: &'static str,
+    challenges: Vec<u64>,
+}
+
+struct ServerCertificate {
+    order: u64,
+    chain: String,
+    alternates: Vec<u64>,
+}
+
+struct ServerChallenge {
+    authorization: u64,
+    r#type: &'static str,
+    token: String,
+    status: &'static str,
+}
+
+impl State {
+    fn new(base_url: String) -> Result<Self, Error> {
+        let ca_key = KeyPair::generate().map_err(|_| Error::Str("failed to generate test CA key"))?;
+        let ca = ca_params(CA_COMMON_NAME)
+            .self_signed(&ca_key)
+            .map_err(|_| Error::Str("failed to generate test CA certificate"))?;
+
+        let alternate_root_key = KeyPair::generate().map_err(|_| Error::Str("failed to generate test CA key"))?;
+        let alternate_root = ca_params(ALTERNATE_ROOT_COMMON_NAME)
+            .self_signed(&alternate_root_key)
+            .map_err(|_| Error::Str("failed to generate test CA certificate"))?;
+        let cross_signed_ca = ca_params(CA_COMMON_NAME)
+            .signed_by(&ca_key, &alternate_root, &alternate_root_key)
+            .map_err(|_| Error::Str("failed to generate test CA certificate"))?;
+
+        Ok(Self {
+            base_url,
+            rng: SystemRandom::new(),
+            ca,
+            ca_key,
+            cross_signed_ca,
+            next_id: 1,
+            nonces: HashSet::new(),
+            nonces_to_reject: 0,
+            accounts: HashMap::new(),
+            orders: HashMap::new(),
+            authorizations: HashMap::new(),
+            challenges: HashMap::new(),
+            certificates: HashMap::new(),
+        })
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn random_token(&self) -> String {
+        let mut token = [0u8; 16];
+        // the system RNG never fails on supported platforms
+        let _ = self.rng.fill(&mut token);
+        base64::encode_with_alphabet(&token, base64::Alphabet::UrlNoPadding)
+    }
+
+    fn new_nonce(&mut self) -> String {
+        let nonce = self.random_token();
+        self.nonces.insert(nonce.clone());
+        nonce
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    fn directory(&self) -> Reply {
+        Reply::json(
+            StatusCode::OK,
+            json!({
+                "newNonce": self.url("/new-nonce"),
+                "newAccount": self.url("/new-account"),
+                "newOrder": self.url("/new-order"),
+                "revokeCert": self.url("/revoke-cert"),
+            }),
+        )
+    }
+
+    fn post(&mut self, path: &str, body: &[u8]) -> Result<Reply, Rejection> {
+        let jws = self.verify(path, body)?;
+
+        if path == "/new-account" {
+            return self.new_account(jws);
+        } else if path == "/new-order" {
+            return self.new_order(jws);
+        }
+
+        let Some((resource, id)) = path[1..]
+            .split_once('/')
+            .and_then(|(resource, id)| Some((resource, id.parse::<u64>().ok()?)))
+        else {
+            return Err(Rejection::not_found());
+        };
+
+        match resource {
+            "account" => self.account(jws, path),
+            "order" => self.order(jws, id),
+            "authz" => self.authorization(jws, id),
+            "challenge" => self.challenge(jws, id),
+            "finalize" => self.finalize(jws, id),
+            "cert" => self.certificate(jws, id),
+            _ => Err(Rejection::not_found()),
+        }
+    }
+
+    /// Verify the JWS signature, nonce and URL of a request
+    ///
+    /// <https://datatracker.ietf.org/doc/html/rfc8555#section-6.2>
+    fn verify(&mut self, path: &str, body: &[u8]) -> Result<Jws, Rejection> {
+        let jose = serde_json::from_slice::<JoseBody>(body).map_err(|_| Rejection::malformed("invalid JWS"))?;
+        let protected = decode_base64(&jose.protected)?;
+        let header = serde_json::from_slice::<ProtectedHeader>(&protected)
+            .map_err(|_| Rejection::malformed("invalid JWS protected header"))?;
+
+        if header.alg != "ES256" {
+            return Err(Rejection::new(
+                StatusCode::BAD_REQUEST,
+                ProblemType::BadSignatureAlgorithm,
+                "only ES256 is supported",
+            ));
+        }
+
+        let nonce_is_valid = header.nonce.is_some_and(|nonce| self.nonces.remove(&nonce));
+        if !nonce_is_valid || self.nonces_to_reject > 0 {
+            self.nonces_to_reject = self.nonces_to_reject.saturating_sub(1);
+            return Err(Rejection::new(StatusCode::BAD_REQUEST, ProblemType::BadNonce, "invalid nonce"));
+        }
+
+        if header.url != self.url(path) {
+            return Err(Rejection::new(
+                StatusCode::UNAUTHORIZED,
+                ProblemType::Unauthorized,
+                "JWS url does not match the request URL",
+            ));
+        }
+
+        let (account, public_key) = match (header.jwk, header.kid) {
+            (Some(jwk), None) => {
+                if jwk.kty != "EC" || jwk.crv != "P-256" {
+                    return Err(Rejection::new(
+                        StatusCode::BAD_REQUEST,
+                        ProblemType::BadPublicKey,
+                        "only P-256 keys are supported",
+                    ));
+                }
+                let mut public_key = vec![0x04];
+                public_key.extend(decode_base64(&jwk.x)?);
+                public_key.extend(decode_base64(&jwk.y)?);
+                (None, public_key)
+            }
+            (None, Some(kid)) => match self.accounts.get(&kid) {
+                Some(public_key) => (Some(kid), public_key.clone()),
+                None => {
+                    return Err(Rejection::new(
+                        StatusCode::BAD_REQUEST,
+                        ProblemType::AccountDoesNotExist,
+                        "unknown account",
+                    ));
+                }
+            },
+            _ => return Err(Rejection::malformed("JWS must contain exactly one of jwk and kid")),
+        };
+
+        let signature = decode_base64(&jose.signature)?;
+        let signing_input = format!("{}.{}", jose.protected, jose.payload);
+        UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, &public_key)
+            .verify(signing_input.as_bytes(), &signature)
+            .map_err(|_| Rejection::malformed("invalid JWS signature"))?;
+
+        Ok(Jws {
+            account,
+            public_key,
+            payload: decode_base64(&jose.payload)?,
+        })
+    }
+
+    fn new_account(&mut self, jws: Jws) -> Result<Reply, Rejection> {
+        if jws.account.is_some() {
+            return Err(Rejection::malformed("newAccount requests must be signed with a jwk"));
+        }
+        let payload = jws.payload::<NewAccountRequest>()?;
+
+        let existing = self
+            .accounts
+            .iter()
+            .find(|(_, public_key)| **public_key == jws.public_key)
+            .map(|(url, _)| url.clone());
+        let (status, url) = match existing {
+            Some(url) => (StatusCode::OK, url),
+            None if payload.only_return_existing => {
+                return Err(Rejection::new(
+                    StatusCode::BAD_REQUEST,
+                    ProblemType::AccountDoesNotExist,
+                    "no account exists with the provided key",
+                ));
+            }
+            None => {
+                let id = self.next_id();
+                let url = self.url(&format!("/account/{id}"));
+                self.accounts.insert(url.clone(), jws.public_key);
+                (StatusCode::CREATED, url)
+            }
+        };
+
+        Ok(Reply::json(status, json!({ "status": "valid", "contact": payload.contact })).location(url))
+    }
+
+    fn account(&mut self, jws: Jws, path: &str) -> Result<Reply, Rejection> {
+        if jws.account.as_deref() != Some(self.url(path).as_str()) {
+            return Err(Rejection::unauthorized());
+        }
+        Ok(Reply::json(StatusCode::OK, json!({ "status": "valid" })))
+    }
+
+    fn new_order(&mut self, jws: Jws) -> Result<Reply, Rejection> {
+        let account = jws.account.clone().ok_or_else(Rejection::unauthorized)?;
+        let payload = jws.payload::<NewOrderRequest>()?;
+        if payload.identifiers.is_empty() {
+            return Err(Rejection::malformed("no identifiers in order"));
+        }
+
+        let order_id = self.next_id();
+        let mut identifiers = Vec::with_capacity(payload.identifiers.len());
+        let mut authorizations = Vec::with_capacity(payload.identifiers.len());
+        for identifier in payload.identifiers {
+            if identifier.r#type != "dns" {
+                return Err(Rejection::new(
+                    StatusCode::BAD_REQUEST,
+                    ProblemType::UnsupportedIdentifier,
+                    "only dns identifiers are supported",
+                ));
+            }
+
+            let authorization_id = self.next_id();
+            let challenges = ["http-01", "dns-01", "tls-alpn-01"]
+                .into_iter()
+                .map(|r#type| {
+                    let challenge_id = self.next_id();
+                    let token = self.random_token();
+                    self.challenges.insert(
+                        challenge_id,
+                        ServerChallenge {
+                            authorization: authorization_id,
+                            r#type,
+                            token,
+                            status: "pending",
+                        },
+                    );
+                    challenge_id
+                })
+                .collect();
+
+            self.authorizations.insert(
+                authorization_id,
+                ServerAuthorization {
+                    order: order_id,
+                    identifier: identifier.value.clone(),
+                    status: "pending",
+                    challenges,
+                },
+            );
+            identifiers.push(identifier.value);
+            authorizations.push(authorization_id);
+        }
+
+        self.orders.insert(
+            order_id,
+            ServerOrder {
+                account,
+                status: "pending",
+                identifiers,
+                authorizations,
+                certificate: None,
+            },
+        );
+
+        Ok(Reply::json(StatusCode::CREATED, self.order_json(order_id))
+            .location(self.url(&format!("/order/{order_id}"))))
+    }
+
+    fn order(&mut self, jws: Jws, id: u64) -> Result<Reply, Rejection> {
+        self.owned_order(&jws, id)?;
+        Ok(Reply::json(StatusCode::OK, self.order_json(id)))
+    }
+
+    fn authorization(&mut self, jws: Jws, id: u64) -> Result<Reply, Rejection> {
+        let order = self.authorizations.get(&id).ok_or_else(Rejection::not_found)?.order;
+        self.owned_order(&jws, order)?;
+        Ok(Reply::json(StatusCode::OK, self.authorization_json(id)))
+    }
+
+    fn challenge(&mut self, jws: Jws, id: u64) -> Result<Reply, Rejection> {
+        let authorization = self.challenges.get(&id).ok_or_else(Rejection::not_found)?.authorization;
+        let order = self.authorizations[&authorization].order;
+        self.owned_order(&jws, order)?;
+
+        // an empty payload is a POST-as-GET request, anything else tells us the challenge is ready
+        let challenge = self.challenges.get_mut(&id).unwrap();
+        if !jws.payload.is_empty() && challenge.status == "pending" {
+            challenge.status = "valid";
+            self.authorizations.get_mut(&authorization).unwrap().status = "valid";
+
+            let order = self.orders.get_mut(&order).unwrap();
+            if order
+                .authorizations
+                .iter()
+                .all(|id| self.authorizations[id].status == "valid")
+            {
+                order.status = "ready";
+            }
+        }
+
+        Ok(Reply::json(StatusCode::OK, self.challenge_json(id)))
+    }
+
+    fn finalize(&mut self, jws: Jws, id: u64) -> Result<Reply, Rejection> {
+        let order = self.owned_order(&jws, id)?;
+        if order.status != "ready" {
+            return Err(Rejection::new(
+                StatusCode::FORBIDDEN,
+                ProblemType::OrderNotReady,
+                format!("order is {}", order.status),
+            ));
+        }
+        let identifiers = order.identifiers.iter().cloned().collect::<BTreeSet<_>>();
+
+        let payload = jws.payload::<FinalizeRequest>()?;
+        let csr_der = decode_base64(&payload.csr)?;
+        let csr = CertificateSigningRequestParams::from_der(&csr_der.into())
+            .map_err(|_| Rejection::new(StatusCode::BAD_REQUEST, ProblemType::BadCsr, "invalid CSR"))?;
+
+        let names = csr
+            .params
+            .subject_alt_names
+            .iter()
+            .map(|san| match san {
+                SanType::DnsName(name) => Ok(name.as_str().to_owned()),
+                _ => Err(Rejection::new(
+                    StatusCode::BAD_REQUEST,
+                    ProblemType::BadCsr,
+                    "CSR contains non-DNS subject alternative names",
+                )),
+            })
+            .collect::<Result<BTreeSet<_>, _>>()?;
+        if names != identifiers {
+            return Err(Rejection::new(
+                StatusCode::BAD_REQUEST,
+                ProblemType::BadCsr,
+                "CSR names do not match the order identifiers",
+            ));
+        }
+
+        let certificate = csr.signed_by(&self.ca, &self.ca_key).map_err(|_| {
+            Rejection::new(StatusCode::INTERNAL_SERVER_ERROR, ProblemType::ServerInternal, "signing failed")
+        })?;
+        let alternate_id = self.next_id();
+        self.certificates.insert(
+            alternate_id,
+            ServerCertificate {
+                order: id,
+                chain: format!("{}{}", certificate.pem(), self.cross_signed_ca.pem()),
+                alternates: Vec::new(),
+            },
+        );
+        let certificate_id = self.next_id();
+        self.certificates.insert(
+            certificate_id,
+            ServerCertificate {
+                order: id,
+                chain: format!("{}{}", certificate.pem(), self.ca.pem()),
+                alternates: vec![alternate_id],
+            },
+        );
+
+        let order = self.orders.get_mut(&id).unwrap();
+        order.status = "valid";
+        order.certificate = Some(certificate_id);
+        Ok(Reply::json(StatusCode::OK, self.order_json(id)))
+    }
+
+    fn certificate(&mut self, jws: Jws, id: u64) -> Result<Reply, Rejection> {
+        let certificate = self.certificates.get(&id).ok_or_else(Rejection::not_found)?;
+        self.owned_order(&jws, certificate.order)?;
+
+        let mut reply = Reply {
+            status: StatusCode::OK,
+            content_type: PEM_CERTIFICATE_CHAIN,
+            headers: Vec::new(),
+            body: certificate.chain.clone().into_bytes(),
+        };
+        for alternate in &certificate.alternates {
+            let link = format!("<{}>;rel=\"alternate\"", self.url(&format!("/cert/{alternate}")));
+            reply.headers.push((LINK, link));
+        }
+        Ok(reply)
+    }
+
+    fn owned_order(&self, jws: &Jws, id: u64) -> Result<&ServerOrder, Rejection> {
+        let order = self.orders.get(&id).ok_or_else(Rejection::not_found)?;
+        if jws.account.as_deref() != Some(order.account.as_str()) {
+            return Err(Rejection::unauthorized());
+        }
+        Ok(order)
+    }
+
+    fn order_json(&self, id: u64) -> Value {
+        let order = &self.orders[&id];
+        let mut json = json!({
+            "status": order.status,
+            "identifiers": order
+                .identifiers
+                .iter()
+                .map(|value| json!({ "type": "dns", "value": value }))
+                .collect::<Vec<_>>(),
+            "authorizations": order
+                .authorizations
+                .iter()
+                .map(|id| self.url(&format!("/authz/{id}")))
+                .collect::<Vec<_>>(),
+            "finalize": self.url(&format!("/finalize/{id}")),
+        });
+        if let Some(certificate) = order.certificate {
+            json["certificate"] = self.url(&format!("/cert/{certificate}")).into();
+        }
+        json
+    }
+
+    fn authorization_json(&self, id: u64) -> Value {
+        let authorization = &self.authorizations[&id];
+        json!({
+            "identifier": { "type": "dns", "value": authorization.identifier },
+            "status": authorization.status,
+            "challenges": authorization
+                .challenges
+                .iter()
+                .map(|id| self.challenge_json(*id))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    fn challenge_json(&self, id: u64) -> Value {
+        let challenge = &self.challenges[&id];
+        json!({
+            "type": challenge.r#type,
+            "url": self.url(&format!("/challenge/{id}")),
+            "token": challenge.token,
+            "status": challenge.status,
+        })
+    }
+}
+
+async fn handle(state: Arc<Mutex<State>>, req: Request<Incoming>) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    let method = req.method().clone();
+    let path = req.uri().path().to_owned();
+    let body = req.into_body().collect().await?.to_bytes();
+
+    let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+    let reply = match (method, path.as_str()) {
+        (Method::GET, "/directory") => state.directory(),
+        // https://datatracker.ietf.org/doc/html/rfc8555#section-7.2
+        (Method::HEAD, "/new-nonce") => Reply::empty(StatusCode::OK),
+        (Method::GET, "/new-nonce") => Reply::empty(StatusCode::NO_CONTENT),
+        (Method::POST, "/revoke-cert") => {
+            Rejection::malformed("revocation is not supported by the test server").into_reply()
+        }
+        (Method::POST, path) => state.post(path, &body).unwrap_or_else(Rejection::into_reply),
+        _ => Rejection::not_found().into_reply(),
+    };
+
+    let mut response = Response::builder()
+        .status(reply.status)
+        .header(CONTENT_TYPE, reply.content_type)
+        .header(REPLAY_NONCE, state.new_nonce());
+    for (name, value) in reply.headers {
+        response = response.header(name, value);
+    }
+
+    // the response is built only from valid header values
+    Ok(response.body(Full::new(Bytes::from(reply.body))).unwrap())
+}
+
+struct Reply {
+    status: StatusCode,
+    content_type: &'static str,
+    headers: Vec<(HeaderName, String)>,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json(status: StatusCode, body: Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    fn empty(status: StatusCode) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn location(mut self, url: String) -> Self {
+        self.headers.push((LOCATION, url));
+        self
+    }
+}
+
+/// An error to be returned as a problem document
+struct Rejection {
+    status: StatusCode,
+    r#type: ProblemType,
+    detail: String,
+}
+
+impl Rejection {
+    fn new(status: StatusCode, r#type: ProblemType, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            r#type,
+            detail: detail.into(),
+        }
+    }
+
+    fn malformed(detail: &str) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, ProblemType::Malformed, detail)
+    }
+
+    fn unauthorized() -> Self {
+        Self::new(
+            StatusCode::UNAUTHORIZED,
+            ProblemType::Unauthorized,
+            "resource belongs to another account",
+        )
+    }
+
+    fn not_found() -> Self {
+        Self::new(StatusCode::NOT_FOUND, ProblemType::Malformed, "resource not found")
+    }
+
+    fn into_reply(self) -> Reply {
+        Reply {
+            status: self.status,
+            content_type: PROBLEM_JSON,
+            headers: Vec::new(),
+            body: json!({
+                "type": self.r#type.as_str(),
+                "detail": self.detail,
+                "status": self.status.as_u16(),
+            })
+            .to_string()
+            .into_bytes(),
+        }
+    }
+}
+
+fn ca_params(common_name: &str) -> CertificateParams {
+    let mut params = CertificateParams::default();
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.distinguished_name.push(DnType::CommonName, common_name);
+    params
+}
+
+fn decode_base64(input: &str) -> Result<Vec<u8>, Rejection> {
+    base64::decode_with_alphabet(input.as_bytes(), base64::Alphabet::UrlNoPadding)
+        .map_err(|_| Rejection::malformed("invalid base64url encoding"))
+}
+
+struct Jws {
+    /// URL of the account that signed the request, `None` if it was signed with a jwk
+    account: Option<String>,
+    public_key: Vec<u8>,
+    payload: Vec<u8>,
+}
+
+impl Jws {
+    fn payload<'de, T: Deserialize<'de>>(&'de self) -> Result<T, Rejection> {
+        serde_json::from_slice(&self.payload).map_err(|_| Rejection::malformed("invalid request payload"))
+    }
+}
+
+#[derive(Deserialize)]
+struct JoseBody {
+    protected: String,
+    payload: String,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct ProtectedHeader {
+    alg: String,
+    nonce: Option<String>,
+    url: String,
+    jwk: Option<ProtectedJwk>,
+    kid: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProtectedJwk {
+    kty: String,
+    crv: String,
+    x: String,
+    y: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NewAccountRequest {
+    #[serde(default)]
+    contact: Vec<String>,
+    #[serde(default)]
+    only_return_existing: bool,
+}
+
+#[derive(Deserialize)]
+struct NewOrderRequest {
+    identifiers: Vec<IdentifierRequest>,
+}
+
+#[derive(Deserialize)]
+struct IdentifierRequest {
+    r#type: String,
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct FinalizeRequest {
+    csr: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, ChallengeType, Identifier, NewAccount, NewOrder, Order, OrderStatus};
+
+    const NEW_ACCOUNT: NewAccount<'static> = NewAccount {
+        contact: &[],
+        terms_of_service_agreed: true,
+        only_return_existing: false,
+    };
+
+    async fn new_order(server: &TestServer, names: &[&str]) -> Result<Order, Error> {
+        let (account, _) =
+            Account::create_with_http(&NEW_ACCOUNT, &server.directory_url(), None, reqwest::Client::new()).await?;
+        let identifiers = names
+            .iter()
+            .map(|name| Identifier::Dns(name.to_string()))
+            .collect::<Vec<_>>();
+        let mut order = account
+            .new_order(&NewOrder {
+                identifiers: &identifiers,
+            })
+            .await?;
+
+        for authorization in order.authorizations().await? {
+            let challenge = authorization
+                .challenges
+                .iter()
+                .find(|challenge| challenge.r#type == ChallengeType::Http01)
+                .ok_or("no http-01 challenge")?;
+            order.set_challenge_ready(&challenge.url).await?;
+        }
+
+        Ok(order)
+    }
+
+    fn csr(names: &[&str]) -> Vec<u8> {
+        let key = KeyPair::generate().unwrap();
+        let names = names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let csr = CertificateParams::new(names).unwrap().serialize_request(&key).unwrap();
+        csr.der().to_vec()
+    }
+
+    #[tokio::test]
+    async fn issue_certificate() -> Result<(), Error> {
+        let server = TestServer::start().await?;
+        let names = ["example.com", "www.example.com"];
+        let mut order = new_order(&server, &names).await?;
+        assert_eq!(order.refresh().await?.status, OrderStatus::Ready);
+
+        order.finalize(&csr(&names)).await?;
+        assert_eq!(order.state().status, OrderStatus::Valid);
+
+        let chain = order.certificate().await?.ok_or("no certificate")?;
+        assert_eq!(chain.matches("BEGIN CERTIFICATE").count(), 2);
+        assert!(chain.ends_with(&server.ca_certificate_pem()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn alternate_chains() -> Result<(), Error> {
+        let server = TestServer::start().await?;
+        let names = ["example.com"];
+        let mut order = new_order(&server, &names).await?;
+        order.finalize(&csr(&names)).await?;
+
+        let chains = order.certificate_chains().await?.ok_or("no certificate")?;
+        assert_eq!(chains.len(), 2);
+        assert_eq!(chains[0].root_issuer().as_deref(), Some(server.root_common_name()));
+        assert_eq!(chains[1].root_issuer().as_deref(), Some(server.alternate_root_common_name()));
+        assert_eq!(
+            chains[1].issuers(),
+            [server.root_common_name(), server.alternate_root_common_name()]
+        );
+
+        let preferred = order
+            .certificate_with_preferred_chain(server.alternate_root_common_name())
+            .await?
+            .ok_or("no certificate")?;
+        assert_eq!(preferred, chains[1].pem());
+
+        let fallback = order
+            .certificate_with_preferred_chain("unknown root")
+            .await?
+            .ok_or("no certificate")?;
+        assert_eq!(fallback, chains[0].pem());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reject_mismatched_csr() -> Result<(), Error> {
+        let server = TestServer::start().await?;
+        let mut order = new_order(&server, &["example.com"]).await?;
+
+        match order.finalize(&csr(&["example.org"])).await {
+            Err(Error::Api(problem)) => assert!(problem.is(&ProblemType::BadCsr)),
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retry_bad_nonce() -> Result<(), Error> {
+        let server = TestServer::start().await?;
+        server.reject_nonces(crate::MAX_BAD_NONCE_RETRIES);
+        Account::create_with_http(&NEW_ACCOUNT, &server.directory_url(), None, reqwest::Client::new()).await?;
+
+        server.reject_nonces(crate::MAX_BAD_NONCE_RETRIES + 1);
+        match Account::create_with_http(&NEW_ACCOUNT, &server.directory_url(), None, reqwest::Client::new()).await {
+            Err(Error::Api(problem)) => assert!(problem.is(&ProblemType::BadNonce)),
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        }
+        Ok(())
+    }
+}
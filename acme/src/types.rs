@@ -107,16 +107,26 @@ pub struct Problem {
     /// One of an enumerated list of problem types
     ///
     /// See <https://datatracker.ietf.org/doc/html/rfc8555#section-6.7>
-    pub r#type: Option<String>,
+    pub r#type: Option<ProblemType>,
     /// A human-readable explanation of the problem
     pub detail: Option<String>,
     /// The HTTP status code returned for this response
     pub status: Option<u16>,
+    /// Problems affecting individual identifiers of the request
+    ///
+    /// See <https://datatracker.ietf.org/doc/html/rfc8555#section-6.7.1>
+    #[serde(default)]
+    pub subproblems: Vec<Subproblem>,
 }
 
 impl Problem {
+    /// Returns `true` if the problem is of the given type
+    pub fn is(&self, r#type: &ProblemType) -> bool {
+        self.r#type.as_ref() == Some(r#type)
+    }
+
     pub(crate) async fn check<T: DeserializeOwned>(rsp: Response) -> Result<T, Error> {
-        rsp.json().await.map_err(Error::Http)
+        Ok(serde_json::from_slice(&Self::from_response(rsp).await?)?)
     }
 
     pub(crate) async fn from_response(rsp: Response) -> Result<Vec<u8>, Error> {
@@ -127,7 +137,20 @@ impl Problem {
             return Ok(body.to_vec());
         }
 
-        Err(serde_json::from_slice::<Problem>(&body)?.into())
+        Err(Self::from_error_body(status.as_u16(), &body).into())
+    }
+
+    /// Parse the body of an error response, falling back to a problem carrying only the
+    /// status code if the server did not send a valid problem document.
+    pub(crate) fn from_error_body(status: u16, body: &[u8]) -> Self {
+        let mut problem = serde_json::from_slice::<Problem>(body).unwrap_or_else(|_| Problem {
+            r#type: None,
+            detail: None,
+            status: None,
+            subproblems: Vec::new(),
+        });
+        problem.status.get_or_insert(status);
+        problem
     }
 }
 
@@ -142,10 +165,152 @@ impl fmt::Display for Problem {
             write!(f, " ({})", r#type)?;
         }
 
+        for subproblem in &self.subproblems {
+            write!(f, "; {subproblem}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A problem affecting a single identifier of a request
+///
+/// <https://datatracker.ietf.org/doc/html/rfc8555#section-6.7.1>
+#[derive(Clone, Debug, Deserialize)]
+pub struct Subproblem {
+    /// One of an enumerated list of problem types
+    pub r#type: Option<ProblemType>,
+    /// A human-readable explanation of the problem
+    pub detail: Option<String>,
+    /// The identifier that caused the problem
+    pub identifier: Option<Identifier>,
+}
+
+impl fmt::Display for Subproblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(Identifier::Dns(name)) = &self.identifier {
+            write!(f, "{name}: ")?;
+        }
+
+        f.write_str(self.detail.as_deref().unwrap_or("unknown error"))?;
+        if let Some(r#type) = &self.r#type {
+            write!(f, " ({})", r#type)?;
+        }
+
         Ok(())
     }
 }
 
+/// The type of a [`Problem`]
+///
+/// <https://datatracker.ietf.org/doc/html/rfc8555#section-6.7>
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+#[serde(from = "String")]
+pub enum ProblemType {
+    AccountDoesNotExist,
+    AlreadyRevoked,
+    BadCsr,
+    BadNonce,
+    BadPublicKey,
+    BadRevocationReason,
+    BadSignatureAlgorithm,
+    Caa,
+    Compound,
+    Connection,
+    Dns,
+    ExternalAccountRequired,
+    IncorrectResponse,
+    InvalidContact,
+    Malformed,
+    OrderNotReady,
+    RateLimited,
+    RejectedIdentifier,
+    ServerInternal,
+    Tls,
+    Unauthorized,
+    UnsupportedContact,
+    UnsupportedIdentifier,
+    UserActionRequired,
+    /// A problem type not defined by RFC 8555, such as a CA-specific extension
+    Other(String),
+}
+
+impl ProblemType {
+    /// Get the URN of the problem type, such as `urn:ietf:params:acme:error:badNonce`
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::AccountDoesNotExist => "urn:ietf:params:acme:error:accountDoesNotExist",
+            Self::AlreadyRevoked => "urn:ietf:params:acme:error:alreadyRevoked",
+            Self::BadCsr => "urn:ietf:params:acme:error:badCSR",
+            Self::BadNonce => "urn:ietf:params:acme:error:badNonce",
+            Self::BadPublicKey => "urn:ietf:params:acme:error:badPublicKey",
+            Self::BadRevocationReason => "urn:ietf:params:acme:error:badRevocationReason",
+            Self::BadSignatureAlgorithm => "urn:ietf:params:acme:error:badSignatureAlgorithm",
+            Self::Caa => "urn:ietf:params:acme:error:caa",
+            Self::Compound => "urn:ietf:params:acme:error:compound",
+            Self::Connection => "urn:ietf:params:acme:error:connection",
+            Self::Dns => "urn:ietf:params:acme:error:dns",
+            Self::ExternalAccountRequired => "urn:ietf:params:acme:error:externalAccountRequired",
+            Self::IncorrectResponse => "urn:ietf:params:acme:error:incorrectResponse",
+            Self::InvalidContact => "urn:ietf:params:acme:error:invalidContact",
+            Self::Malformed => "urn:ietf:params:acme:error:malformed",
+            Self::OrderNotReady => "urn:ietf:params:acme:error:orderNotReady",
+            Self::RateLimited => "urn:ietf:params:acme:error:rateLimited",
+            Self::RejectedIdentifier => "urn:ietf:params:acme:error:rejectedIdentifier",
+            Self::ServerInternal => "urn:ietf:params:acme:error:serverInternal",
+            Self::Tls => "urn:ietf:params:acme:error:tls",
+            Self::Unauthorized => "urn:ietf:params:acme:error:unauthorized",
+            Self::UnsupportedContact => "urn:ietf:params:acme:error:unsupportedContact",
+            Self::UnsupportedIdentifier => "urn:ietf:params:acme:error:unsupportedIdentifier",
+            Self::UserActionRequired => "urn:ietf:params:acme:error:userActionRequired",
+            Self::Other(urn) => urn,
+        }
+    }
+}
+
+impl From<String> for ProblemType {
+    fn from(urn: String) -> Self {
+        let Some(name) = urn.strip_prefix("urn:ietf:params:acme:error:") else {
+            return Self::Other(urn);
+        };
+
+        match name {
+            "accountDoesNotExist" => Self::AccountDoesNotExist,
+            "alreadyRevoked" => Self::AlreadyRevoked,
+            "badCSR" => Self::BadCsr,
+            "badNonce" => Self::BadNonce,
+            "badPublicKey" => Self::BadPublicKey,
+            "badRevocationReason" => Self::BadRevocationReason,
+            "badSignatureAlgorithm" => Self::BadSignatureAlgorithm,
+            "caa" => Self::Caa,
+            "compound" => Self::Compound,
+            "connection" => Self::Connection,
+            "dns" => Self::Dns,
+            "externalAccountRequired" => Self::ExternalAccountRequired,
+            "incorrectResponse" => Self::IncorrectResponse,
+            "invalidContact" => Self::InvalidContact,
+            "malformed" => Self::Malformed,
+            "orderNotReady" => Self::OrderNotReady,
+            "rateLimited" => Self::RateLimited,
+            "rejectedIdentifier" => Self::RejectedIdentifier,
+            "serverInternal" => Self::ServerInternal,
+            "tls" => Self::Tls,
+            "unauthorized" => Self::Unauthorized,
+            "unsupportedContact" => Self::UnsupportedContact,
+            "unsupportedIdentifier" => Self::UnsupportedIdentifier,
+            "userActionRequired" => Self::UserActionRequired,
+            _ => Self::Other(urn),
+        }
+    }
+}
+
+impl fmt::Display for ProblemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl std::error::Error for Problem {}
 
 #[derive(Debug, Serialize)]
@@ -1,148 +1,570 @@
-use std::path::PathBuf;
+use std::{
+    env, fmt, io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
 
 use bytes::Bytes;
+use futures_util::Stream;
 use hyper::{
-    Method, StatusCode, Uri,
+    HeaderMap, Method, StatusCode, Uri,
+    body::{Body, Frame, Incoming, SizeHint},
     client::conn::http1::SendRequest,
-    header::{CONTENT_TYPE, HOST},
+    header::{CONNECTION, CONTENT_TYPE, HOST, HeaderValue, UPGRADE},
 };
-use hyper_utils::{
-    http_body_util::{BodyExt, Full},
-    rt::TokioIo,
+use hyper_utils::http_body_util::{BodyExt, Full, StreamBody};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::sync::OnceCell;
+use tracing::debug;
+
+use crate::{
+    error::Error,
+    transport::{DEFAULT_SOCKET_PATH, Transport},
 };
-use serde::{Serialize, de::DeserializeOwned};
-use tokio::{net::UnixStream, sync::Mutex};
-use tracing::{debug, error};
 
-use crate::error::Error;
+/// The most recent version of the Docker Engine API supported by this client. When the daemon
+/// is older, the client uses the version of the daemon instead.
+const MAX_API_VERSION: ApiVersion = ApiVersion {
+    major: 1,
+    minor: 47,
+};
+
+/// The maximum number of idle connections kept open to be reused by the next requests
+const MAX_IDLE_CONNECTIONS: usize = 8;
 
 pub struct Client {
-    socket_path: PathBuf,
+    transport: Transport,
+    api_version: OnceCell<ApiVersion>,
     // we use the interior mutability pattern to avoid users needing to make the client mut
     // each time they want to send a request.
     // See here to learn more about the Interior Mutability Pattern
     // https://doc.rust-lang.org/book/ch15-05-interior-mutability.html
-    // socket: Arc<Mutex<RefCell<Option<SendRequest<Full<Bytes>>>>>>,
-    socket: Mutex<Option<SendRequest<Full<Bytes>>>>,
+    // The lock is only held to take or return a connection, never while a request is in flight,
+    // so a std Mutex is enough.
+    idle_connections: Mutex<Vec<SendRequest<RequestBody>>>,
 }
 
 impl Client {
+    /// Create a client connecting to the daemon over a Unix socket, `/var/run/docker.sock` by
+    /// default.
     pub fn new(socket_path: Option<&str>) -> Client {
-        let socket_path = socket_path.unwrap_or("/var/run/docker.sock");
+        let socket_path = socket_path.unwrap_or(DEFAULT_SOCKET_PATH);
         let socket_path = PathBuf::from(socket_path);
 
+        return Client::with_transport(Transport::Unix(socket_path));
+    }
+
+    /// Create a client configured from the environment, like the docker CLI:
+    /// - `DOCKER_HOST`: `unix:///path/to/docker.sock` or `tcp://host:port`. Defaults to
+    ///   `unix:///var/run/docker.sock`.
+    /// - `DOCKER_TLS_VERIFY`: if set and not empty, TCP connections use mutual TLS.
+    /// - `DOCKER_CERT_PATH`: the directory containing the `ca.pem`, `cert.pem` and `key.pem` files
+    ///   used for TLS. Defaults to `~/.docker`.
+    /// - `DOCKER_API_VERSION`: use this version of the API instead of negotiating it.
+    pub fn from_env() -> Result<Client, Error> {
+        return Client::from_vars(|name| env::var(name).ok().filter(|value| !value.is_empty()));
+    }
+
+    /// `from_env` with the non-empty environment variables returned by `var`
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Client, Error> {
+        let host = var("DOCKER_HOST").unwrap_or_else(|| format!("unix://{DEFAULT_SOCKET_PATH}"));
+
+        // the certificates are only needed, and HOME only required, when TLS is enabled
+        let client = if var("DOCKER_TLS_VERIFY").is_some() {
+            let cert_path = match var("DOCKER_CERT_PATH") {
+                Some(cert_path) => PathBuf::from(cert_path),
+                None => {
+                    let home = var("HOME")
+                        .ok_or_else(|| Error::InvalidConfig("DOCKER_CERT_PATH and HOME are not set".to_string()))?;
+                    Path::new(&home).join(".docker")
+                }
+            };
+            Client::with_host(&host, Some(&cert_path))?
+        } else {
+            Client::with_host(&host, None)?
+        };
+
+        return match var("DOCKER_API_VERSION") {
+            Some(version) => client.with_api_version(&version),
+            None => Ok(client),
+        };
+    }
+
+    /// Create a client connecting to `host`: `unix:///path/to/docker.sock` or `tcp://host:port`.
+    /// If `cert_path` is set, TCP connections use mutual TLS with the `ca.pem`, `cert.pem` and
+    /// `key.pem` files of this directory.
+    pub fn with_host(host: &str, cert_path: Option<&Path>) -> Result<Client, Error> {
+        return Ok(Client::with_transport(Transport::parse(host, cert_path)?));
+    }
+
+    fn with_transport(transport: Transport) -> Client {
         return Client {
-            socket_path: socket_path,
-            socket: Mutex::new(None),
+            transport,
+            api_version: OnceCell::new(),
+            idle_connections: Mutex::new(Vec::with_capacity(MAX_IDLE_CONNECTIONS)),
         };
     }
 
+    /// Use this version of the API (e.g. `1.45`) instead of negotiating it with the daemon.
+    pub fn with_api_version(self, version: &str) -> Result<Client, Error> {
+        let version = ApiVersion::parse(version)
+            .ok_or_else(|| Error::InvalidConfig(format!("invalid docker API version: {version}")))?;
+
+        return Ok(Client {
+            api_version: OnceCell::new_with(Some(version)),
+            ..self
+        });
+    }
+
     /// connect to the docker host.
     /// Note that you don't necessarily need to call `connect`. The client automatically connects
     /// to the Docker host on the first request if `connect` is not called before.
     pub async fn connect(&self) -> Result<(), Error> {
-        if self.socket.lock().await.is_some() {
+        if self
+            .idle_connections
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|conn| !conn.is_closed())
+        {
             return Ok(());
         }
 
-        let unix_stream = UnixStream::connect(&self.socket_path)
-            .await
-            .map_err(|err| Error::Connecting(err.into()))?;
-        let stream = TokioIo::new(unix_stream);
+        let conn = self.transport.connect().await?;
+        self.release_connection(conn);
 
-        let (sender, conn) = hyper::client::conn::http1::handshake(stream)
-            .await
-            .map_err(|err| Error::Connecting(err.into()))?;
-        debug!("connection established");
+        return Ok(());
+    }
 
-        // spawn a task to poll the connection and drive the HTTP state
-        tokio::task::spawn(async move {
-            if let Err(err) = conn.await {
-                error!("connection error: {:?}", err);
+    /// Take an idle connection from the pool, or open a new one if none is available.
+    /// Returns whether the connection was reused, in which case the daemon may have closed it
+    /// in the meantime.
+    async fn acquire_connection(&self) -> Result<(SendRequest<RequestBody>, bool), Error> {
+        {
+            let mut idle_connections = self.idle_connections.lock().unwrap();
+            idle_connections.retain(|conn| !conn.is_closed());
+            // connections may still be busy reading the body of their previous response
+            if let Some(index) = idle_connections.iter().position(|conn| conn.is_ready()) {
+                return Ok((idle_connections.swap_remove(index), true));
             }
-        });
-
-        self.socket.lock().await.replace(sender);
+        }
 
-        return Ok(());
+        let conn = self.transport.connect().await?;
+        return Ok((conn, false));
     }
 
-    pub(crate) async fn send_request<R: DeserializeOwned, S: Serialize>(
-        &self,
-        path: &str,
-        query: Option<S>,
-        body: Option<S>,
-    ) -> Result<R, Error> {
-        if self.socket.lock().await.is_none() {
-            self.connect().await?;
+    /// Return a connection to the pool so it can be reused by the next requests.
+    fn release_connection(&self, conn: SendRequest<RequestBody>) {
+        if conn.is_closed() {
+            return;
         }
 
-        // first we need to prepare the request for hyper
-        let path_and_query = match query {
-            Some(query_params) => {
-                let query_string = serde_urlencoded::to_string(query_params)
-                    .map_err(|err| Error::Unspecified(format!("encoding request's query parameters: {err}")))?;
-                format!("{path}?{query_string}")
+        let mut idle_connections = self.idle_connections.lock().unwrap();
+        if idle_connections.len() < MAX_IDLE_CONNECTIONS {
+            idle_connections.push(conn);
+        }
+    }
+
+    /// Get the version of the API used to talk to the daemon, e.g. `1.45`.
+    /// On the first call, the version is negotiated with the daemon: the client uses the most
+    /// recent version supported by both.
+    pub async fn api_version(&self) -> Result<String, Error> {
+        let version = self
+            .api_version
+            .get_or_try_init(|| self.negotiate_api_version())
+            .await?;
+        return Ok(version.to_string());
+    }
+
+    async fn negotiate_api_version(&self) -> Result<ApiVersion, Error> {
+        let response = self
+            .request_unversioned(Method::GET, "/_ping", None::<()>, Bytes::new())
+            .await?;
+        let ping_version = response
+            .headers()
+            .get("api-version")
+            .and_then(|value| value.to_str().ok())
+            .and_then(ApiVersion::parse);
+
+        let daemon_version = match ping_version {
+            Some(version) => version,
+            None => {
+                // older daemons don't return the API version when pinged
+                let response = self
+                    .request_unversioned(Method::GET, "/version", None::<()>, Bytes::new())
+                    .await?;
+                let body = response
+                    .collect()
+                    .await
+                    .map_err(|err| Error::Unspecified(format!("reading response: {err}")))?
+                    .to_bytes();
+                let version: VersionResponse = serde_json::from_slice(&body)
+                    .map_err(|err| Error::Unspecified(format!("parsing response: {err}")))?;
+                ApiVersion::parse(&version.api_version).ok_or_else(|| {
+                    Error::Unspecified(format!("invalid API version returned by the daemon: {}", version.api_version))
+                })?
             }
-            None => path.to_string(),
         };
 
-        let hyper_uri = Uri::builder()
-            .scheme("unix")
-            .authority("docker")
-            .path_and_query(path_and_query)
-            .build()
-            .map_err(|err| Error::Unspecified(format!("building request's URL: {err}")))?;
+        let version = daemon_version.min(MAX_API_VERSION);
+        debug!("using docker API version {version}");
+        return Ok(version);
+    }
+
+    /// Prefix `path` with the API version, e.g. `/v1.47/containers/json`
+    async fn versioned_path(&self, path: &str) -> Result<String, Error> {
+        let version = self
+            .api_version
+            .get_or_try_init(|| self.negotiate_api_version())
+            .await?;
+        return Ok(format!("/v{version}{path}"));
+    }
 
+    /// Send a request to the Docker Engine API and parse its JSON response.
+    /// Empty responses (e.g. `204 No Content`) are parsed as `null`, so `R` can be `()`.
+    pub(crate) async fn send_request<R: DeserializeOwned, Q: Serialize, B: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<Q>,
+        body: Option<B>,
+    ) -> Result<R, Error> {
         let body = body
             .map(|body_data| serde_json::to_vec(&body_data))
             .unwrap_or(Ok(Vec::new()))
             .map_err(|err| Error::Unspecified(format!("encoding body to JSON: {err}")))?;
-        let body_bytes = Bytes::from(body);
-
-        let hyper_request = hyper::Request::builder()
-            .method(Method::GET)
-            .uri(hyper_uri)
-            .header(HOST, "docker")
-            .header(CONTENT_TYPE, "application/json")
-            .body(Full::new(body_bytes))
-            .map_err(|err| Error::Unspecified(format!("building request: {err}")))?;
-
-        let response = {
-            let mut socket = self.socket.lock().await;
-            // we can safely unwrap here as `connect` would have returned an error earlier if the connection
-            // failed
-            socket
-                .as_mut()
-                .unwrap()
-                .send_request(hyper_request)
-                .await
-                .map_err(|err| Error::Unspecified(format!("sending request: {err}")))?
-        };
-
-        if response.status() != StatusCode::OK {
-            return Err(Error::Unspecified(format!(
-                "received not OK status code: {}",
-                response.status()
-            )));
-        }
 
-        // let mut response_body = BytesMut::with_capacity(response.size_hint().upper().unwrap_or(500) as usize);
-        // while let Some(next) = response.frame().await {
-        //     let frame = next.map_err(|err| Error::Unspecified(format!("reading response: {err}")))?;
-        //     if let Some(chunk) = frame.data_ref() {
-        //         response_body.put(chunk.as_ref());
-        //     }
-        // }
+        let response = self.request(method, path, query, Bytes::from(body)).await?;
 
         let response_body = response
             .collect()
             .await
             .map_err(|err| Error::Unspecified(format!("reading response: {err}")))?
             .to_bytes();
-        let res = serde_json::from_slice(&response_body)
+        let response_body: &[u8] = if response_body.is_empty() {
+            b"null"
+        } else {
+            &response_body
+        };
+        let res = serde_json::from_slice(response_body)
             .map_err(|err| Error::Unspecified(format!("parsing response: {err}")))?;
 
         return Ok(res);
     }
+
+    /// Send a request to the Docker Engine API and return the response if its status code is
+    /// successful. Otherwise, the error message returned by the daemon is decoded into an `Error`.
+    pub(crate) async fn request<Q: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<Q>,
+        body: Bytes,
+    ) -> Result<hyper::Response<Incoming>, Error> {
+        let path = self.versioned_path(path).await?;
+        return self.request_unversioned(method, &path, query, body).await;
+    }
+
+    async fn request_unversioned<Q: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<Q>,
+        body: Bytes,
+    ) -> Result<hyper::Response<Incoming>, Error> {
+        loop {
+            let (mut conn, reused) = self.acquire_connection().await?;
+            let hyper_request =
+                build_request(method.clone(), path, query.as_ref(), body.clone().into(), HeaderMap::new())?;
+
+            match conn.send_request(hyper_request).await {
+                Ok(response) => {
+                    self.release_connection(conn);
+                    return check_response(response).await;
+                }
+                // the daemon closed the idle connection before the request was sent: retry on
+                // another connection. The pool is eventually exhausted and a new connection is
+                // opened, so this can't loop forever.
+                Err(err) if reused && is_connection_closed(&err) => {
+                    debug!("retrying request on another connection: {err}");
+                }
+                Err(err) => return Err(Error::Unspecified(format!("sending request: {err}"))),
+            }
+        }
+    }
+
+    /// Send a request on a new connection dedicated to this request, so that long-lived responses
+    /// such as log streams, or streamed request bodies, don't hold up other requests.
+    /// `headers` are added to the request, and override the default `Content-Type`.
+    pub(crate) async fn request_stream<Q: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        query: Option<Q>,
+        body: RequestBody,
+        headers: HeaderMap,
+    ) -> Result<hyper::Response<Incoming>, Error> {
+        let path = self.versioned_path(path).await?;
+        let hyper_request = build_request(method, &path, query, body, headers)?;
+
+        let mut sender = self.transport.connect().await?;
+        let response = sender
+            .send_request(hyper_request)
+            .await
+            .map_err(|err| Error::Unspecified(format!("sending request: {err}")))?;
+
+        return check_response(response).await;
+    }
+}
+
+/// A stream of chunks of a request body, e.g. the tar archive of an image's build context
+pub(crate) type BodyStream = Pin<Box<dyn Stream<Item = Result<Frame<Bytes>, Error>> + Send>>;
+
+/// The body of a request to the Docker Engine API
+pub(crate) enum RequestBody {
+    Full(Full<Bytes>),
+    Stream(StreamBody<BodyStream>),
+}
+
+impl From<Bytes> for RequestBody {
+    fn from(body: Bytes) -> Self {
+        return RequestBody::Full(Full::new(body));
+    }
+}
+
+impl Body for RequestBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Error>>> {
+        return match self.get_mut() {
+            RequestBody::Full(full) => Pin::new(full)
+                .poll_frame(cx)
+                .map(|frame| frame.map(|frame| frame.map_err(|err| match err {}))),
+            RequestBody::Stream(stream) => Pin::new(stream).poll_frame(cx),
+        };
+    }
+
+    fn is_end_stream(&self) -> bool {
+        return match self {
+            RequestBody::Full(full) => full.is_end_stream(),
+            RequestBody::Stream(stream) => stream.is_end_stream(),
+        };
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        return match self {
+            RequestBody::Full(full) => full.size_hint(),
+            RequestBody::Stream(stream) => Body::size_hint(stream),
+        };
+    }
+}
+
+/// Headers asking the daemon to hijack the connection (e.g. to attach to a container's
+/// `stdin`), which can then be retrieved with `hyper::upgrade::on`
+pub(crate) fn upgrade_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONNECTION, HeaderValue::from_static("Upgrade"));
+    headers.insert(UPGRADE, HeaderValue::from_static("tcp"));
+    return headers;
+}
+
+/// Whether the request failed because the daemon had closed the connection before reading the
+/// request, in which case the request can safely be sent again.
+fn is_connection_closed(err: &hyper::Error) -> bool {
+    if err.is_canceled() {
+        return true;
+    }
+
+    let io_error = std::error::Error::source(err).and_then(|source| source.downcast_ref::<io::Error>());
+    return io_error.is_some_and(|io_error| io_error.kind() == io::ErrorKind::BrokenPipe);
+}
+
+fn build_request<Q: Serialize>(
+    method: Method,
+    path: &str,
+    query: Option<Q>,
+    body: RequestBody,
+    headers: HeaderMap,
+) -> Result<hyper::Request<RequestBody>, Error> {
+    let path_and_query = match query {
+        Some(query_params) => {
+            let query_string = serde_urlencoded::to_string(query_params)
+                .map_err(|err| Error::Unspecified(format!("encoding request's query parameters: {err}")))?;
+            format!("{path}?{query_string}")
+        }
+        None => path.to_string(),
+    };
+
+    let hyper_uri = Uri::builder()
+        .path_and_query(path_and_query)
+        .build()
+        .map_err(|err| Error::Unspecified(format!("building request's URL: {err}")))?;
+
+    let mut request = hyper::Request::builder()
+        .method(method)
+        .uri(hyper_uri)
+        .header(HOST, "docker")
+        .header(CONTENT_TYPE, "application/json")
+        .body(body)
+        .map_err(|err| Error::Unspecified(format!("building request: {err}")))?;
+    request.headers_mut().extend(headers);
+
+    return Ok(request);
+}
+
+/// Return the response if its status code is successful. Otherwise, decode the error message
+/// returned by the daemon into an `Error`.
+async fn check_response(response: hyper::Response<Incoming>) -> Result<hyper::Response<Incoming>, Error> {
+    let status = response.status();
+    if status.is_success() || status == StatusCode::SWITCHING_PROTOCOLS {
+        return Ok(response);
+    }
+
+    let response_body = response
+        .collect()
+        .await
+        .map_err(|err| Error::Unspecified(format!("reading response: {err}")))?
+        .to_bytes();
+    let message = match serde_json::from_slice::<ErrorResponse>(&response_body) {
+        Ok(error_response) => error_response.message,
+        Err(_) => String::from_utf8_lossy(&response_body).trim().to_string(),
+    };
+
+    return Err(Error::from_status(status, message));
+}
+
+/// A version of the Docker Engine API, e.g. `1.47`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct ApiVersion {
+    major: u32,
+    minor: u32,
+}
+
+impl ApiVersion {
+    fn parse(version: &str) -> Option<ApiVersion> {
+        let (major, minor) = version.trim().trim_start_matches('v').split_once('.')?;
+        return Some(ApiVersion {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        });
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}.{}", self.major, self.minor);
+    }
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    #[serde(rename = "ApiVersion")]
+    api_version: String,
+}
+
+/// The body of the Docker Engine API's error responses
+#[derive(Deserialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::mock_daemon::MockDaemon;
+
+    fn vars(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        return move |name| vars.iter().find(|(var, _)| var == name).map(|(_, value)| value.clone());
+    }
+
+    #[test]
+    fn from_vars_default_socket() {
+        let client = Client::from_vars(vars(&[])).unwrap();
+        assert!(matches!(&client.transport, Transport::Unix(path) if path == Path::new(DEFAULT_SOCKET_PATH)));
+        assert!(client.api_version.get().is_none());
+    }
+
+    #[test]
+    fn from_vars_unix_host() {
+        let client = Client::from_vars(vars(&[("DOCKER_HOST", "unix:///run/docker.sock")])).unwrap();
+        assert!(matches!(&client.transport, Transport::Unix(path) if path == Path::new("/run/docker.sock")));
+    }
+
+    #[test]
+    fn from_vars_tcp_host_and_api_version() {
+        let client =
+            Client::from_vars(vars(&[("DOCKER_HOST", "tcp://10.0.0.2:2375"), ("DOCKER_API_VERSION", "1.45")])).unwrap();
+        assert!(matches!(&client.transport, Transport::Tcp { address, tls: None } if address == "10.0.0.2:2375"));
+        assert_eq!(client.api_version.get().map(ToString::to_string).as_deref(), Some("1.45"));
+    }
+
+    #[test]
+    fn from_vars_tls_without_cert_path() {
+        // neither DOCKER_CERT_PATH nor HOME is set, so there is no directory to load the certificates from
+        assert!(matches!(
+            Client::from_vars(vars(&[("DOCKER_HOST", "tcp://10.0.0.2:2376"), ("DOCKER_TLS_VERIFY", "1")])),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn response_status_mapping() {
+        let daemon = MockDaemon::start().await.unwrap();
+        let client = daemon.client();
+        let add_error = |path: &str, status: StatusCode| {
+            daemon.add_response(Method::POST, path, status, &json!({ "message": format!("error {path}") }));
+        };
+        add_error("/bad-request", StatusCode::BAD_REQUEST);
+        add_error("/not-found", StatusCode::NOT_FOUND);
+        add_error("/conflict", StatusCode::CONFLICT);
+        add_error("/server-error", StatusCode::INTERNAL_SERVER_ERROR);
+        daemon.add_raw_response(
+            Method::POST,
+            "/not-modified",
+            StatusCode::NOT_MODIFIED,
+            "text/plain",
+            Bytes::new(),
+        );
+        daemon.add_raw_response(Method::POST, "/no-content", StatusCode::NO_CONTENT, "text/plain", Bytes::new());
+        daemon.add_response(Method::POST, "/created", StatusCode::CREATED, &json!({ "Id": "abc" }));
+        daemon.add_raw_response(
+            Method::POST,
+            "/plain-error",
+            StatusCode::SERVICE_UNAVAILABLE,
+            "text/plain",
+            Bytes::from_static(b"daemon is shutting down\n"),
+        );
+
+        let send =
+            |path: &'static str| client.send_request::<serde_json::Value, (), ()>(Method::POST, path, None, None);
+        assert!(
+            matches!(send("/bad-request").await, Err(Error::BadRequest(message)) if message == "error /bad-request")
+        );
+        assert!(matches!(send("/not-found").await, Err(Error::NotFound(message)) if message == "error /not-found"));
+        assert!(matches!(send("/conflict").await, Err(Error::Conflict(message)) if message == "error /conflict"));
+        assert!(matches!(
+            send("/server-error").await,
+            Err(Error::Server { status: StatusCode::INTERNAL_SERVER_ERROR, message }) if message == "error /server-error"
+        ));
+        assert!(matches!(
+            send("/plain-error").await,
+            Err(Error::Server { status: StatusCode::SERVICE_UNAVAILABLE, message }) if message == "daemon is shutting down"
+        ));
+        assert!(matches!(send("/not-modified").await, Err(Error::NotModified)));
+        assert_eq!(send("/no-content").await.unwrap(), serde_json::Value::Null);
+        assert_eq!(send("/created").await.unwrap(), json!({ "Id": "abc" }));
+
+        let requests = daemon.requests();
+        assert!(requests.iter().skip(1).all(|request| request.method == Method::POST));
+        assert_eq!(requests.last().unwrap().path, "/created");
+    }
 }
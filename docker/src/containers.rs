@@ -1,6 +1,17 @@
+use bytes::Bytes;
+use hyper::{HeaderMap, Method};
+
 use crate::{
     Client, Error,
-    model::{ContainerSummary, ListContainersOptions},
+    client::upgrade_headers,
+    json_stream::{JsonStream, response_json_stream},
+    logs::{AttachContainerResults, LogStream, hijack_connection, response_log_stream},
+    model::{
+        AttachContainerOptions, ContainerCreateBody, ContainerCreateResponse, ContainerInspectResponse, ContainerStats,
+        ContainerSummary, ContainerWaitResponse, CreateContainerOptions, InspectContainerOptions, KillContainerOptions,
+        ListContainersOptions, LogsOptions, RemoveContainerOptions, RestartContainerOptions, StatsOptions,
+        StopContainerOptions, WaitContainerOptions,
+    },
 };
 
 impl Client {
@@ -8,6 +19,339 @@ impl Client {
         &self,
         options: Option<ListContainersOptions>,
     ) -> Result<Vec<ContainerSummary>, Error> {
-        return self.send_request("/containers/json", options, None).await;
+        return self
+            .send_request(Method::GET, "/containers/json", options, None::<()>)
+            .await;
+    }
+
+    pub async fn create_container(
+        &self,
+        options: Option<CreateContainerOptions>,
+        config: ContainerCreateBody,
+    ) -> Result<ContainerCreateResponse, Error> {
+        return self
+            .send_request(Method::POST, "/containers/create", options, Some(config))
+            .await;
+    }
+
+    /// Start a container. Returns `Error::NotModified` if the container is already started.
+    pub async fn start_container(&self, id: &str) -> Result<(), Error> {
+        return self
+            .send_request(Method::POST, &format!("/containers/{id}/start"), None::<()>, None::<()>)
+            .await;
+    }
+
+    /// Stop a container. Returns `Error::NotModified` if the container is already stopped.
+    pub async fn stop_container(&self, id: &str, options: Option<StopContainerOptions>) -> Result<(), Error> {
+        return self
+            .send_request(Method::POST, &format!("/containers/{id}/stop"), options, None::<()>)
+            .await;
+    }
+
+    pub async fn restart_container(&self, id: &str, options: Option<RestartContainerOptions>) -> Result<(), Error> {
+        return self
+            .send_request(Method::POST, &format!("/containers/{id}/restart"), options, None::<()>)
+            .await;
+    }
+
+    /// Send a POSIX signal to a container, defaulting to killing the container.
+    pub async fn kill_container(&self, id: &str, options: Option<KillContainerOptions>) -> Result<(), Error> {
+        return self
+            .send_request(Method::POST, &format!("/containers/{id}/kill"), options, None::<()>)
+            .await;
+    }
+
+    pub async fn remove_container(&self, id: &str, options: Option<RemoveContainerOptions>) -> Result<(), Error> {
+        return self
+            .send_request(Method::DELETE, &format!("/containers/{id}"), options, None::<()>)
+            .await;
+    }
+
+    /// Return low-level information about a container.
+    pub async fn inspect_container(
+        &self,
+        id: &str,
+        options: Option<InspectContainerOptions>,
+    ) -> Result<ContainerInspectResponse, Error> {
+        return self
+            .send_request(Method::GET, &format!("/containers/{id}/json"), options, None::<()>)
+            .await;
+    }
+
+    /// Block until a container stops, then return its exit code.
+    pub async fn wait_container(
+        &self,
+        id: &str,
+        options: Option<WaitContainerOptions>,
+    ) -> Result<ContainerWaitResponse, Error> {
+        return self
+            .send_request(Method::POST, &format!("/containers/{id}/wait"), options, None::<()>)
+            .await;
+    }
+
+    /// Get the `stdout` and `stderr` logs of a container.
+    /// With `follow`, the stream stays open and yields new output until the container stops.
+    pub async fn logs(&self, id: &str, options: Option<LogsOptions>) -> Result<LogStream, Error> {
+        let response = self
+            .request_stream(
+                Method::GET,
+                &format!("/containers/{id}/logs"),
+                options,
+                Bytes::new().into(),
+                HeaderMap::new(),
+            )
+            .await?;
+
+        return Ok(response_log_stream(response));
+    }
+
+    /// Attach to a container to read its output and write to its `stdin`.
+    /// The connection to the daemon is hijacked for the duration of the attachment.
+    pub async fn attach_container(
+        &self,
+        id: &str,
+        options: Option<AttachContainerOptions>,
+    ) -> Result<AttachContainerResults, Error> {
+        let response = self
+            .request_stream(
+                Method::POST,
+                &format!("/containers/{id}/attach"),
+                options,
+                Bytes::new().into(),
+                upgrade_headers(),
+            )
+            .await?;
+
+        let (output, input) = hijack_connection(response).await?;
+
+        return Ok(AttachContainerResults {
+            output,
+            input,
+        });
+    }
+
+    /// Get a sample of the resource usage statistics of a container.
+    /// The daemon waits for a second sample to fill `precpu_stats`, so that the CPU usage can be
+    /// computed with `ContainerStats::cpu_percent`.
+    pub async fn stats(&self, id: &str) -> Result<ContainerStats, Error> {
+        let options = StatsOptions {
+            stream: false,
+            one_shot: false,
+        };
+        return self
+            .send_request(Method::GET, &format!("/containers/{id}/stats"), Some(options), None::<()>)
+            .await;
+    }
+
+    /// Stream the resource usage statistics of a container, sampled every second.
+    pub async fn stats_stream(&self, id: &str) -> Result<JsonStream<'static, ContainerStats>, Error> {
+        let options = StatsOptions {
+            stream: true,
+            one_shot: false,
+        };
+        let response = self
+            .request_stream(
+                Method::GET,
+                &format!("/containers/{id}/stats"),
+                Some(options),
+                Bytes::new().into(),
+                HeaderMap::new(),
+            )
+            .await?;
+
+        return Ok(response_json_stream(response));
+    }
+}
+
+impl ContainerStats {
+    /// The CPU usage of the container since the previous sample, as a percentage of one CPU, like
+    /// `docker stats`: a container using 2 CPUs fully is at 200%.
+    /// Returns `None` if the previous sample is not available, e.g. for one-shot stats.
+    pub fn cpu_percent(&self) -> Option<f64> {
+        let cpu_delta = self
+            .cpu_stats
+            .cpu_usage
+            .total_usage
+            .checked_sub(self.precpu_stats.cpu_usage.total_usage)?;
+        let system_delta = self
+            .cpu_stats
+            .system_cpu_usage?
+            .checked_sub(self.precpu_stats.system_cpu_usage?)?;
+        if system_delta == 0 {
+            return None;
+        }
+
+        let online_cpus = match self.cpu_stats.online_cpus {
+            Some(online_cpus) if online_cpus > 0 => online_cpus as f64,
+            _ => self.cpu_stats.cpu_usage.percpu_usage.as_ref()?.len() as f64,
+        };
+
+        return Some(cpu_delta as f64 / system_delta as f64 * online_cpus * 100.0);
+    }
+
+    /// The memory used by the container, excluding the page cache, like `docker stats`
+    pub fn memory_usage(&self) -> Option<u64> {
+        let usage = self.memory_stats.usage?;
+        let cache = self.memory_stats.stats.as_ref().and_then(|stats| {
+            // cgroups v1 and v2 respectively
+            stats.get("total_inactive_file").or_else(|| stats.get("inactive_file"))
+        });
+
+        return Some(match cache {
+            Some(cache) if *cache < usage => usage - cache,
+            _ => usage,
+        });
+    }
+
+    /// The memory used by the container as a percentage of its limit
+    pub fn memory_percent(&self) -> Option<f64> {
+        let limit = self.memory_stats.limit.filter(|limit| *limit > 0)?;
+        return Some(self.memory_usage()? as f64 / limit as f64 * 100.0);
+    }
+
+    /// The bytes received and sent on all the network interfaces of the container
+    pub fn network_bytes(&self) -> (u64, u64) {
+        return self
+            .networks
+            .iter()
+            .flat_map(|networks| networks.values())
+            .fold((0, 0), |(rx, tx), network| (rx + network.rx_bytes, tx + network.tx_bytes));
+    }
+
+    /// The bytes read from and written to block devices by the container
+    pub fn blkio_bytes(&self) -> (u64, u64) {
+        return self
+            .blkio_stats
+            .io_service_bytes_recursive
+            .iter()
+            .flatten()
+            .fold((0, 0), |(read, write), entry| match entry.op.to_ascii_lowercase().as_str() {
+                "read" => (read + entry.value, write),
+                "write" => (read, write + entry.value),
+                _ => (read, write),
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{mock_daemon::MockDaemon, model::ContainerConfig};
+
+    #[test]
+    fn stats_helpers() {
+        let stats: ContainerStats = serde_json::from_str(
+            r#"{"read":"2024-05-01T10:00:01Z","preread":"2024-05-01T10:00:00Z","name":"/web","id":"abc",
+            "pids_stats":{"current":3},
+            "cpu_stats":{"cpu_usage":{"total_usage":1500000000,"usage_in_kernelmode":0,"usage_in_usermode":0},
+                "system_cpu_usage":20000000000,"online_cpus":4,"throttling_data":{}},
+            "precpu_stats":{"cpu_usage":{"total_usage":1000000000},"system_cpu_usage":16000000000},
+            "memory_stats":{"usage":104857600,"limit":419430400,"stats":{"inactive_file":4857600}},
+            "networks":{"eth0":{"rx_bytes":100,"rx_packets":1,"rx_errors":0,"rx_dropped":0,"tx_bytes":50,
+                "tx_packets":1,"tx_errors":0,"tx_dropped":0}},
+            "blkio_stats":{"io_service_bytes_recursive":[{"major":8,"minor":0,"op":"read","value":4096},
+                {"major":8,"minor":0,"op":"write","value":8192}]}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(stats.cpu_percent(), Some(50.0));
+        assert_eq!(stats.memory_usage(), Some(100_000_000));
+        assert_eq!(stats.memory_percent().map(f64::round), Some(24.0));
+        assert_eq!(stats.network_bytes(), (100, 50));
+        assert_eq!(stats.blkio_bytes(), (4096, 8192));
+    }
+
+    #[tokio::test]
+    async fn container_lifecycle() {
+        let daemon = MockDaemon::start().await.unwrap();
+        daemon.add_response(
+            Method::POST,
+            "/containers/create",
+            StatusCode::CREATED,
+            &json!({ "Id": "abc", "Warnings": [] }),
+        );
+        for action in ["start", "stop", "restart", "kill"] {
+            daemon.add_raw_response(
+                Method::POST,
+                &format!("/containers/abc/{action}"),
+                StatusCode::NO_CONTENT,
+                "text/plain",
+                Bytes::new(),
+            );
+        }
+        daemon.add_response(
+            Method::POST,
+            "/containers/abc/wait",
+            StatusCode::OK,
+            &json!({ "StatusCode": 137 }),
+        );
+        daemon.add_raw_response(
+            Method::DELETE,
+            "/containers/abc",
+            StatusCode::NO_CONTENT,
+            "text/plain",
+            Bytes::new(),
+        );
+        let client = daemon.client();
+
+        let config = ContainerCreateBody {
+            config: ContainerConfig {
+                image: Some("nginx:latest".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let options = CreateContainerOptions {
+            name: Some("web".to_string()),
+            ..Default::default()
+        };
+        let created = client.create_container(Some(options), config).await.unwrap();
+        assert_eq!(created.id, "abc");
+
+        client.start_container("abc").await.unwrap();
+        let stop = StopContainerOptions {
+            t: Some(5),
+            ..Default::default()
+        };
+        client.stop_container("abc", Some(stop)).await.unwrap();
+        client.restart_container("abc", None).await.unwrap();
+        let kill = KillContainerOptions {
+            signal: Some("SIGKILL".to_string()),
+        };
+        client.kill_container("abc", Some(kill)).await.unwrap();
+        let wait = client.wait_container("abc", None).await.unwrap();
+        assert_eq!(wait.status_code, 137);
+        let remove = RemoveContainerOptions {
+            force: true,
+            ..Default::default()
+        };
+        client.remove_container("abc", Some(remove)).await.unwrap();
+
+        let requests: Vec<_> = daemon.requests().into_iter().skip(1).collect();
+        let paths: Vec<_> = requests
+            .iter()
+            .map(|request| (request.method.as_str(), request.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("POST", "/containers/create"),
+                ("POST", "/containers/abc/start"),
+                ("POST", "/containers/abc/stop"),
+                ("POST", "/containers/abc/restart"),
+                ("POST", "/containers/abc/kill"),
+                ("POST", "/containers/abc/wait"),
+                ("DELETE", "/containers/abc"),
+            ]
+        );
+        assert_eq!(requests[0].query["name"], "web");
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["Image"], "nginx:latest");
+        assert_eq!(requests[2].query["t"], "5");
+        assert_eq!(requests[4].query["signal"], "SIGKILL");
+        assert_eq!(requests[6].query["force"], "true");
     }
 }
@@ -1,7 +1,18 @@
 mod client;
 pub mod containers;
 mod error;
+pub mod exec;
+pub mod images;
+mod json_stream;
+pub mod logs;
+#[cfg(any(test, feature = "mock-daemon"))]
+pub mod mock_daemon;
 pub mod model;
+pub mod networks;
+pub mod system;
+mod transport;
+pub mod volumes;
 
 pub use client::Client;
 pub use error::Error;
+pub use json_stream::JsonStream;
@@ -1,7 +1,45 @@
+use hyper::StatusCode;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("connecting to docker socket: {0}")]
-    Connecting(Box<dyn std::error::Error>),
+    Connecting(Box<dyn std::error::Error + Send + Sync>),
+    /// The configuration of the client, e.g. `DOCKER_HOST` or the TLS certificates, is invalid
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
+    /// The daemon answered `304 Not Modified`, e.g. when starting a container that is already
+    /// running
+    #[error("not modified")]
+    NotModified,
+    #[error("bad request: {0}")]
+    BadRequest(String),
+    #[error("not found: {0}")]
+    NotFound(String),
+    #[error("conflict: {0}")]
+    Conflict(String),
+    #[error("docker daemon error ({status}): {message}")]
+    Server { status: StatusCode, message: String },
+    /// A message streamed by the daemon, e.g. an event, can't be decoded. The following messages
+    /// may still be valid.
+    #[error("parsing message: {0}")]
+    InvalidMessage(String),
     #[error("{0}")]
     Unspecified(String),
 }
+
+impl Error {
+    /// Build the error matching an unsuccessful response of the Docker Engine API.
+    /// `message` is the `message` field of the JSON error body returned by the daemon.
+    pub(crate) fn from_status(status: StatusCode, message: String) -> Error {
+        return match status {
+            StatusCode::NOT_MODIFIED => Error::NotModified,
+            StatusCode::BAD_REQUEST => Error::BadRequest(message),
+            StatusCode::NOT_FOUND => Error::NotFound(message),
+            StatusCode::CONFLICT => Error::Conflict(message),
+            _ => Error::Server {
+                status,
+                message,
+            },
+        };
+    }
+}
@@ -0,0 +1,262 @@
+use std::{fmt, pin::Pin, time::Duration};
+
+use bytes::{Bytes, BytesMut};
+use futures_util::StreamExt;
+use hyper::{HeaderMap, Method};
+use tokio::io::AsyncWrite;
+
+use crate::{
+    Client, Error,
+    client::upgrade_headers,
+    logs::{LogOutput, LogStream, hijack_connection, response_log_stream},
+    model::{CreateExecOptions, ExecInspectResponse, IdResponse, ResizeExecOptions, StartExecOptions},
+};
+
+/// How long to wait between two checks of the state of an exec instance whose output has ended
+/// but which is still marked as running
+const EXEC_EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How many times the state of an exec instance is checked before giving up waiting for its exit
+/// code, i.e. 10 seconds
+const EXEC_EXIT_MAX_POLLS: u32 = 200;
+
+/// The result of `Client::start_exec`
+pub enum StartExecResults {
+    /// The connection to the daemon has been hijacked to stream the output of the command and
+    /// write to its `stdin`
+    Attached {
+        output: LogStream,
+        input: Pin<Box<dyn AsyncWrite + Send>>,
+    },
+    /// The command was started with `detach`
+    Detached,
+}
+
+impl fmt::Debug for StartExecResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            StartExecResults::Attached {
+                ..
+            } => f.write_str("StartExecResults::Attached"),
+            StartExecResults::Detached => f.write_str("StartExecResults::Detached"),
+        };
+    }
+}
+
+/// The output of a command run to completion with `Client::run_exec`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecOutput {
+    pub exit_code: i64,
+    /// The output of the command. When the command runs with a TTY, `stdout` and `stderr` can't
+    /// be distinguished and all the output is in `stdout`.
+    pub stdout: Bytes,
+    pub stderr: Bytes,
+}
+
+impl Client {
+    /// Create an exec instance, to run a command inside a running container.
+    /// The command is not started until `start_exec` is called.
+    pub async fn create_exec(&self, container_id: &str, options: CreateExecOptions) -> Result<IdResponse, Error> {
+        return self
+            .send_request(
+                Method::POST,
+                &format!("/containers/{container_id}/exec"),
+                None::<()>,
+                Some(options),
+            )
+            .await;
+    }
+
+    /// Start a previously created exec instance.
+    /// Unless `detach` is set, the connection to the daemon is hijacked to interact with the
+    /// command.
+    pub async fn start_exec(&self, id: &str, options: Option<StartExecOptions>) -> Result<StartExecResults, Error> {
+        let options = options.unwrap_or_default();
+        let body =
+            serde_json::to_vec(&options).map_err(|err| Error::Unspecified(format!("encoding body to JSON: {err}")))?;
+
+        if options.detach {
+            self.request(Method::POST, &format!("/exec/{id}/start"), None::<()>, Bytes::from(body))
+                .await?;
+            return Ok(StartExecResults::Detached);
+        }
+
+        let response = self
+            .request_stream(
+                Method::POST,
+                &format!("/exec/{id}/start"),
+                None::<()>,
+                Bytes::from(body).into(),
+                upgrade_headers(),
+            )
+            .await?;
+        let (output, input) = hijack_connection(response).await?;
+
+        return Ok(StartExecResults::Attached {
+            output,
+            input,
+        });
+    }
+
+    /// Resize the TTY session used by an exec instance. This only works if `tty` was specified
+    /// when creating and starting the exec instance.
+    pub async fn resize_exec(&self, id: &str, options: ResizeExecOptions) -> Result<(), Error> {
+        return self
+            .send_request(Method::POST, &format!("/exec/{id}/resize"), Some(options), None::<()>)
+            .await;
+    }
+
+    /// Return low-level information about an exec instance, including its exit code.
+    pub async fn inspect_exec(&self, id: &str) -> Result<ExecInspectResponse, Error> {
+        return self
+            .send_request(Method::GET, &format!("/exec/{id}/json"), None::<()>, None::<()>)
+            .await;
+    }
+
+    /// Run a command inside a running container until it exits, e.g. for health probes, and return
+    /// its exit code and output.
+    pub async fn run_exec(&self, container_id: &str, options: CreateExecOptions) -> Result<ExecOutput, Error> {
+        let tty = options.tty.unwrap_or(false);
+        let options = CreateExecOptions {
+            attach_stdin: Some(false),
+            attach_stdout: Some(options.attach_stdout.unwrap_or(true)),
+            attach_stderr: Some(options.attach_stderr.unwrap_or(true)),
+            ..options
+        };
+        let exec = self.create_exec(container_id, options).await?;
+
+        let start_options = StartExecOptions {
+            detach: false,
+            tty,
+            console_size: None,
+        };
+        let body = serde_json::to_vec(&start_options)
+            .map_err(|err| Error::Unspecified(format!("encoding body to JSON: {err}")))?;
+        let response = self
+            .request_stream(
+                Method::POST,
+                &format!("/exec/{}/start", exec.id),
+                None::<()>,
+                Bytes::from(body).into(),
+                HeaderMap::new(),
+            )
+            .await?;
+
+        let mut stdout = BytesMut::new();
+        let mut stderr = BytesMut::new();
+        let mut output = response_log_stream(response);
+        while let Some(chunk) = output.next().await {
+            match chunk? {
+                LogOutput::StdErr {
+                    message,
+                } => stderr.extend_from_slice(&message),
+                other => stdout.extend_from_slice(other.message()),
+            }
+        }
+
+        for _ in 0..EXEC_EXIT_MAX_POLLS {
+            let inspect = self.inspect_exec(&exec.id).await?;
+            match (inspect.running, inspect.exit_code) {
+                (_, Some(exit_code)) if inspect.running != Some(true) => {
+                    return Ok(ExecOutput {
+                        exit_code,
+                        stdout: stdout.freeze(),
+                        stderr: stderr.freeze(),
+                    });
+                }
+                (Some(false), None) => {
+                    return Err(Error::Unspecified(format!("exec {} stopped without an exit code", exec.id)));
+                }
+                _ => tokio::time::sleep(EXEC_EXIT_POLL_INTERVAL).await,
+            }
+        }
+
+        return Err(Error::Unspecified(format!(
+            "exec {}: timed out waiting for the exit code of the command",
+            exec.id
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{logs::frame, mock_daemon::MockDaemon};
+
+    fn add_exec_responses(daemon: &MockDaemon, id: &str) {
+        daemon.add_response(Method::POST, "/containers/web/exec", StatusCode::CREATED, &json!({ "Id": id }));
+        let output = [frame(1, b"ready\n"), frame(2, b"warning\n")].concat();
+        daemon.add_raw_response(
+            Method::POST,
+            &format!("/exec/{id}/start"),
+            StatusCode::OK,
+            "application/vnd.docker.multiplexed-stream",
+            Bytes::from(output),
+        );
+    }
+
+    #[tokio::test]
+    async fn run_exec_until_exit() {
+        let daemon = MockDaemon::start().await.unwrap();
+        add_exec_responses(&daemon, "exec1");
+        // the output ends before the daemon notices that the command exited
+        daemon.add_response(Method::GET, "/exec/exec1/json", StatusCode::OK, &json!({ "Running": true }));
+        daemon.add_response(
+            Method::GET,
+            "/exec/exec1/json",
+            StatusCode::OK,
+            &json!({ "Running": false, "ExitCode": 3 }),
+        );
+
+        let output = daemon
+            .client()
+            .run_exec(
+                "web",
+                CreateExecOptions {
+                    cmd: Some(vec!["healthcheck".to_string()]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            output,
+            ExecOutput {
+                exit_code: 3,
+                stdout: Bytes::from_static(b"ready\n"),
+                stderr: Bytes::from_static(b"warning\n"),
+            }
+        );
+
+        let requests = daemon.requests();
+        let paths: Vec<&str> = requests.iter().map(|request| request.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/_ping",
+                "/containers/web/exec",
+                "/exec/exec1/start",
+                "/exec/exec1/json",
+                "/exec/exec1/json"
+            ]
+        );
+        let create: CreateExecOptions = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(create.attach_stdin, Some(false));
+        assert_eq!(create.attach_stdout, Some(true));
+        assert_eq!(create.cmd, Some(vec!["healthcheck".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn run_exec_without_exit_code() {
+        let daemon = MockDaemon::start().await.unwrap();
+        add_exec_responses(&daemon, "exec2");
+        daemon.add_response(Method::GET, "/exec/exec2/json", StatusCode::OK, &json!({ "Running": false }));
+
+        let result = daemon.client().run_exec("web", CreateExecOptions::default()).await;
+        assert!(matches!(result, Err(Error::Unspecified(message)) if message.contains("without an exit code")));
+        assert_eq!(daemon.requests().len(), 4);
+    }
+}
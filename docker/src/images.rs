@@ -0,0 +1,233 @@
+use std::{collections::HashMap, fmt::Display};
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use hyper::{
+    HeaderMap, Method,
+    body::Frame,
+    header::{CONTENT_TYPE, HeaderName, HeaderValue},
+};
+use hyper_utils::http_body_util::StreamBody;
+use serde::Serialize;
+
+use crate::{
+    Client, Error,
+    client::RequestBody,
+    json_stream::{JsonStream, response_json_stream},
+    model::{
+        BuildImageOptions, BuildInfo, CreateImageInfo, CreateImageOptions, DockerCredentials, ErrorDetail,
+        ImageDeleteResponseItem, ImageInspect, ImageSummary, ListImagesOptions, RemoveImageOptions,
+    },
+};
+
+const X_REGISTRY_AUTH: HeaderName = HeaderName::from_static("x-registry-auth");
+const X_REGISTRY_CONFIG: HeaderName = HeaderName::from_static("x-registry-config");
+
+impl Client {
+    /// Pull an image from a registry (or import it).
+    /// The returned stream yields the progress of the pull, and an error if the pull fails.
+    pub async fn create_image(
+        &self,
+        options: Option<CreateImageOptions>,
+        credentials: Option<DockerCredentials>,
+    ) -> Result<JsonStream<'static, CreateImageInfo>, Error> {
+        let mut headers = HeaderMap::new();
+        if let Some(credentials) = credentials {
+            headers.insert(X_REGISTRY_AUTH, encode_header(&credentials)?);
+        }
+
+        let response = self
+            .request_stream(Method::POST, "/images/create", options, Bytes::new().into(), headers)
+            .await?;
+
+        let progress = response_json_stream::<CreateImageInfo>(response)
+            .map(|info| info.and_then(|info| check_error_detail(info.error_detail.as_ref()).map(|_| info)));
+        return Ok(Box::pin(progress));
+    }
+
+    pub async fn list_images(&self, options: Option<ListImagesOptions>) -> Result<Vec<ImageSummary>, Error> {
+        return self
+            .send_request(Method::GET, "/images/json", options, None::<()>)
+            .await;
+    }
+
+    /// Return low-level information about an image.
+    pub async fn inspect_image(&self, name: &str) -> Result<ImageInspect, Error> {
+        return self
+            .send_request(Method::GET, &format!("/images/{name}/json"), None::<()>, None::<()>)
+            .await;
+    }
+
+    /// Remove an image, along with any untagged parent images that were referenced by that image.
+    pub async fn remove_image(
+        &self,
+        name: &str,
+        options: Option<RemoveImageOptions>,
+    ) -> Result<Vec<ImageDeleteResponseItem>, Error> {
+        return self
+            .send_request(Method::DELETE, &format!("/images/{name}"), options, None::<()>)
+            .await;
+    }
+
+    /// Build an image from a tar archive of the build context, containing the Dockerfile.
+    /// `credentials` are used to pull the base images and are indexed by registry address.
+    /// The returned stream yields the output of the build, and an error if the build fails.
+    pub async fn build_image<S, E>(
+        &self,
+        options: Option<BuildImageOptions>,
+        credentials: Option<HashMap<String, DockerCredentials>>,
+        context: S,
+    ) -> Result<JsonStream<'static, BuildInfo>, Error>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Display,
+    {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/x-tar"));
+        if let Some(credentials) = credentials {
+            headers.insert(X_REGISTRY_CONFIG, encode_header(&credentials)?);
+        }
+
+        let context = context.map(|chunk| {
+            chunk
+                .map(Frame::data)
+                .map_err(|err| Error::Unspecified(format!("reading build context: {err}")))
+        });
+        let body = RequestBody::Stream(StreamBody::new(Box::pin(context)));
+
+        let response = self
+            .request_stream(Method::POST, "/build", options, body, headers)
+            .await?;
+
+        let output = response_json_stream::<BuildInfo>(response)
+            .map(|info| info.and_then(|info| check_error_detail(info.error_detail.as_ref()).map(|_| info)));
+        return Ok(Box::pin(output));
+    }
+}
+
+/// Encode the value of the `X-Registry-Auth` and `X-Registry-Config` headers, which are
+/// base64url-encoded JSON
+fn encode_header<T: Serialize>(value: &T) -> Result<HeaderValue, Error> {
+    let json = serde_json::to_vec(value)
+        .map_err(|err| Error::Unspecified(format!("encoding registry credentials to JSON: {err}")))?;
+    let encoded = base64::encode_with_alphabet(&json, base64::Alphabet::Url);
+
+    return HeaderValue::try_from(encoded)
+        .map_err(|err| Error::Unspecified(format!("encoding registry credentials: {err}")));
+}
+
+/// Pulls and builds report their errors in the progress stream, as the status of the response
+/// has already been sent when they happen.
+fn check_error_detail(error_detail: Option<&ErrorDetail>) -> Result<(), Error> {
+    return match error_detail {
+        Some(error_detail) => Err(Error::Unspecified(
+            error_detail
+                .message
+                .clone()
+                .unwrap_or_else(|| "unknown error".to_string()),
+        )),
+        None => Ok(()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+    use hyper::StatusCode;
+
+    use super::*;
+    use crate::mock_daemon::MockDaemon;
+
+    fn decode_header(value: &HeaderValue) -> serde_json::Value {
+        let json = base64::decode_with_alphabet(value.as_bytes(), base64::Alphabet::Url).unwrap();
+        return serde_json::from_slice(&json).unwrap();
+    }
+
+    #[tokio::test]
+    async fn pull_image_with_credentials() {
+        let daemon = MockDaemon::start().await.unwrap();
+        let progress = concat!(
+            "{\"status\":\"Pulling from library/nginx\",\"id\":\"latest\"}\n",
+            "{\"errorDetail\":{\"message\":\"manifest unknown\"},\"error\":\"manifest unknown\"}\n",
+        );
+        daemon.add_raw_response(
+            Method::POST,
+            "/images/create",
+            StatusCode::OK,
+            "application/json",
+            Bytes::from_static(progress.as_bytes()),
+        );
+        let client = daemon.client();
+
+        let options = CreateImageOptions {
+            from_image: Some("registry.example.com/nginx".to_string()),
+            ..Default::default()
+        };
+        let credentials = DockerCredentials {
+            username: Some("jane".to_string()),
+            password: Some("secret?".to_string()),
+            serveraddress: Some("registry.example.com".to_string()),
+            ..Default::default()
+        };
+        let mut progress = client.create_image(Some(options), Some(credentials)).await.unwrap();
+        let info = progress.next().await.unwrap().unwrap();
+        assert_eq!(info.status.as_deref(), Some("Pulling from library/nginx"));
+        assert!(
+            matches!(progress.next().await, Some(Err(Error::Unspecified(message))) if message == "manifest unknown")
+        );
+
+        let request = daemon.requests().pop().unwrap();
+        assert_eq!(request.path, "/images/create");
+        assert_eq!(request.query["fromImage"], "registry.example.com/nginx");
+        assert_eq!(
+            decode_header(&request.headers[X_REGISTRY_AUTH]),
+            serde_json::json!({ "username": "jane", "password": "secret?", "serveraddress": "registry.example.com" })
+        );
+    }
+
+    #[tokio::test]
+    async fn build_image_from_tar_context() {
+        let daemon = MockDaemon::start().await.unwrap();
+        daemon.add_raw_response(
+            Method::POST,
+            "/build",
+            StatusCode::OK,
+            "application/json",
+            Bytes::from_static(b"{\"stream\":\"Step 1/1 : FROM scratch\\n\"}\n"),
+        );
+        let client = daemon.client();
+
+        let context = stream::iter([
+            Ok::<_, std::io::Error>(Bytes::from_static(b"first chunk of the archive, ")),
+            Ok(Bytes::from_static(b"second chunk")),
+        ]);
+        let options = BuildImageOptions {
+            t: Some("app:latest".to_string()),
+            ..Default::default()
+        };
+        let credentials = HashMap::from([(
+            "registry.example.com".to_string(),
+            DockerCredentials {
+                identitytoken: Some("token".to_string()),
+                ..Default::default()
+            },
+        )]);
+        let mut output = client
+            .build_image(Some(options), Some(credentials), context)
+            .await
+            .unwrap();
+        let info = output.next().await.unwrap().unwrap();
+        assert_eq!(info.stream.as_deref(), Some("Step 1/1 : FROM scratch\n"));
+        assert!(output.next().await.is_none());
+
+        let request = daemon.requests().pop().unwrap();
+        assert_eq!((request.method, request.path.as_str()), (Method::POST, "/build"));
+        assert_eq!(request.query["t"], "app:latest");
+        assert_eq!(request.headers[CONTENT_TYPE], "application/x-tar");
+        assert_eq!(request.body, Bytes::from_static(b"first chunk of the archive, second chunk"));
+        assert_eq!(
+            decode_header(&request.headers[X_REGISTRY_CONFIG]),
+            serde_json::json!({ "registry.example.com": { "identitytoken": "token" } })
+        );
+    }
+}
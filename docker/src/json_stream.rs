@@ -0,0 +1,76 @@
+use std::pin::Pin;
+
+use bytes::{Bytes, BytesMut};
+use futures_util::{Stream, StreamExt, stream};
+use hyper::body::Incoming;
+use hyper_utils::http_body_util::BodyDataStream;
+use serde::de::DeserializeOwned;
+
+use crate::Error;
+
+/// A stream of JSON messages, such as events or the progress of an image pull
+pub type JsonStream<'a, T> = Pin<Box<dyn Stream<Item = Result<T, Error>> + Send + 'a>>;
+
+/// Decode the JSON messages streamed in the body of a response.
+pub(crate) fn response_json_stream<T: DeserializeOwned + Send + 'static>(
+    response: hyper::Response<Incoming>,
+) -> JsonStream<'static, T> {
+    let chunks = BodyDataStream::new(response.into_body())
+        .map(|chunk| chunk.map_err(|err| Error::Unspecified(format!("reading response: {err}"))));
+    return decode_json_stream(chunks);
+}
+
+struct Decoder<S> {
+    chunks: Pin<Box<S>>,
+    buffer: BytesMut,
+    done: bool,
+}
+
+/// Decode a stream of raw chunks of bytes containing newline-delimited JSON messages.
+fn decode_json_stream<'a, T, S>(chunks: S) -> JsonStream<'a, T>
+where
+    T: DeserializeOwned + Send + 'a,
+    S: Stream<Item = Result<Bytes, Error>> + Send + 'a,
+{
+    let decoder = Decoder {
+        chunks: Box::pin(chunks),
+        buffer: BytesMut::new(),
+        done: false,
+    };
+
+    return Box::pin(stream::unfold(decoder, |mut decoder| async move {
+        if decoder.done {
+            return None;
+        }
+
+        loop {
+            if let Some(newline) = decoder.buffer.iter().position(|b| *b == b'\n') {
+                let line = decoder.buffer.split_to(newline + 1);
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                return Some((parse_message(&line), decoder));
+            }
+
+            match decoder.chunks.next().await {
+                Some(Ok(chunk)) => decoder.buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    decoder.done = true;
+                    return Some((Err(err), decoder));
+                }
+                None => {
+                    decoder.done = true;
+                    if decoder.buffer.trim_ascii().is_empty() {
+                        return None;
+                    }
+                    let message = parse_message(&decoder.buffer);
+                    return Some((message, decoder));
+                }
+            }
+        }
+    }));
+}
+
+fn parse_message<T: DeserializeOwned>(line: &[u8]) -> Result<T, Error> {
+    return serde_json::from_slice(line).map_err(|err| Error::InvalidMessage(err.to_string()));
+}
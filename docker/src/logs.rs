@@ -0,0 +1,325 @@
+use std::{fmt, pin::Pin};
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures_util::{Stream, StreamExt, stream};
+use hyper::{HeaderMap, body::Incoming, header::CONTENT_TYPE};
+use hyper_utils::{http_body_util::BodyDataStream, rt::TokioIo};
+use tokio::io::{AsyncReadExt, AsyncWrite};
+
+use crate::Error;
+
+/// A stream of the output of a container or an exec instance
+pub type LogStream = Pin<Box<dyn Stream<Item = Result<LogOutput, Error>> + Send>>;
+
+/// A chunk of output of a container.
+/// When the container doesn't have a TTY, the daemon multiplexes `stdout` and `stderr` on the
+/// same connection and each chunk tells which stream it comes from. Otherwise, the raw output of
+/// the TTY is returned as `Console`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogOutput {
+    StdIn { message: Bytes },
+    StdOut { message: Bytes },
+    StdErr { message: Bytes },
+    Console { message: Bytes },
+}
+
+impl LogOutput {
+    pub fn message(&self) -> &Bytes {
+        return match self {
+            LogOutput::StdIn {
+                message,
+            } => message,
+            LogOutput::StdOut {
+                message,
+            } => message,
+            LogOutput::StdErr {
+                message,
+            } => message,
+            LogOutput::Console {
+                message,
+            } => message,
+        };
+    }
+
+    pub fn into_bytes(self) -> Bytes {
+        return match self {
+            LogOutput::StdIn {
+                message,
+            } => message,
+            LogOutput::StdOut {
+                message,
+            } => message,
+            LogOutput::StdErr {
+                message,
+            } => message,
+            LogOutput::Console {
+                message,
+            } => message,
+        };
+    }
+}
+
+impl fmt::Display for LogOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", String::from_utf8_lossy(self.message()));
+    }
+}
+
+/// The streams of a container attached with `Client::attach_container`
+pub struct AttachContainerResults {
+    /// The output of the container
+    pub output: LogStream,
+    /// Write to the container's `stdin`
+    pub input: Pin<Box<dyn AsyncWrite + Send>>,
+}
+
+impl fmt::Debug for AttachContainerResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str("AttachContainerResults");
+    }
+}
+
+const MULTIPLEXED_STREAM_CONTENT_TYPE: &str = "application/vnd.docker.multiplexed-stream";
+const RAW_STREAM_CONTENT_TYPE: &str = "application/vnd.docker.raw-stream";
+
+const HIJACKED_READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// The size of the header of each frame of a multiplexed stream:
+/// `[STREAM_TYPE, 0, 0, 0, SIZE1, SIZE2, SIZE3, SIZE4]`, with the size encoded as big endian.
+const FRAME_HEADER_SIZE: usize = 8;
+
+/// The maximum size of a frame of a multiplexed stream, so that a corrupted header can't make
+/// the decoder buffer up to 4 GiB.
+const MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StreamFormat {
+    /// `stdout` and `stderr` framed with a header, used for containers without a TTY
+    Multiplexed,
+    /// The raw output of a TTY
+    Raw,
+    /// Older versions of the API don't tell the format of the stream, in which case we guess it
+    /// from the first bytes of the stream.
+    Unknown,
+}
+
+impl StreamFormat {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> StreamFormat {
+        let content_type = headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok());
+        return match content_type {
+            Some(content_type) if content_type.starts_with(MULTIPLEXED_STREAM_CONTENT_TYPE) => {
+                StreamFormat::Multiplexed
+            }
+            Some(content_type) if content_type.starts_with(RAW_STREAM_CONTENT_TYPE) => StreamFormat::Raw,
+            _ => StreamFormat::Unknown,
+        };
+    }
+}
+
+/// Decode the output of a container streamed in the body of a response.
+pub(crate) fn response_log_stream(response: hyper::Response<Incoming>) -> LogStream {
+    let format = StreamFormat::from_headers(response.headers());
+    let chunks = BodyDataStream::new(response.into_body())
+        .map(|chunk| chunk.map_err(|err| Error::Unspecified(format!("reading output: {err}"))));
+
+    return decode_log_stream(chunks, format);
+}
+
+/// Take over the connection of a response to a request sent with `upgrade_headers`, to read
+/// the output of a container and write to its `stdin`.
+pub(crate) async fn hijack_connection(
+    response: hyper::Response<Incoming>,
+) -> Result<(LogStream, Pin<Box<dyn AsyncWrite + Send>>), Error> {
+    let format = StreamFormat::from_headers(response.headers());
+    let upgraded = hyper::upgrade::on(response)
+        .await
+        .map_err(|err| Error::Unspecified(format!("upgrading connection: {err}")))?;
+    let (reader, writer) = tokio::io::split(TokioIo::new(upgraded));
+
+    let chunks = stream::unfold(reader, |mut reader| async move {
+        let mut buffer = BytesMut::with_capacity(HIJACKED_READ_BUFFER_SIZE);
+        return match reader.read_buf(&mut buffer).await {
+            Ok(0) => None,
+            Ok(_) => Some((Ok(buffer.freeze()), reader)),
+            Err(err) => Some((Err(Error::Unspecified(format!("reading output: {err}"))), reader)),
+        };
+    });
+
+    return Ok((decode_log_stream(chunks, format), Box::pin(writer)));
+}
+
+struct Decoder<S> {
+    chunks: Pin<Box<S>>,
+    buffer: BytesMut,
+    format: StreamFormat,
+    done: bool,
+}
+
+/// Decode a stream of raw chunks of bytes into a stream of `LogOutput`.
+pub(crate) fn decode_log_stream<S>(chunks: S, format: StreamFormat) -> LogStream
+where
+    S: Stream<Item = Result<Bytes, Error>> + Send + 'static,
+{
+    let decoder = Decoder {
+        chunks: Box::pin(chunks),
+        buffer: BytesMut::new(),
+        format,
+        done: false,
+    };
+
+    return Box::pin(stream::unfold(decoder, |mut decoder| async move {
+        if decoder.done {
+            return None;
+        }
+
+        loop {
+            match decode_frame(&mut decoder.buffer, &mut decoder.format) {
+                Ok(Some(output)) => return Some((Ok(output), decoder)),
+                Ok(None) => {}
+                Err(err) => {
+                    decoder.done = true;
+                    return Some((Err(err), decoder));
+                }
+            }
+
+            match decoder.chunks.next().await {
+                Some(Ok(chunk)) => decoder.buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    decoder.done = true;
+                    return Some((Err(err), decoder));
+                }
+                None => {
+                    decoder.done = true;
+                    if decoder.buffer.is_empty() {
+                        return None;
+                    }
+                    let err = Error::Unspecified("stream ended in the middle of a frame".to_string());
+                    return Some((Err(err), decoder));
+                }
+            }
+        }
+    }));
+}
+
+/// Decode the next frame from `buffer`, or return `None` if more data is needed.
+fn decode_frame(buffer: &mut BytesMut, format: &mut StreamFormat) -> Result<Option<LogOutput>, Error> {
+    if buffer.is_empty() {
+        return Ok(None);
+    }
+
+    if *format == StreamFormat::Unknown {
+        // the first byte of a multiplexed stream is the stream type: 0 (stdin), 1 (stdout) or
+        // 2 (stderr), which are not printable characters and are thus very unlikely to be the
+        // first byte of the output of a TTY.
+        *format = if buffer[0] <= 2 {
+            StreamFormat::Multiplexed
+        } else {
+            StreamFormat::Raw
+        };
+    }
+
+    if *format == StreamFormat::Raw {
+        return Ok(Some(LogOutput::Console {
+            message: buffer.split().freeze(),
+        }));
+    }
+
+    if buffer.len() < FRAME_HEADER_SIZE {
+        return Ok(None);
+    }
+    let size = u32::from_be_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]) as usize;
+    if size > MAX_FRAME_SIZE {
+        return Err(Error::Unspecified(format!(
+            "frame of {size} bytes exceeds the maximum of {MAX_FRAME_SIZE} bytes"
+        )));
+    }
+    if buffer.len() < FRAME_HEADER_SIZE + size {
+        return Ok(None);
+    }
+
+    let stream_type = buffer[0];
+    buffer.advance(FRAME_HEADER_SIZE);
+    let message = buffer.split_to(size).freeze();
+
+    return Ok(Some(match stream_type {
+        0 => LogOutput::StdIn {
+            message,
+        },
+        1 => LogOutput::StdOut {
+            message,
+        },
+        2 => LogOutput::StdErr {
+            message,
+        },
+        _ => LogOutput::Console {
+            message,
+        },
+    }));
+}
+
+/// Encode a frame of a multiplexed output stream
+#[cfg(test)]
+pub(crate) fn frame(stream_type: u8, message: &[u8]) -> Vec<u8> {
+    let mut frame = vec![stream_type, 0, 0, 0];
+    frame.extend_from_slice(&(message.len() as u32).to_be_bytes());
+    frame.extend_from_slice(message);
+    return frame;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn decode(chunks: Vec<Vec<u8>>, format: StreamFormat) -> Vec<Result<LogOutput, Error>> {
+        let chunks = stream::iter(chunks.into_iter().map(|chunk| Ok(Bytes::from(chunk))));
+        return decode_log_stream(chunks, format).collect().await;
+    }
+
+    #[tokio::test]
+    async fn multiplexed_frames_split_across_chunks() {
+        let mut data = frame(1, b"hello\n");
+        data.extend(frame(2, b"oops\n"));
+        let chunks = data.chunks(3).map(|chunk| chunk.to_vec()).collect();
+
+        let output = decode(chunks, StreamFormat::Multiplexed).await;
+        let output: Vec<LogOutput> = output.into_iter().map(|res| res.unwrap()).collect();
+        assert_eq!(
+            output,
+            vec![
+                LogOutput::StdOut {
+                    message: Bytes::from_static(b"hello\n")
+                },
+                LogOutput::StdErr {
+                    message: Bytes::from_static(b"oops\n")
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn guess_stream_format() {
+        let output = decode(vec![frame(1, b"hello")], StreamFormat::Unknown).await;
+        assert!(matches!(output[..], [Ok(LogOutput::StdOut { .. })]));
+
+        let output = decode(vec![b"$ ".to_vec()], StreamFormat::Unknown).await;
+        assert!(matches!(output[..], [Ok(LogOutput::Console { .. })]));
+    }
+
+    #[tokio::test]
+    async fn truncated_frame() {
+        let mut data = frame(1, b"hello");
+        data.truncate(10);
+
+        let output = decode(vec![data], StreamFormat::Multiplexed).await;
+        assert!(matches!(output[..], [Err(Error::Unspecified(_))]));
+    }
+
+    #[tokio::test]
+    async fn oversized_frame() {
+        let mut data = frame(1, b"hello");
+        data.extend([1, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+
+        let output = decode(vec![data, vec![0; 1024]], StreamFormat::Multiplexed).await;
+        assert!(matches!(output[..], [Ok(LogOutput::StdOut { .. }), Err(Error::Unspecified(_))]));
+    }
+}
@@ -4,7 +4,7 @@ use docker::{Client, model::ListContainersOptions};
 
 #[tokio::main]
 async fn main() {
-    let client = Client::new(None);
+    let client = Client::from_env().unwrap();
 
     let mut filters = HashMap::new();
     filters.insert("label".to_string(), vec!["pingoo.service=test".to_string()]);
@@ -0,0 +1,623 @@
+//! A fake Docker daemon for offline tests.
+//!
+//! The [`MockDaemon`] serves a subset of the Docker Engine API over a Unix socket in a temporary
+//! directory: `/_ping`, `/version`, `/containers/json`, `/containers/{id}/json` and `/events`.
+//! Containers and events are canned data provided by the test, and every request, with its headers
+//! and body, is recorded so tests can assert on what their code sent. Other endpoints answer `404 Not Found`, unless a
+//! response was set for them with [`MockDaemon::add_response`].
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), docker::Error> {
+//! use docker::{mock_daemon::MockDaemon, model::ContainerSummary};
+//!
+//! let daemon = MockDaemon::start().await?;
+//! daemon.add_container(ContainerSummary {
+//!     id: Some("abc".to_string()),
+//!     state: Some("running".to_string()),
+//!     ..Default::default()
+//! });
+//!
+//! let containers = daemon.client().list_containers(None).await?;
+//! assert_eq!(containers.len(), 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt, stream};
+use hyper::{
+    HeaderMap, Method, Request, Response, StatusCode,
+    body::{Frame, Incoming},
+    header::CONTENT_TYPE,
+    server::conn::http1,
+    service::service_fn,
+};
+use hyper_utils::{
+    http_body_util::{BodyExt, StreamBody},
+    rt::TokioIo,
+};
+use serde::Serialize;
+use serde_json::json;
+use tokio::{net::UnixListener, sync::broadcast, task::JoinHandle};
+
+use crate::{
+    Client, Error,
+    model::{
+        ContainerConfig, ContainerInspectResponse, ContainerState, ContainerSummary, EventMessage, NetworkSettings,
+    },
+};
+
+/// The version of the API reported by the mock daemon
+pub const API_VERSION: &str = "1.47";
+/// How long to wait before accepting connections again after an error
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+static NEXT_SOCKET_ID: AtomicUsize = AtomicUsize::new(0);
+
+type ResponseBody = StreamBody<Pin<Box<dyn Stream<Item = Result<Frame<Bytes>, Infallible>> + Send>>>;
+
+/// A request received by the mock daemon
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: Method,
+    /// The path of the request, without the API version prefix, e.g. `/containers/json`
+    pub path: String,
+    /// The decoded query parameters
+    pub query: HashMap<String, String>,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+/// A fake Docker daemon listening on a Unix socket in a temporary directory
+///
+/// The daemon stops and the socket is removed when the `MockDaemon` is dropped.
+pub struct MockDaemon {
+    directory: PathBuf,
+    socket_path: PathBuf,
+    state: Arc<Mutex<State>>,
+    task: JoinHandle<()>,
+}
+
+struct State {
+    containers: Vec<ContainerSummary>,
+    events: Vec<EventMessage>,
+    events_sender: broadcast::Sender<EventMessage>,
+    requests: Vec<RecordedRequest>,
+    /// The responses set by the test, by method and path
+    responses: HashMap<(Method, String), VecDeque<CannedResponse>>,
+    /// The tasks serving the connections accepted by the daemon
+    connections: Vec<JoinHandle<()>>,
+}
+
+#[derive(Clone)]
+struct CannedResponse {
+    status: StatusCode,
+    content_type: String,
+    body: Bytes,
+}
+
+impl MockDaemon {
+    /// Start a new daemon listening on a Unix socket in a new temporary directory
+    pub async fn start() -> Result<MockDaemon, Error> {
+        let directory = std::env::temp_dir().join(format!(
+            "docker-mock-daemon-{}-{}",
+            std::process::id(),
+            NEXT_SOCKET_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&directory).map_err(|err| Error::Connecting(err.into()))?;
+        let socket_path = directory.join("docker.sock");
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = UnixListener::bind(&socket_path).map_err(|err| Error::Connecting(err.into()))?;
+
+        let (events_sender, _) = broadcast::channel(64);
+        let state = Arc::new(Mutex::new(State {
+            containers: Vec::new(),
+            events: Vec::new(),
+            events_sender,
+            requests: Vec::new(),
+            responses: HashMap::new(),
+            connections: Vec::new(),
+        }));
+
+        let server_state = state.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    // e.g. too many open files: wait for connections to be closed instead of
+                    // spinning on the error
+                    Err(_) => {
+                        tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+                        continue;
+                    }
+                };
+
+                let state = server_state.clone();
+                let connection = tokio::spawn(async move {
+                    let service = service_fn(move |req| handle(state.clone(), req));
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+                lock(&server_state).connections.push(connection);
+            }
+        });
+
+        return Ok(MockDaemon {
+            directory,
+            socket_path,
+            state,
+            task,
+        });
+    }
+
+    /// Get the path of the Unix socket the daemon listens on
+    pub fn socket_path(&self) -> &Path {
+        return &self.socket_path;
+    }
+
+    /// Create a client connected to this daemon
+    pub fn client(&self) -> Client {
+        return Client::new(Some(&self.socket_path.to_string_lossy()));
+    }
+
+    /// Add a container to the containers served by the daemon
+    pub fn add_container(&self, container: ContainerSummary) {
+        self.state().containers.push(container);
+    }
+
+    /// Replace the containers served by the daemon
+    pub fn set_containers(&self, containers: Vec<ContainerSummary>) {
+        self.state().containers = containers;
+    }
+
+    /// Publish an event to the clients streaming `/events`. The event is also replayed to the
+    /// clients connecting later with a `since` timestamp anterior to the event.
+    /// `time` and `timeNano` are set to the current time if missing.
+    pub fn push_event(&self, mut event: EventMessage) {
+        if event.time_nano.is_none() {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            event.time_nano = Some(now.as_nanos() as i64);
+        }
+        if event.time.is_none() {
+            event.time = event.time_nano.map(|time_nano| time_nano / 1_000_000_000);
+        }
+
+        let mut state = self.state();
+        state.events.push(event.clone());
+        // there may be no client listening
+        let _ = state.events_sender.send(event);
+    }
+
+    /// Answer the requests to `path` (without the API version prefix, e.g. `/images/json`) with
+    /// `status` and `body` encoded to JSON, instead of the default response of the daemon.
+    /// The responses added for the same endpoint are served in order, and the last one is repeated.
+    pub fn add_response<T: Serialize>(&self, method: Method, path: &str, status: StatusCode, body: &T) {
+        let body = serde_json::to_vec(body).unwrap_or_default();
+        self.add_raw_response(method, path, status, "application/json", Bytes::from(body));
+    }
+
+    /// Like [`MockDaemon::add_response`], with a body that is not JSON, e.g. the multiplexed
+    /// output of an exec instance
+    pub fn add_raw_response(&self, method: Method, path: &str, status: StatusCode, content_type: &str, body: Bytes) {
+        self.state()
+            .responses
+            .entry((method, path.to_string()))
+            .or_default()
+            .push_back(CannedResponse {
+                status,
+                content_type: content_type.to_string(),
+                body,
+            });
+    }
+
+    /// Get the requests received by the daemon, in order
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        return self.state().requests.clone();
+    }
+
+    /// Get the number of connections accepted by the daemon
+    pub fn connection_count(&self) -> usize {
+        return self.state().connections.len();
+    }
+
+    /// Close all the open connections, without notifying the clients, like a daemon that
+    /// restarted or dropped idle connections
+    pub fn close_connections(&self) {
+        for connection in &self.state().connections {
+            connection.abort();
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        return lock(&self.state);
+    }
+}
+
+impl Drop for MockDaemon {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.close_connections();
+        let _ = std::fs::remove_dir_all(&self.directory);
+    }
+}
+
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    return state.lock().unwrap_or_else(|err| err.into_inner());
+}
+
+async fn handle(state: Arc<Mutex<State>>, req: Request<Incoming>) -> Result<Response<ResponseBody>, Infallible> {
+    let method = req.method().clone();
+    let path = strip_version(req.uri().path()).to_string();
+    let query: HashMap<String, String> =
+        serde_urlencoded::from_str(req.uri().query().unwrap_or_default()).unwrap_or_default();
+    let headers = req.headers().clone();
+    let body = match req.into_body().collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(_) => Bytes::new(),
+    };
+
+    let canned_response = {
+        let mut state = lock(&state);
+        state.requests.push(RecordedRequest {
+            method: method.clone(),
+            path: path.clone(),
+            query: query.clone(),
+            headers,
+            body,
+        });
+        state
+            .responses
+            .get_mut(&(method.clone(), path.clone()))
+            .and_then(|responses| {
+                if responses.len() > 1 {
+                    responses.pop_front()
+                } else {
+                    responses.front().cloned()
+                }
+            })
+    };
+    if let Some(canned_response) = canned_response {
+        return Ok(full_response(
+            canned_response.status,
+            &canned_response.content_type,
+            canned_response.body,
+        ));
+    }
+
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let response = match (&method, segments.as_slice()) {
+        (&Method::GET | &Method::HEAD, ["_ping"]) => {
+            let mut response = full_response(StatusCode::OK, "text/plain", Bytes::from_static(b"OK"));
+            response
+                .headers_mut()
+                .insert("api-version", API_VERSION.parse().unwrap());
+            response
+        }
+        (&Method::GET, ["version"]) => json_response(
+            StatusCode::OK,
+            &json!({ "Version": "mock", "ApiVersion": API_VERSION, "MinAPIVersion": "1.24", "Os": "linux" }),
+        ),
+        (&Method::GET, ["containers", "json"]) => list_containers(&state, &query),
+        (&Method::GET, ["containers", id, "json"]) => inspect_container(&state, id),
+        (&Method::GET, ["events"]) => events(&state, &query),
+        _ => error_response(StatusCode::NOT_FOUND, "page not found"),
+    };
+
+    return Ok(response);
+}
+
+/// Remove the `/v1.xx` prefix of versioned paths
+fn strip_version(path: &str) -> &str {
+    let Some(rest) = path.strip_prefix("/v") else {
+        return path;
+    };
+    return match rest.find('/') {
+        Some(index) if rest[..index].split('.').all(|part| part.parse::<u32>().is_ok()) => &rest[index..],
+        _ => path,
+    };
+}
+
+fn list_containers(state: &Mutex<State>, query: &HashMap<String, String>) -> Response<ResponseBody> {
+    let all = query.get("all").is_some_and(|all| all == "true" || all == "1");
+    let filters: HashMap<String, Vec<String>> = match query.get("filters") {
+        Some(filters) => match serde_json::from_str(filters) {
+            Ok(filters) => filters,
+            Err(err) => return error_response(StatusCode::BAD_REQUEST, &format!("invalid filter: {err}")),
+        },
+        None => HashMap::new(),
+    };
+
+    let containers: Vec<ContainerSummary> = lock(state)
+        .containers
+        .iter()
+        .filter(|container| all || container.state.as_deref() == Some("running"))
+        .filter(|container| {
+            filters.iter().all(|(key, values)| {
+                values
+                    .iter()
+                    .all(|value| container_matches_filter(container, key, value))
+            })
+        })
+        .cloned()
+        .collect();
+
+    return json_response(StatusCode::OK, &containers);
+}
+
+fn container_matches_filter(container: &ContainerSummary, key: &str, value: &str) -> bool {
+    return match key {
+        "label" => {
+            let labels = container.labels.as_ref();
+            match value.split_once('=') {
+                Some((label, label_value)) => {
+                    labels.and_then(|labels| labels.get(label)).map(String::as_str) == Some(label_value)
+                }
+                None => labels.is_some_and(|labels| labels.contains_key(value)),
+            }
+        }
+        "id" => container.id.as_deref().is_some_and(|id| id.starts_with(value)),
+        "name" => container
+            .names
+            .iter()
+            .flatten()
+            .any(|name| name.trim_start_matches('/').contains(value.trim_start_matches('/'))),
+        "status" => container.state.as_deref() == Some(value),
+        // unsupported filters don't filter anything out
+        _ => true,
+    };
+}
+
+fn inspect_container(state: &Mutex<State>, id: &str) -> Response<ResponseBody> {
+    let state = lock(state);
+    let container = state.containers.iter().find(|container| {
+        container.id.as_deref() == Some(id)
+            || container
+                .names
+                .iter()
+                .flatten()
+                .any(|name| name.trim_start_matches('/') == id.trim_start_matches('/'))
+    });
+    let Some(container) = container else {
+        return error_response(StatusCode::NOT_FOUND, &format!("No such container: {id}"));
+    };
+
+    let inspect = ContainerInspectResponse {
+        id: container.id.clone(),
+        name: container.names.as_ref().and_then(|names| names.first().cloned()),
+        image: container.image_id.clone(),
+        state: Some(ContainerState {
+            status: container.state.as_deref().and_then(|status| status.parse().ok()),
+            running: Some(container.state.as_deref() == Some("running")),
+            ..Default::default()
+        }),
+        config: Some(ContainerConfig {
+            image: container.image.clone(),
+            labels: container.labels.clone(),
+            ..Default::default()
+        }),
+        network_settings: Some(NetworkSettings {
+            networks: container
+                .network_settings
+                .as_ref()
+                .and_then(|settings| settings.networks.clone()),
+            ..Default::default()
+        }),
+        mounts: container.mounts.clone(),
+        ..Default::default()
+    };
+
+    return json_response(StatusCode::OK, &inspect);
+}
+
+fn events(state: &Mutex<State>, query: &HashMap<String, String>) -> Response<ResponseBody> {
+    let since = query.get("since").and_then(|since| parse_timestamp(since));
+    let until = query.get("until").and_then(|until| parse_timestamp(until));
+    let in_range = move |event: &EventMessage| {
+        let time = event.time_nano.unwrap_or_default();
+        return since.is_none_or(|since| time >= since) && until.is_none_or(|until| time <= until);
+    };
+
+    // subscribe before reading the past events so no event can be missed in between
+    let (past_events, receiver) = {
+        let state = lock(state);
+        let past_events: Vec<EventMessage> = if since.is_some() {
+            state.events.iter().filter(|event| in_range(event)).cloned().collect()
+        } else {
+            Vec::new()
+        };
+        (past_events, state.events_sender.subscribe())
+    };
+
+    let past_events = stream::iter(past_events);
+    let events: Pin<Box<dyn Stream<Item = EventMessage> + Send>> = if until.is_some() {
+        Box::pin(past_events)
+    } else {
+        let live_events = stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        });
+        Box::pin(past_events.chain(live_events.filter(move |event| std::future::ready(in_range(event)))))
+    };
+
+    let body = events.map(|event| {
+        let mut line = serde_json::to_vec(&event).unwrap_or_default();
+        line.push(b'\n');
+        Ok(Frame::data(Bytes::from(line)))
+    });
+
+    return Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/json")
+        .body(StreamBody::new(Box::pin(body) as Pin<Box<dyn Stream<Item = _> + Send>>))
+        .unwrap();
+}
+
+/// Parse a `since` or `until` timestamp (`seconds[.nanoseconds]`) to nanoseconds
+fn parse_timestamp(timestamp: &str) -> Option<i64> {
+    let (seconds, nanoseconds) = timestamp.split_once('.').unwrap_or((timestamp, "0"));
+    let nanoseconds = format!("{nanoseconds:0<9}");
+    return Some(seconds.parse::<i64>().ok()? * 1_000_000_000 + nanoseconds.get(..9)?.parse::<i64>().ok()?);
+}
+
+fn full_response(status: StatusCode, content_type: &str, body: Bytes) -> Response<ResponseBody> {
+    let body = stream::once(async move { Ok(Frame::data(body)) });
+    return Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, content_type)
+        .body(StreamBody::new(Box::pin(body) as Pin<Box<dyn Stream<Item = _> + Send>>))
+        .unwrap();
+}
+
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Response<ResponseBody> {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    return full_response(status, "application/json", Bytes::from(body));
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<ResponseBody> {
+    return json_response(status, &json!({ "message": message }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{EventActor, EventMessageTypeEnum, EventsOptions, ListContainersOptions};
+
+    fn container(id: &str, state: &str, service: Option<&str>) -> ContainerSummary {
+        return ContainerSummary {
+            id: Some(id.to_string()),
+            names: Some(vec![format!("/{id}")]),
+            state: Some(state.to_string()),
+            labels: service.map(|service| HashMap::from([("pingoo.service".to_string(), service.to_string())])),
+            ..Default::default()
+        };
+    }
+
+    fn container_event(id: &str, action: &str) -> EventMessage {
+        return EventMessage {
+            typ: Some(EventMessageTypeEnum::CONTAINER),
+            action: Some(action.to_string()),
+            actor: Some(EventActor {
+                id: Some(id.to_string()),
+                attributes: None,
+            }),
+            ..Default::default()
+        };
+    }
+
+    #[tokio::test]
+    async fn list_and_inspect_containers() {
+        let daemon = MockDaemon::start().await.unwrap();
+        daemon.set_containers(vec![
+            container("web", "running", Some("web")),
+            container("db", "running", None),
+            container("old-web", "exited", Some("web")),
+        ]);
+        let client = daemon.client();
+
+        let options = ListContainersOptions {
+            filters: HashMap::from([("label".to_string(), vec!["pingoo.service=web".to_string()])]),
+            ..Default::default()
+        };
+        let containers = client.list_containers(Some(options.clone())).await.unwrap();
+        assert_eq!(containers, vec![container("web", "running", Some("web"))]);
+
+        let containers = client
+            .list_containers(Some(ListContainersOptions {
+                all: true,
+                ..options
+            }))
+            .await
+            .unwrap();
+        assert_eq!(containers.len(), 2);
+
+        let inspect = client.inspect_container("db", None).await.unwrap();
+        assert_eq!(inspect.state.unwrap().running, Some(true));
+        assert!(matches!(client.inspect_container("nope", None).await, Err(Error::NotFound(_))));
+
+        let requests = daemon.requests();
+        assert_eq!(requests[0].path, "/_ping");
+        assert_eq!(requests[1].path, "/containers/json");
+        assert_eq!(requests[1].query["filters"], r#"{"label":["pingoo.service=web"]}"#);
+        assert_eq!(requests[2].query["all"], "true");
+        assert_eq!(requests[3].path, "/containers/db/json");
+        assert_eq!(client.api_version().await.unwrap(), API_VERSION);
+    }
+
+    #[tokio::test]
+    async fn stream_events() {
+        let daemon = MockDaemon::start().await.unwrap();
+        daemon.push_event(container_event("web", "start"));
+        let client = daemon.client();
+
+        let mut events = client
+            .events(Some(EventsOptions {
+                since: Some("0".to_string()),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        let event = events.next().await.unwrap().unwrap();
+        assert_eq!((event.actor_id(), event.action.as_deref()), (Some("web"), Some("start")));
+
+        daemon.push_event(container_event("web", "die"));
+        let event = events.next().await.unwrap().unwrap();
+        assert_eq!((event.actor_id(), event.action.as_deref()), (Some("web"), Some("die")));
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests() {
+        let daemon = MockDaemon::start().await.unwrap();
+        daemon.add_container(container("web", "running", None));
+        let client = daemon.client();
+
+        // a long-running events stream must not block the other requests
+        let _events = client.events(None).await.unwrap();
+        let (first, second) =
+            tokio::join!(client.inspect_container("web", None), client.inspect_container("web", None));
+        assert_eq!(first.unwrap(), second.unwrap());
+    }
+
+    #[tokio::test]
+    async fn reconnect_after_connection_closed() {
+        let daemon = MockDaemon::start().await.unwrap();
+        daemon.add_container(container("web", "running", None));
+        let client = daemon.client();
+
+        // idle connections are reused
+        client.inspect_container("web", None).await.unwrap();
+        let connections = daemon.connection_count();
+        client.inspect_container("web", None).await.unwrap();
+        assert_eq!(daemon.connection_count(), connections);
+
+        // the idle connections are closed by the daemon: the next request is sent on a new one,
+        // whether the client noticed the connection was closed or got its request canceled
+        daemon.close_connections();
+        let inspect = client.inspect_container("web", None).await.unwrap();
+        assert_eq!(inspect.id.as_deref(), Some("web"));
+        assert_eq!(daemon.connection_count(), connections + 1);
+
+        daemon.close_connections();
+        tokio::task::yield_now().await;
+        client.inspect_container("web", None).await.unwrap();
+        assert_eq!(daemon.connection_count(), connections + 2);
+        assert_eq!(daemon.requests().len(), 5);
+    }
+}
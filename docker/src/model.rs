@@ -374,3 +374,2043 @@ pub struct EndpointIpamConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub link_local_i_ps: Option<Vec<String>>,
 }
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CreateContainerOptions {
+    /// Assign the specified name to the container. Must match `/?[a-zA-Z0-9][a-zA-Z0-9_.-]+`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Platform in the format `os[/arch[/variant]]` used for image lookup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+}
+
+/// Configuration for a container that is portable between hosts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerConfig {
+    /// The hostname to use for the container, as a valid RFC 1123 hostname.
+    #[serde(rename = "Hostname")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+
+    /// The domain name to use for the container.
+    #[serde(rename = "Domainname")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domainname: Option<String>,
+
+    /// The user that commands are run as inside the container.
+    #[serde(rename = "User")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// Whether to attach to `stdin`.
+    #[serde(rename = "AttachStdin")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach_stdin: Option<bool>,
+
+    /// Whether to attach to `stdout`.
+    #[serde(rename = "AttachStdout")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach_stdout: Option<bool>,
+
+    /// Whether to attach to `stderr`.
+    #[serde(rename = "AttachStderr")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach_stderr: Option<bool>,
+
+    /// An object mapping ports to an empty object in the form:  `{\"<port>/<tcp|udp|sctp>\": {}}`
+    #[serde(rename = "ExposedPorts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exposed_ports: Option<HashMap<String, HashMap<(), ()>>>,
+
+    /// Attach standard streams to a TTY, including `stdin` if it is not closed.
+    #[serde(rename = "Tty")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tty: Option<bool>,
+
+    /// Open `stdin`
+    #[serde(rename = "OpenStdin")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_stdin: Option<bool>,
+
+    /// Close `stdin` after one attached client disconnects
+    #[serde(rename = "StdinOnce")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stdin_once: Option<bool>,
+
+    /// A list of environment variables to set inside the container in the form `[\"VAR=value\", ...]`.
+    #[serde(rename = "Env")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<Vec<String>>,
+
+    /// Command to run specified as a string or an array of strings.
+    #[serde(rename = "Cmd")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<Vec<String>>,
+
+    #[serde(rename = "Healthcheck")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub healthcheck: Option<HealthConfig>,
+
+    /// The name (or reference) of the image to use when creating the container, or which was used when the container was created.
+    #[serde(rename = "Image")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    /// An object mapping mount point paths inside the container to empty objects.
+    #[serde(rename = "Volumes")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volumes: Option<HashMap<String, HashMap<(), ()>>>,
+
+    /// The working directory for commands to run in.
+    #[serde(rename = "WorkingDir")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+
+    /// The entry point for the container as a string or an array of strings.
+    #[serde(rename = "Entrypoint")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<Vec<String>>,
+
+    /// Disable networking for the container.
+    #[serde(rename = "NetworkDisabled")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_disabled: Option<bool>,
+
+    /// User-defined key/value metadata.
+    #[serde(rename = "Labels")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
+
+    /// Signal to stop a container as a string or unsigned integer.
+    #[serde(rename = "StopSignal")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_signal: Option<String>,
+
+    /// Timeout to stop a container in seconds.
+    #[serde(rename = "StopTimeout")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_timeout: Option<i64>,
+}
+
+/// The body of a `/containers/create` request: the container's configuration, along with its host
+/// and networking configurations.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ContainerCreateBody {
+    #[serde(flatten)]
+    pub config: ContainerConfig,
+
+    #[serde(rename = "HostConfig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_config: Option<HostConfig>,
+
+    #[serde(rename = "NetworkingConfig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub networking_config: Option<NetworkingConfig>,
+}
+
+/// A test to perform to check that the container is healthy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HealthConfig {
+    /// The test to perform. Possible values are:  - `[]` inherit healthcheck from image or parent image - `[\"NONE\"]` disable healthcheck - `[\"CMD\", args...]` exec arguments directly - `[\"CMD-SHELL\", command]` run command with system's default shell
+    #[serde(rename = "Test")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test: Option<Vec<String>>,
+
+    /// The time to wait between checks in nanoseconds. It should be 0 or at least 1000000 (1 ms). 0 means inherit.
+    #[serde(rename = "Interval")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<i64>,
+
+    /// The time to wait before considering the check to have hung. It should be 0 or at least 1000000 (1 ms). 0 means inherit.
+    #[serde(rename = "Timeout")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<i64>,
+
+    /// The number of consecutive failures needed to consider a container as unhealthy. 0 means inherit.
+    #[serde(rename = "Retries")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<i64>,
+
+    /// Start period for the container to initialize before starting health-retries countdown in nanoseconds. It should be 0 or at least 1000000 (1 ms). 0 means inherit.
+    #[serde(rename = "StartPeriod")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<i64>,
+}
+
+/// Container configuration that depends on the host we are running on
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HostConfig {
+    /// A list of volume bindings for this container. Each volume binding is a string in one of these forms:  - `host-src:container-dest[:options]` to bind-mount a host path into the container. - `volume-name:container-dest[:options]` to bind-mount a volume managed by a volume driver into the container.
+    #[serde(rename = "Binds")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binds: Option<Vec<String>>,
+
+    /// Network mode to use for this container. Supported standard values are: `bridge`, `host`, `none`, and `container:<name|id>`. Any other value is taken as a custom network's name to which this container should connect to.
+    #[serde(rename = "NetworkMode")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_mode: Option<String>,
+
+    /// A map of exposed container ports and the host port they should map to.
+    #[serde(rename = "PortBindings")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port_bindings: Option<HashMap<String, Option<Vec<PortBinding>>>>,
+
+    #[serde(rename = "RestartPolicy")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_policy: Option<RestartPolicy>,
+
+    /// Automatically remove the container when the container's process exits. This has no effect if `RestartPolicy` is set.
+    #[serde(rename = "AutoRemove")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_remove: Option<bool>,
+
+    /// A list of DNS servers for the container to use.
+    #[serde(rename = "Dns")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns: Option<Vec<String>>,
+
+    /// A list of hostnames/IP mappings to add to the container's `/etc/hosts` file. Specified in the form `[\"hostname:IP\"]`.
+    #[serde(rename = "ExtraHosts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_hosts: Option<Vec<String>>,
+
+    /// A list of kernel capabilities to add to the container.
+    #[serde(rename = "CapAdd")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cap_add: Option<Vec<String>>,
+
+    /// A list of kernel capabilities to drop from the container.
+    #[serde(rename = "CapDrop")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cap_drop: Option<Vec<String>>,
+
+    /// Gives the container full access to the host.
+    #[serde(rename = "Privileged")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privileged: Option<bool>,
+
+    /// Allocates an ephemeral host port for all of a container's exposed ports.
+    #[serde(rename = "PublishAllPorts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_all_ports: Option<bool>,
+
+    /// Mount the container's root filesystem as read only.
+    #[serde(rename = "ReadonlyRootfs")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub readonly_rootfs: Option<bool>,
+
+    /// Run an init inside the container that forwards signals and reaps processes.
+    #[serde(rename = "Init")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init: Option<bool>,
+
+    /// Memory limit in bytes.
+    #[serde(rename = "Memory")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<i64>,
+
+    /// CPU quota in units of 10<sup>-9</sup> CPUs.
+    #[serde(rename = "NanoCpus")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nano_cpus: Option<i64>,
+
+    /// An integer value representing this container's relative CPU weight versus other containers.
+    #[serde(rename = "CpuShares")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_shares: Option<i64>,
+
+    /// Specification for mounts to be added to the container.
+    #[serde(rename = "Mounts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mounts: Option<Vec<Mount>>,
+
+    #[serde(rename = "LogConfig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_config: Option<HostConfigLogConfig>,
+}
+
+/// PortBinding represents a binding between a host IP address and a host port.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PortBinding {
+    /// Host IP address that the container's port is mapped to.
+    #[serde(rename = "HostIp")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_ip: Option<String>,
+
+    /// Host port number that the container's port is mapped to.
+    #[serde(rename = "HostPort")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_port: Option<String>,
+}
+
+/// The behavior to apply when the container exits. The default is not to restart.  An ever increasing delay (double the previous delay, starting at 100ms) is added before each restart to prevent flooding the server.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RestartPolicy {
+    #[serde(rename = "Name")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<RestartPolicyNameEnum>,
+
+    /// If `on-failure` is used, the number of times to retry before giving up.
+    #[serde(rename = "MaximumRetryCount")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum_retry_count: Option<i64>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize, Eq, Ord)]
+pub enum RestartPolicyNameEnum {
+    #[serde(rename = "")]
+    EMPTY,
+    #[serde(rename = "no")]
+    NO,
+    #[serde(rename = "always")]
+    ALWAYS,
+    #[serde(rename = "unless-stopped")]
+    UNLESS_STOPPED,
+    #[serde(rename = "on-failure")]
+    ON_FAILURE,
+}
+
+impl ::std::fmt::Display for RestartPolicyNameEnum {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl ::std::str::FromStr for RestartPolicyNameEnum {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(RestartPolicyNameEnum::EMPTY),
+            "no" => Ok(RestartPolicyNameEnum::NO),
+            "always" => Ok(RestartPolicyNameEnum::ALWAYS),
+            "unless-stopped" => Ok(RestartPolicyNameEnum::UNLESS_STOPPED),
+            "on-failure" => Ok(RestartPolicyNameEnum::ON_FAILURE),
+            x => Err(format!("Invalid enum type: {}", x)),
+        }
+    }
+}
+
+impl ::std::convert::AsRef<str> for RestartPolicyNameEnum {
+    fn as_ref(&self) -> &str {
+        match self {
+            RestartPolicyNameEnum::EMPTY => "",
+            RestartPolicyNameEnum::NO => "no",
+            RestartPolicyNameEnum::ALWAYS => "always",
+            RestartPolicyNameEnum::UNLESS_STOPPED => "unless-stopped",
+            RestartPolicyNameEnum::ON_FAILURE => "on-failure",
+        }
+    }
+}
+
+/// A mount to be added to a container at creation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mount {
+    /// Container path.
+    #[serde(rename = "Target")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    /// Mount source (e.g. a volume name, a host path).
+    #[serde(rename = "Source")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    #[serde(rename = "Type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<MountPointTypeEnum>,
+
+    /// Whether the mount should be read-only.
+    #[serde(rename = "ReadOnly")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+}
+
+/// The logging configuration for this container
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HostConfigLogConfig {
+    #[serde(rename = "Type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<String>,
+
+    #[serde(rename = "Config")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<HashMap<String, String>>,
+}
+
+/// NetworkingConfig represents the container's networking configuration for each of its interfaces. It is used for the networking configs specified in the `docker create` and `docker network connect` commands.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkingConfig {
+    /// A mapping of network name to endpoint configuration for that network.
+    #[serde(rename = "EndpointsConfig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoints_config: Option<HashMap<String, EndpointSettings>>,
+}
+
+/// OK response to ContainerCreate operation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerCreateResponse {
+    /// The ID of the created container
+    #[serde(rename = "Id")]
+    pub id: String,
+
+    /// Warnings encountered when creating the container
+    #[serde(rename = "Warnings")]
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StopContainerOptions {
+    /// Signal to send to the container. Defaults to the container's `StopSignal`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal: Option<String>,
+
+    /// Number of seconds to wait before killing the container
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub t: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RestartContainerOptions {
+    /// Signal to send to the container. Defaults to the container's `StopSignal`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal: Option<String>,
+
+    /// Number of seconds to wait before killing the container
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub t: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct KillContainerOptions {
+    /// Signal to send to the container as an integer or string (e.g. `SIGINT`). Defaults to `SIGKILL`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RemoveContainerOptions {
+    /// Remove anonymous volumes associated with the container
+    pub v: bool,
+    /// If the container is running, kill it before removing it
+    pub force: bool,
+    /// Remove the specified link associated with the container
+    pub link: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InspectContainerOptions {
+    /// Return the size of container as fields `SizeRw` and `SizeRootFs`
+    pub size: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WaitContainerOptions {
+    /// Wait until a container state reaches the given condition: `not-running` (the default),
+    /// `next-exit` or `removed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+/// OK response to ContainerWait operation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerWaitResponse {
+    /// Exit code of the container
+    #[serde(rename = "StatusCode")]
+    pub status_code: i64,
+
+    #[serde(rename = "Error")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ContainerWaitExitError>,
+}
+
+/// Container waiting error, if any
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerWaitExitError {
+    /// Details of an error
+    #[serde(rename = "Message")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerInspectResponse {
+    /// The ID of the container
+    #[serde(rename = "Id")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// The time the container was created
+    #[serde(rename = "Created")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    /// The path to the command being run
+    #[serde(rename = "Path")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// The arguments to the command being run
+    #[serde(rename = "Args")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+
+    #[serde(rename = "State")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<ContainerState>,
+
+    /// The container's image ID
+    #[serde(rename = "Image")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    #[serde(rename = "ResolvConfPath")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolv_conf_path: Option<String>,
+
+    #[serde(rename = "HostnamePath")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname_path: Option<String>,
+
+    #[serde(rename = "HostsPath")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts_path: Option<String>,
+
+    #[serde(rename = "LogPath")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_path: Option<String>,
+
+    #[serde(rename = "Name")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(rename = "RestartCount")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_count: Option<i64>,
+
+    #[serde(rename = "Driver")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+
+    #[serde(rename = "Platform")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+
+    #[serde(rename = "MountLabel")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mount_label: Option<String>,
+
+    #[serde(rename = "ProcessLabel")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_label: Option<String>,
+
+    #[serde(rename = "AppArmorProfile")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_armor_profile: Option<String>,
+
+    /// IDs of exec instances that are running in the container.
+    #[serde(rename = "ExecIDs")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exec_ids: Option<Vec<String>>,
+
+    #[serde(rename = "HostConfig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_config: Option<HostConfig>,
+
+    /// The size of files that have been created or changed by this container.
+    #[serde(rename = "SizeRw")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_rw: Option<i64>,
+
+    /// The total size of all the files in this container.
+    #[serde(rename = "SizeRootFs")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_root_fs: Option<i64>,
+
+    #[serde(rename = "Mounts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mounts: Option<Vec<MountPoint>>,
+
+    #[serde(rename = "Config")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<ContainerConfig>,
+
+    #[serde(rename = "NetworkSettings")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_settings: Option<NetworkSettings>,
+}
+
+/// ContainerState stores container's running state. It's part of ContainerJSONBase and will be returned by the \"inspect\" command.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerState {
+    #[serde(rename = "Status")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ContainerStateStatusEnum>,
+
+    /// Whether this container is running.  Note that a running container can be _paused_. The `Running` and `Paused` booleans are not mutually exclusive.
+    #[serde(rename = "Running")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running: Option<bool>,
+
+    /// Whether this container is paused.
+    #[serde(rename = "Paused")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+
+    /// Whether this container is restarting.
+    #[serde(rename = "Restarting")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restarting: Option<bool>,
+
+    /// Whether a process within this container has been killed because it ran out of memory since the container was last started.
+    #[serde(rename = "OOMKilled")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oom_killed: Option<bool>,
+
+    #[serde(rename = "Dead")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead: Option<bool>,
+
+    /// The process ID of this container
+    #[serde(rename = "Pid")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<i64>,
+
+    /// The last exit code of this container
+    #[serde(rename = "ExitCode")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i64>,
+
+    #[serde(rename = "Error")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// The time when this container was last started.
+    #[serde(rename = "StartedAt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+
+    /// The time when this container last exited.
+    #[serde(rename = "FinishedAt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+
+    #[serde(rename = "Health")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health: Option<Health>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize, Eq, Ord)]
+pub enum ContainerStateStatusEnum {
+    #[serde(rename = "")]
+    EMPTY,
+    #[serde(rename = "created")]
+    CREATED,
+    #[serde(rename = "running")]
+    RUNNING,
+    #[serde(rename = "paused")]
+    PAUSED,
+    #[serde(rename = "restarting")]
+    RESTARTING,
+    #[serde(rename = "removing")]
+    REMOVING,
+    #[serde(rename = "exited")]
+    EXITED,
+    #[serde(rename = "dead")]
+    DEAD,
+}
+
+impl ::std::fmt::Display for ContainerStateStatusEnum {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl ::std::str::FromStr for ContainerStateStatusEnum {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(ContainerStateStatusEnum::EMPTY),
+            "created" => Ok(ContainerStateStatusEnum::CREATED),
+            "running" => Ok(ContainerStateStatusEnum::RUNNING),
+            "paused" => Ok(ContainerStateStatusEnum::PAUSED),
+            "restarting" => Ok(ContainerStateStatusEnum::RESTARTING),
+            "removing" => Ok(ContainerStateStatusEnum::REMOVING),
+            "exited" => Ok(ContainerStateStatusEnum::EXITED),
+            "dead" => Ok(ContainerStateStatusEnum::DEAD),
+            x => Err(format!("Invalid enum type: {}", x)),
+        }
+    }
+}
+
+impl ::std::convert::AsRef<str> for ContainerStateStatusEnum {
+    fn as_ref(&self) -> &str {
+        match self {
+            ContainerStateStatusEnum::EMPTY => "",
+            ContainerStateStatusEnum::CREATED => "created",
+            ContainerStateStatusEnum::RUNNING => "running",
+            ContainerStateStatusEnum::PAUSED => "paused",
+            ContainerStateStatusEnum::RESTARTING => "restarting",
+            ContainerStateStatusEnum::REMOVING => "removing",
+            ContainerStateStatusEnum::EXITED => "exited",
+            ContainerStateStatusEnum::DEAD => "dead",
+        }
+    }
+}
+
+/// Health stores information about the container's healthcheck results.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Health {
+    #[serde(rename = "Status")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<HealthStatusEnum>,
+
+    /// FailingStreak is the number of consecutive failures
+    #[serde(rename = "FailingStreak")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failing_streak: Option<i64>,
+
+    /// Log contains the last few results (oldest first)
+    #[serde(rename = "Log")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log: Option<Vec<HealthcheckResult>>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize, Eq, Ord)]
+pub enum HealthStatusEnum {
+    #[serde(rename = "")]
+    EMPTY,
+    #[serde(rename = "none")]
+    NONE,
+    #[serde(rename = "starting")]
+    STARTING,
+    #[serde(rename = "healthy")]
+    HEALTHY,
+    #[serde(rename = "unhealthy")]
+    UNHEALTHY,
+}
+
+impl ::std::fmt::Display for HealthStatusEnum {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl ::std::str::FromStr for HealthStatusEnum {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(HealthStatusEnum::EMPTY),
+            "none" => Ok(HealthStatusEnum::NONE),
+            "starting" => Ok(HealthStatusEnum::STARTING),
+            "healthy" => Ok(HealthStatusEnum::HEALTHY),
+            "unhealthy" => Ok(HealthStatusEnum::UNHEALTHY),
+            x => Err(format!("Invalid enum type: {}", x)),
+        }
+    }
+}
+
+impl ::std::convert::AsRef<str> for HealthStatusEnum {
+    fn as_ref(&self) -> &str {
+        match self {
+            HealthStatusEnum::EMPTY => "",
+            HealthStatusEnum::NONE => "none",
+            HealthStatusEnum::STARTING => "starting",
+            HealthStatusEnum::HEALTHY => "healthy",
+            HealthStatusEnum::UNHEALTHY => "unhealthy",
+        }
+    }
+}
+
+/// HealthcheckResult stores information about a single run of a healthcheck probe
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HealthcheckResult {
+    /// Date and time at which this check started in RFC 3339 format with nano-seconds.
+    #[serde(rename = "Start")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+
+    /// Date and time at which this check ended in RFC 3339 format with nano-seconds.
+    #[serde(rename = "End")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+
+    /// ExitCode meanings:  - `0` healthy - `1` unhealthy - `2` reserved (considered unhealthy) - other values: error running probe
+    #[serde(rename = "ExitCode")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i64>,
+
+    /// Output from last check
+    #[serde(rename = "Output")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+/// NetworkSettings exposes the network settings in the API
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    /// Name of the default bridge interface when dockerd's --bridge flag is set.
+    #[serde(rename = "Bridge")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge: Option<String>,
+
+    /// SandboxID uniquely represents a container's network stack.
+    #[serde(rename = "SandboxID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox_id: Option<String>,
+
+    /// SandboxKey is the full path of the netns handle
+    #[serde(rename = "SandboxKey")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox_key: Option<String>,
+
+    /// A map of exposed container ports and the host port they are mapped to.
+    #[serde(rename = "Ports")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ports: Option<HashMap<String, Option<Vec<PortBinding>>>>,
+
+    /// Information about all networks that the container is connected to.
+    #[serde(rename = "Networks")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub networks: Option<HashMap<String, EndpointSettings>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LogsOptions {
+    /// Keep the connection open and stream new output as the container produces it
+    pub follow: bool,
+    /// Return logs from `stdout`. At least one of `stdout` and `stderr` must be set.
+    pub stdout: bool,
+    /// Return logs from `stderr`. At least one of `stdout` and `stderr` must be set.
+    pub stderr: bool,
+    /// Only return logs since this time, as a UNIX timestamp
+    pub since: i64,
+    /// Only return logs before this time, as a UNIX timestamp
+    pub until: i64,
+    /// Add timestamps to every log line
+    pub timestamps: bool,
+    /// Only return this number of lines from the end of the logs. `all` (the default) returns
+    /// all the lines.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tail: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AttachContainerOptions {
+    /// Stream attached streams from the time the request was made onwards
+    pub stream: bool,
+    /// Replay previous logs from the container before streaming
+    pub logs: bool,
+    /// Attach to `stdin`
+    pub stdin: bool,
+    /// Attach to `stdout`
+    pub stdout: bool,
+    /// Attach to `stderr`
+    pub stderr: bool,
+    /// Override the key sequence for detaching a container, e.g. `ctrl-p,ctrl-q`
+    #[serde(rename = "detachKeys")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detach_keys: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EventsOptions {
+    /// Show events created since this timestamp, as a UNIX timestamp with optional nanoseconds,
+    /// e.g. `1700000000.000000001`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Show events created until this timestamp, then stop streaming
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+
+    /// See Docker's documentation to learn how to use filters
+    /// https://docs.docker.com/reference/cli/docker/system/events/#filter
+    #[serde(serialize_with = "serialize_as_json")]
+    pub filters: HashMap<String, Vec<String>>,
+}
+
+/// EventMessage represents the information an event contains.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventMessage {
+    /// The type of object emitting the event
+    #[serde(rename = "Type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub typ: Option<EventMessageTypeEnum>,
+
+    /// The type of event, e.g. `start`, `die`, `health_status: healthy` or `connect`
+    #[serde(rename = "Action")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+
+    #[serde(rename = "Actor")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<EventActor>,
+
+    /// Scope of the event. Engine events are `local` scope. Cluster (Swarm) events are `swarm` scope.
+    #[serde(rename = "scope")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<EventMessageScopeEnum>,
+
+    /// Timestamp of event
+    #[serde(rename = "time")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<i64>,
+
+    /// Timestamp of event, with nanosecond accuracy
+    #[serde(rename = "timeNano")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_nano: Option<i64>,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize, Eq, Ord)]
+pub enum EventMessageTypeEnum {
+    #[serde(rename = "")]
+    EMPTY,
+    #[serde(rename = "builder")]
+    BUILDER,
+    #[serde(rename = "config")]
+    CONFIG,
+    #[serde(rename = "container")]
+    CONTAINER,
+    #[serde(rename = "daemon")]
+    DAEMON,
+    #[serde(rename = "image")]
+    IMAGE,
+    #[serde(rename = "network")]
+    NETWORK,
+    #[serde(rename = "node")]
+    NODE,
+    #[serde(rename = "plugin")]
+    PLUGIN,
+    #[serde(rename = "secret")]
+    SECRET,
+    #[serde(rename = "service")]
+    SERVICE,
+    #[serde(rename = "volume")]
+    VOLUME,
+}
+
+impl ::std::fmt::Display for EventMessageTypeEnum {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl ::std::str::FromStr for EventMessageTypeEnum {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(EventMessageTypeEnum::EMPTY),
+            "builder" => Ok(EventMessageTypeEnum::BUILDER),
+            "config" => Ok(EventMessageTypeEnum::CONFIG),
+            "container" => Ok(EventMessageTypeEnum::CONTAINER),
+            "daemon" => Ok(EventMessageTypeEnum::DAEMON),
+            "image" => Ok(EventMessageTypeEnum::IMAGE),
+            "network" => Ok(EventMessageTypeEnum::NETWORK),
+            "node" => Ok(EventMessageTypeEnum::NODE),
+            "plugin" => Ok(EventMessageTypeEnum::PLUGIN),
+            "secret" => Ok(EventMessageTypeEnum::SECRET),
+            "service" => Ok(EventMessageTypeEnum::SERVICE),
+            "volume" => Ok(EventMessageTypeEnum::VOLUME),
+            x => Err(format!("Invalid enum type: {}", x)),
+        }
+    }
+}
+
+impl ::std::convert::AsRef<str> for EventMessageTypeEnum {
+    fn as_ref(&self) -> &str {
+        match self {
+            EventMessageTypeEnum::EMPTY => "",
+            EventMessageTypeEnum::BUILDER => "builder",
+            EventMessageTypeEnum::CONFIG => "config",
+            EventMessageTypeEnum::CONTAINER => "container",
+            EventMessageTypeEnum::DAEMON => "daemon",
+            EventMessageTypeEnum::IMAGE => "image",
+            EventMessageTypeEnum::NETWORK => "network",
+            EventMessageTypeEnum::NODE => "node",
+            EventMessageTypeEnum::PLUGIN => "plugin",
+            EventMessageTypeEnum::SECRET => "secret",
+            EventMessageTypeEnum::SERVICE => "service",
+            EventMessageTypeEnum::VOLUME => "volume",
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize, Eq, Ord)]
+pub enum EventMessageScopeEnum {
+    #[serde(rename = "")]
+    EMPTY,
+    #[serde(rename = "local")]
+    LOCAL,
+    #[serde(rename = "swarm")]
+    SWARM,
+}
+
+impl ::std::fmt::Display for EventMessageScopeEnum {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+impl ::std::str::FromStr for EventMessageScopeEnum {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Ok(EventMessageScopeEnum::EMPTY),
+            "local" => Ok(EventMessageScopeEnum::LOCAL),
+            "swarm" => Ok(EventMessageScopeEnum::SWARM),
+            x => Err(format!("Invalid enum type: {}", x)),
+        }
+    }
+}
+
+impl ::std::convert::AsRef<str> for EventMessageScopeEnum {
+    fn as_ref(&self) -> &str {
+        match self {
+            EventMessageScopeEnum::EMPTY => "",
+            EventMessageScopeEnum::LOCAL => "local",
+            EventMessageScopeEnum::SWARM => "swarm",
+        }
+    }
+}
+
+/// Actor describes something that generates events, like a container, network, or a volume.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventActor {
+    /// The ID of the object emitting the event
+    #[serde(rename = "ID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Various key/value attributes of the object, depending on its type, e.g. the name, image
+    /// and labels of a container, or the ID of the container connected to a network.
+    #[serde(rename = "Attributes")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<HashMap<String, String>>,
+}
+
+/// Credentials to authenticate to a registry, sent base64-encoded in the `X-Registry-Auth` header
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DockerCredentials {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// The registry's address, e.g. `https://index.docker.io/v1/`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serveraddress: Option<String>,
+    /// A token obtained from the registry to use instead of the username and password
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identitytoken: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registrytoken: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CreateImageOptions {
+    /// Name of the image to pull. The name may include a tag or digest, e.g. `nginx:1.27`.
+    #[serde(rename = "fromImage")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_image: Option<String>,
+    /// Source to import. The value may be a URL from which the image can be retrieved or `-` to
+    /// read the image from the request body.
+    #[serde(rename = "fromSrc")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_src: Option<String>,
+    /// Repository name given to an image when it is imported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// Tag or digest. If empty when pulling an image, all tags for the given image are pulled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Platform in the format `os[/arch[/variant]]`, e.g. `linux/arm64`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+}
+
+/// A progress message of an image pull
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateImageInfo {
+    /// The ID of the layer the message refers to
+    #[serde(rename = "id")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(rename = "errorDetail")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_detail: Option<ErrorDetail>,
+
+    /// e.g. `Downloading` or `Pull complete`
+    #[serde(rename = "status")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// A human-readable progress bar
+    #[serde(rename = "progress")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<String>,
+
+    #[serde(rename = "progressDetail")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress_detail: Option<ProgressDetail>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDetail {
+    #[serde(rename = "code")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<i64>,
+
+    #[serde(rename = "message")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProgressDetail {
+    /// Number of bytes downloaded or extracted
+    #[serde(rename = "current")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<i64>,
+
+    #[serde(rename = "total")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ListImagesOptions {
+    /// Show all images. Only images from a final layer (no children) are shown by default.
+    pub all: bool,
+    /// Show digest information as a `RepoDigests` field on each image
+    pub digests: bool,
+
+    /// See Docker's documentation to learn how to use filters
+    /// https://docs.docker.com/reference/cli/docker/image/ls/#filter
+    #[serde(serialize_with = "serialize_as_json")]
+    pub filters: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImageSummary {
+    /// ID is the content-addressable ID of an image, e.g. `sha256:ec3f0931...`
+    #[serde(rename = "Id")]
+    pub id: String,
+
+    /// ID of the parent image, or an empty string if the image has no parent
+    #[serde(rename = "ParentId")]
+    pub parent_id: String,
+
+    /// List of image names/tags in the local image cache that reference this image
+    #[serde(rename = "RepoTags")]
+    #[serde(default)]
+    pub repo_tags: Vec<String>,
+
+    /// List of content-addressable digests of locally available image manifests that the image
+    /// is referenced from
+    #[serde(rename = "RepoDigests")]
+    #[serde(default)]
+    pub repo_digests: Vec<String>,
+
+    /// Date and time at which the image was created as a Unix timestamp
+    #[serde(rename = "Created")]
+    pub created: i64,
+
+    /// Total size of the image including all layers it is composed of
+    #[serde(rename = "Size")]
+    pub size: i64,
+
+    /// Total size of image layers that are shared between this image and other images, or `-1`
+    /// if it was not calculated
+    #[serde(rename = "SharedSize")]
+    pub shared_size: i64,
+
+    /// User-defined key/value metadata
+    #[serde(rename = "Labels")]
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+
+    /// Number of containers using this image, or `-1` if it was not calculated
+    #[serde(rename = "Containers")]
+    pub containers: i64,
+}
+
+/// Information about an image in the local image cache
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImageInspect {
+    /// ID is the content-addressable ID of an image, e.g. `sha256:ec3f0931...`
+    #[serde(rename = "Id")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(rename = "RepoTags")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_tags: Option<Vec<String>>,
+
+    #[serde(rename = "RepoDigests")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_digests: Option<Vec<String>>,
+
+    /// ID of the parent image
+    #[serde(rename = "Parent")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+
+    /// Optional message that was set when committing or importing the image
+    #[serde(rename = "Comment")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
+    /// Date and time at which the image was created, formatted in RFC 3339 format with nano-seconds
+    #[serde(rename = "Created")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    /// Name of the author that was specified when committing the image
+    #[serde(rename = "Author")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    #[serde(rename = "Config")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<ContainerConfig>,
+
+    /// Hardware CPU architecture that the image runs on
+    #[serde(rename = "Architecture")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub architecture: Option<String>,
+
+    /// CPU architecture variant (presently ARM-only)
+    #[serde(rename = "Variant")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+
+    /// Operating System the image is built to run on
+    #[serde(rename = "Os")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+
+    /// Total size of the image including all layers it is composed of
+    #[serde(rename = "Size")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    #[serde(rename = "RootFS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_fs: Option<ImageInspectRootFs>,
+}
+
+/// Information about the image's RootFS, including the layer IDs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImageInspectRootFs {
+    #[serde(rename = "Type")]
+    pub typ: String,
+
+    #[serde(rename = "Layers")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layers: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RemoveImageOptions {
+    /// Remove the image even if it is being used by stopped containers or has other tags
+    pub force: bool,
+    /// Do not delete untagged parent images
+    pub noprune: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImageDeleteResponseItem {
+    /// The image ID of an image that was untagged
+    #[serde(rename = "Untagged")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub untagged: Option<String>,
+
+    /// The image ID of an image that was deleted
+    #[serde(rename = "Deleted")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BuildImageOptions {
+    /// Path within the build context to the Dockerfile. Defaults to `Dockerfile`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dockerfile: Option<String>,
+    /// A name and optional tag to apply to the image in the `name:tag` format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub t: Option<String>,
+    /// Extra hosts to add to `/etc/hosts`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extrahosts: Option<String>,
+    /// Suppress verbose build output
+    pub q: bool,
+    /// Do not use the cache when building the image
+    pub nocache: bool,
+    /// Attempt to pull the image even if an older image exists locally
+    pub pull: bool,
+    /// Remove intermediate containers after a successful build
+    pub rm: bool,
+    /// Always remove intermediate containers, even upon failure
+    pub forcerm: bool,
+    /// Build-time variables, accessible with `ARG` in the Dockerfile
+    #[serde(serialize_with = "serialize_as_json")]
+    pub buildargs: HashMap<String, String>,
+    /// Arbitrary key/value labels to set on the image
+    #[serde(serialize_with = "serialize_as_json")]
+    pub labels: HashMap<String, String>,
+    /// Target build stage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Platform in the format `os[/arch[/variant]]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// Sets the networking mode for the run commands during build
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub networkmode: Option<String>,
+}
+
+/// A message of the output of an image build
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    #[serde(rename = "id")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// A line of output of the build, e.g. `Step 1/3 : FROM alpine`
+    #[serde(rename = "stream")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<String>,
+
+    #[serde(rename = "errorDetail")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_detail: Option<ErrorDetail>,
+
+    #[serde(rename = "status")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    #[serde(rename = "progress")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<String>,
+
+    #[serde(rename = "progressDetail")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress_detail: Option<ProgressDetail>,
+
+    /// The ID of the built image, in the last message of a successful build
+    #[serde(rename = "aux")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aux: Option<ImageId>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImageId {
+    #[serde(rename = "ID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CreateExecOptions {
+    /// Attach to `stdin` of the exec command
+    #[serde(rename = "AttachStdin")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach_stdin: Option<bool>,
+
+    /// Attach to `stdout` of the exec command
+    #[serde(rename = "AttachStdout")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach_stdout: Option<bool>,
+
+    /// Attach to `stderr` of the exec command
+    #[serde(rename = "AttachStderr")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attach_stderr: Option<bool>,
+
+    /// Initial console size, as an `[height, width]` array
+    #[serde(rename = "ConsoleSize")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub console_size: Option<Vec<u32>>,
+
+    /// Override the key sequence for detaching a container, e.g. `ctrl-p,ctrl-q`
+    #[serde(rename = "DetachKeys")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detach_keys: Option<String>,
+
+    /// Allocate a pseudo-TTY
+    #[serde(rename = "Tty")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tty: Option<bool>,
+
+    /// A list of environment variables in the form `["VAR=value", ...]`
+    #[serde(rename = "Env")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<Vec<String>>,
+
+    /// Command to run
+    #[serde(rename = "Cmd")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<Vec<String>>,
+
+    /// Runs the exec process with extended privileges
+    #[serde(rename = "Privileged")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privileged: Option<bool>,
+
+    /// The user, and optionally, group to run the exec process inside the container, in the
+    /// format `user`, `user:group`, `uid` or `uid:gid`
+    #[serde(rename = "User")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// The working directory for the exec process inside the container
+    #[serde(rename = "WorkingDir")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+}
+
+/// Response to an API call that returns just an Id
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IdResponse {
+    /// The id of the newly created object
+    #[serde(rename = "Id")]
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StartExecOptions {
+    /// Detach from the command. The output of the command is then not returned.
+    #[serde(rename = "Detach")]
+    pub detach: bool,
+
+    /// Allocate a pseudo-TTY
+    #[serde(rename = "Tty")]
+    pub tty: bool,
+
+    /// Initial console size, as an `[height, width]` array
+    #[serde(rename = "ConsoleSize")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub console_size: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResizeExecOptions {
+    /// Height of the TTY session in characters
+    pub h: u32,
+    /// Width of the TTY session in characters
+    pub w: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecInspectResponse {
+    #[serde(rename = "CanRemove")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_remove: Option<bool>,
+
+    #[serde(rename = "DetachKeys")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detach_keys: Option<String>,
+
+    #[serde(rename = "ID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(rename = "Running")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running: Option<bool>,
+
+    /// The exit code of the process, or `None` while it's running
+    #[serde(rename = "ExitCode")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i64>,
+
+    #[serde(rename = "ProcessConfig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_config: Option<ProcessConfig>,
+
+    #[serde(rename = "OpenStdin")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_stdin: Option<bool>,
+
+    #[serde(rename = "OpenStderr")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_stderr: Option<bool>,
+
+    #[serde(rename = "OpenStdout")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_stdout: Option<bool>,
+
+    #[serde(rename = "ContainerID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_id: Option<String>,
+
+    /// The system process ID for the exec process
+    #[serde(rename = "Pid")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessConfig {
+    #[serde(rename = "privileged")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privileged: Option<bool>,
+
+    #[serde(rename = "user")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    #[serde(rename = "tty")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tty: Option<bool>,
+
+    #[serde(rename = "entrypoint")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<String>,
+
+    #[serde(rename = "arguments")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ListNetworksOptions {
+    /// See Docker's documentation to learn how to use filters
+    /// https://docs.docker.com/reference/cli/docker/network/ls/#filter
+    #[serde(serialize_with = "serialize_as_json")]
+    pub filters: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Network {
+    #[serde(rename = "Name")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(rename = "Id")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Date and time at which the network was created in RFC 3339 format with nano-seconds
+    #[serde(rename = "Created")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    /// The level at which the network exists, e.g. `swarm` for cluster-wide or `local` for
+    /// machine level
+    #[serde(rename = "Scope")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
+    /// The name of the driver used to create the network, e.g. `bridge` or `overlay`
+    #[serde(rename = "Driver")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+
+    #[serde(rename = "EnableIPv6")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_ipv6: Option<bool>,
+
+    #[serde(rename = "IPAM")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipam: Option<Ipam>,
+
+    /// Whether the network is created to only allow internal networking connectivity
+    #[serde(rename = "Internal")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal: Option<bool>,
+
+    /// Whether a global / swarm scope network is manually attachable by regular containers
+    #[serde(rename = "Attachable")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachable: Option<bool>,
+
+    #[serde(rename = "Ingress")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingress: Option<bool>,
+
+    /// Contains endpoints attached to the network, indexed by container ID
+    #[serde(rename = "Containers")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub containers: Option<HashMap<String, NetworkContainer>>,
+
+    /// Network-specific options uses when creating the network
+    #[serde(rename = "Options")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<HashMap<String, String>>,
+
+    /// User-defined key/value metadata.
+    #[serde(rename = "Labels")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ipam {
+    /// Name of the IP Address Management driver
+    #[serde(rename = "Driver")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+
+    /// List of IPAM configuration options, specified as a map: `{\"Subnet\": <CIDR>, \"IPRange\": <CIDR>, \"Gateway\": <IP address>, \"AuxAddress\": <device_name:IP address>}`
+    #[serde(rename = "Config")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<Vec<IpamConfig>>,
+
+    /// Driver-specific options, specified as a map
+    #[serde(rename = "Options")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IpamConfig {
+    #[serde(rename = "Subnet")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet: Option<String>,
+
+    #[serde(rename = "IPRange")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_range: Option<String>,
+
+    #[serde(rename = "Gateway")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<String>,
+
+    #[serde(rename = "AuxiliaryAddresses")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auxiliary_addresses: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkContainer {
+    #[serde(rename = "Name")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(rename = "EndpointID")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint_id: Option<String>,
+
+    #[serde(rename = "MacAddress")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+
+    #[serde(rename = "IPv4Address")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4_address: Option<String>,
+
+    #[serde(rename = "IPv6Address")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkCreateRequest {
+    /// The network's name
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    /// Name of the network driver plugin to use. Defaults to `bridge`.
+    #[serde(rename = "Driver")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+
+    /// The level at which the network exists (e.g. `swarm` for cluster-wide or `local` for
+    /// machine level).
+    #[serde(rename = "Scope")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
+    /// Restrict external access to the network
+    #[serde(rename = "Internal")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal: Option<bool>,
+
+    /// Globally scoped network is manually attachable by regular containers from workers in
+    /// swarm mode
+    #[serde(rename = "Attachable")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachable: Option<bool>,
+
+    #[serde(rename = "Ingress")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingress: Option<bool>,
+
+    #[serde(rename = "IPAM")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipam: Option<Ipam>,
+
+    /// Enable IPv6 on the network
+    #[serde(rename = "EnableIPv6")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_ipv6: Option<bool>,
+
+    /// Network specific options to be used by the drivers
+    #[serde(rename = "Options")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<HashMap<String, String>>,
+
+    /// User-defined key/value metadata.
+    #[serde(rename = "Labels")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
+}
+
+/// OK response to NetworkCreate operation
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkCreateResponse {
+    /// The ID of the created network
+    #[serde(rename = "Id")]
+    pub id: String,
+
+    /// Warnings encountered when creating the network
+    #[serde(rename = "Warning")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InspectNetworkOptions {
+    /// Detailed inspect output for troubleshooting
+    pub verbose: bool,
+    /// Filter the network by scope (`swarm`, `global`, or `local`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConnectRequest {
+    /// The ID or name of the container to connect to the network
+    #[serde(rename = "Container")]
+    pub container: String,
+
+    #[serde(rename = "EndpointConfig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint_config: Option<EndpointSettings>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkDisconnectRequest {
+    /// The ID or name of the container to disconnect from the network
+    #[serde(rename = "Container")]
+    pub container: String,
+
+    /// Force the container to disconnect from the network
+    #[serde(rename = "Force")]
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ListVolumesOptions {
+    /// See Docker's documentation to learn how to use filters
+    /// https://docs.docker.com/reference/cli/docker/volume/ls/#filter
+    #[serde(serialize_with = "serialize_as_json")]
+    pub filters: HashMap<String, Vec<String>>,
+}
+
+/// Volume list response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VolumeListResponse {
+    #[serde(rename = "Volumes")]
+    #[serde(default)]
+    pub volumes: Vec<Volume>,
+
+    /// Warnings that occurred when fetching the list of volumes
+    #[serde(rename = "Warnings")]
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Volume {
+    /// Name of the volume
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    /// Name of the volume driver used by the volume
+    #[serde(rename = "Driver")]
+    pub driver: String,
+
+    /// Mount path of the volume on the host
+    #[serde(rename = "Mountpoint")]
+    pub mountpoint: String,
+
+    /// Date/Time the volume was created
+    #[serde(rename = "CreatedAt")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+
+    /// User-defined key/value metadata
+    #[serde(rename = "Labels")]
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+
+    /// The level at which the volume exists: `global` for cluster-wide or `local` for machine
+    /// level
+    #[serde(rename = "Scope")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
+    /// The driver specific options used when creating the volume
+    #[serde(rename = "Options")]
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+}
+
+/// Volume configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VolumeCreateOptions {
+    /// The new volume's name. If not specified, Docker generates a name.
+    #[serde(rename = "Name")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Name of the volume driver to use
+    #[serde(rename = "Driver")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+
+    /// A mapping of driver options and values. These options are passed directly to the driver
+    /// and are driver specific.
+    #[serde(rename = "DriverOpts")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver_opts: Option<HashMap<String, String>>,
+
+    /// User-defined key/value metadata
+    #[serde(rename = "Labels")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RemoveVolumeOptions {
+    /// Force the removal of the volume
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PruneVolumesOptions {
+    /// See Docker's documentation to learn how to use filters
+    /// https://docs.docker.com/reference/cli/docker/volume/prune/#filter
+    #[serde(serialize_with = "serialize_as_json")]
+    pub filters: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VolumePruneResponse {
+    /// Volumes that were deleted
+    #[serde(rename = "VolumesDeleted")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volumes_deleted: Option<Vec<String>>,
+
+    /// Disk space reclaimed in bytes
+    #[serde(rename = "SpaceReclaimed")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub space_reclaimed: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StatsOptions {
+    /// Stream the stats every second instead of returning them once
+    pub stream: bool,
+    /// Only get a single stat instead of waiting for 2 cycles. `precpu_stats` is then empty.
+    #[serde(rename = "one-shot")]
+    pub one_shot: bool,
+}
+
+/// Resource usage statistics of a container
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerStats {
+    /// Name of the container, prefixed with a `/`
+    #[serde(default)]
+    pub name: String,
+
+    /// ID of the container
+    #[serde(default)]
+    pub id: String,
+
+    /// Date and time at which this sample was collected, in RFC 3339 format with nano-seconds
+    #[serde(default)]
+    pub read: String,
+
+    /// Date and time at which the previous sample was collected
+    #[serde(default)]
+    pub preread: String,
+
+    #[serde(default)]
+    pub pids_stats: PidsStats,
+
+    #[serde(default)]
+    pub cpu_stats: CpuStats,
+
+    /// CPU statistics of the previous sample, used to compute the CPU usage between the two
+    /// samples
+    #[serde(default)]
+    pub precpu_stats: CpuStats,
+
+    #[serde(default)]
+    pub memory_stats: MemoryStats,
+
+    /// Network statistics indexed by interface name. Absent for containers without networking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub networks: Option<HashMap<String, NetworkStats>>,
+
+    #[serde(default)]
+    pub blkio_stats: BlkioStats,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PidsStats {
+    /// Number of processes / threads in the container
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<u64>,
+
+    /// Limit of the number of processes / threads in the container
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuStats {
+    #[serde(default)]
+    pub cpu_usage: CpuUsage,
+
+    /// System usage, in nanoseconds. Not populated on Windows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_cpu_usage: Option<u64>,
+
+    /// Number of online CPUs. Not populated on Windows.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub online_cpus: Option<u32>,
+
+    #[serde(default)]
+    pub throttling_data: ThrottlingData,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuUsage {
+    /// Total CPU time consumed in nanoseconds
+    pub total_usage: u64,
+
+    /// Total CPU time consumed per core in nanoseconds. Only available on cgroups v1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percpu_usage: Option<Vec<u64>>,
+
+    /// Time spent by tasks of the container in kernel mode in nanoseconds
+    #[serde(default)]
+    pub usage_in_kernelmode: u64,
+
+    /// Time spent by tasks of the container in user mode in nanoseconds
+    #[serde(default)]
+    pub usage_in_usermode: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThrottlingData {
+    /// Number of periods with throttling active
+    #[serde(default)]
+    pub periods: u64,
+
+    /// Number of periods when the container hit its throttling limit
+    #[serde(default)]
+    pub throttled_periods: u64,
+
+    /// Aggregated time in nanoseconds the container was throttled for
+    #[serde(default)]
+    pub throttled_time: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryStats {
+    /// Current memory usage in bytes, including the page cache
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<u64>,
+
+    /// Maximum memory usage recorded. Only available on cgroups v1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_usage: Option<u64>,
+
+    /// Detailed memory statistics of the cgroup, which depend on the version of cgroups, e.g.
+    /// `cache` (v1) or `inactive_file` (v2)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<HashMap<String, u64>>,
+
+    /// Number of times memory usage hits limits. Only available on cgroups v1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failcnt: Option<u64>,
+
+    /// Memory limit of the container in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkStats {
+    /// Bytes received
+    pub rx_bytes: u64,
+    /// Packets received
+    pub rx_packets: u64,
+    /// Received errors
+    pub rx_errors: u64,
+    /// Incoming packets dropped
+    pub rx_dropped: u64,
+    /// Bytes sent
+    pub tx_bytes: u64,
+    /// Packets sent
+    pub tx_packets: u64,
+    /// Sent errors
+    pub tx_errors: u64,
+    /// Outgoing packets dropped
+    pub tx_dropped: u64,
+}
+
+/// Block I/O statistics. On cgroups v2, only `io_service_bytes_recursive` is populated.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlkioStats {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_service_bytes_recursive: Option<Vec<BlkioStatEntry>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_serviced_recursive: Option<Vec<BlkioStatEntry>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_queue_recursive: Option<Vec<BlkioStatEntry>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_service_time_recursive: Option<Vec<BlkioStatEntry>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_wait_time_recursive: Option<Vec<BlkioStatEntry>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_merged_recursive: Option<Vec<BlkioStatEntry>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_time_recursive: Option<Vec<BlkioStatEntry>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sectors_recursive: Option<Vec<BlkioStatEntry>>,
+}
+
+/// Block I/O statistics of a device
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlkioStatEntry {
+    pub major: u64,
+    pub minor: u64,
+    /// The operation, e.g. `read` or `write`
+    pub op: String,
+    pub value: u64,
+}
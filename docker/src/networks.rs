@@ -0,0 +1,162 @@
+use hyper::Method;
+
+use crate::{
+    Client, Error,
+    model::{
+        InspectNetworkOptions, ListNetworksOptions, Network, NetworkConnectRequest, NetworkCreateRequest,
+        NetworkCreateResponse, NetworkDisconnectRequest,
+    },
+};
+
+impl Client {
+    pub async fn list_networks(&self, options: Option<ListNetworksOptions>) -> Result<Vec<Network>, Error> {
+        return self.send_request(Method::GET, "/networks", options, None::<()>).await;
+    }
+
+    pub async fn create_network(&self, config: NetworkCreateRequest) -> Result<NetworkCreateResponse, Error> {
+        return self
+            .send_request(Method::POST, "/networks/create", None::<()>, Some(config))
+            .await;
+    }
+
+    /// Return low-level information about a network, including the containers connected to it.
+    pub async fn inspect_network(&self, id: &str, options: Option<InspectNetworkOptions>) -> Result<Network, Error> {
+        return self
+            .send_request(Method::GET, &format!("/networks/{id}"), options, None::<()>)
+            .await;
+    }
+
+    /// Connect a container to a network. The container can be running or stopped.
+    pub async fn connect_network(&self, id: &str, config: NetworkConnectRequest) -> Result<(), Error> {
+        return self
+            .send_request(Method::POST, &format!("/networks/{id}/connect"), None::<()>, Some(config))
+            .await;
+    }
+
+    pub async fn disconnect_network(&self, id: &str, config: NetworkDisconnectRequest) -> Result<(), Error> {
+        return self
+            .send_request(Method::POST, &format!("/networks/{id}/disconnect"), None::<()>, Some(config))
+            .await;
+    }
+
+    pub async fn remove_network(&self, id: &str) -> Result<(), Error> {
+        return self
+            .send_request(Method::DELETE, &format!("/networks/{id}"), None::<()>, None::<()>)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+    use hyper::StatusCode;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{mock_daemon::MockDaemon, model::EndpointSettings};
+
+    fn body(body: &Bytes) -> Value {
+        return serde_json::from_slice(body).unwrap();
+    }
+
+    #[tokio::test]
+    async fn network_requests() {
+        let daemon = MockDaemon::start().await.unwrap();
+        daemon.add_response(
+            Method::GET,
+            "/networks",
+            StatusCode::OK,
+            &json!([{ "Name": "backend", "Id": "net1", "Driver": "bridge" }]),
+        );
+        daemon.add_response(Method::POST, "/networks/create", StatusCode::CREATED, &json!({ "Id": "net1" }));
+        daemon.add_response(
+            Method::GET,
+            "/networks/net1",
+            StatusCode::OK,
+            &json!({ "Name": "backend", "Id": "net1" }),
+        );
+        for action in ["connect", "disconnect"] {
+            daemon.add_raw_response(
+                Method::POST,
+                &format!("/networks/net1/{action}"),
+                StatusCode::OK,
+                "text/plain",
+                Bytes::new(),
+            );
+        }
+        daemon.add_raw_response(
+            Method::DELETE,
+            "/networks/net1",
+            StatusCode::NO_CONTENT,
+            "text/plain",
+            Bytes::new(),
+        );
+        let client = daemon.client();
+
+        let options = ListNetworksOptions {
+            filters: HashMap::from([("driver".to_string(), vec!["bridge".to_string()])]),
+        };
+        let networks = client.list_networks(Some(options)).await.unwrap();
+        assert_eq!(networks[0].id.as_deref(), Some("net1"));
+
+        let config = NetworkCreateRequest {
+            name: "backend".to_string(),
+            driver: Some("bridge".to_string()),
+            internal: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(client.create_network(config).await.unwrap().id, "net1");
+
+        let options = InspectNetworkOptions {
+            verbose: true,
+            ..Default::default()
+        };
+        let network = client.inspect_network("net1", Some(options)).await.unwrap();
+        assert_eq!(network.name.as_deref(), Some("backend"));
+
+        let config = NetworkConnectRequest {
+            container: "web".to_string(),
+            endpoint_config: Some(EndpointSettings {
+                aliases: Some(vec!["api".to_string()]),
+                ..Default::default()
+            }),
+        };
+        client.connect_network("net1", config).await.unwrap();
+        let config = NetworkDisconnectRequest {
+            container: "web".to_string(),
+            force: true,
+        };
+        client.disconnect_network("net1", config).await.unwrap();
+        client.remove_network("net1").await.unwrap();
+
+        let requests: Vec<_> = daemon.requests().into_iter().skip(1).collect();
+        let paths: Vec<_> = requests
+            .iter()
+            .map(|request| (request.method.as_str(), request.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("GET", "/networks"),
+                ("POST", "/networks/create"),
+                ("GET", "/networks/net1"),
+                ("POST", "/networks/net1/connect"),
+                ("POST", "/networks/net1/disconnect"),
+                ("DELETE", "/networks/net1"),
+            ]
+        );
+        assert_eq!(requests[0].query["filters"], r#"{"driver":["bridge"]}"#);
+        assert_eq!(
+            body(&requests[1].body),
+            json!({ "Name": "backend", "Driver": "bridge", "Internal": true })
+        );
+        assert_eq!(requests[2].query["verbose"], "true");
+        let connect = body(&requests[3].body);
+        assert_eq!(connect["Container"], "web");
+        assert_eq!(connect["EndpointConfig"]["Aliases"], json!(["api"]));
+        assert_eq!(body(&requests[4].body), json!({ "Container": "web", "Force": true }));
+        assert!(requests[5].body.is_empty());
+    }
+}
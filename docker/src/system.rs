@@ -0,0 +1,243 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use futures_util::{StreamExt, stream};
+use hyper::{HeaderMap, Method};
+use tracing::warn;
+
+use crate::{
+    Client, Error,
+    json_stream::{JsonStream, response_json_stream},
+    model::{EventMessage, EventsOptions, HealthStatusEnum},
+};
+
+const EVENTS_RECONNECT_MIN_DELAY: Duration = Duration::from_secs(1);
+const EVENTS_RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+struct EventsState<'a> {
+    client: &'a Client,
+    options: EventsOptions,
+    events: Option<JsonStream<'a, EventMessage>>,
+    last_event: Option<EventMessage>,
+    reconnect_delay: Duration,
+}
+
+impl Client {
+    /// Stream real-time events from the daemon, such as containers starting, dying or changing
+    /// health status, or being connected to a network.
+    ///
+    /// If the connection to the daemon is lost (e.g. when the daemon restarts), the stream
+    /// reconnects with an exponential backoff and resumes from the last received event, so no
+    /// event is missed. Errors are yielded but don't end the stream, unless the daemon rejects the
+    /// request. The stream ends only when `until` is set and reached.
+    pub async fn events(&self, options: Option<EventsOptions>) -> Result<JsonStream<'_, EventMessage>, Error> {
+        let mut options = options.unwrap_or_default();
+        let connected_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let events = self.events_request(&options).await?;
+        // if the connection is lost before the first event, resume from the time we connected
+        if options.since.is_none() {
+            options.since = Some(format_timestamp(connected_at.as_nanos() as i64));
+        }
+
+        let state = EventsState {
+            client: self,
+            options,
+            events: Some(events),
+            last_event: None,
+            reconnect_delay: Duration::ZERO,
+        };
+
+        return Ok(Box::pin(stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+
+            loop {
+                let events = match state.events.as_mut() {
+                    Some(events) => events,
+                    None => {
+                        tokio::time::sleep(state.reconnect_delay).await;
+                        match state.client.events_request(&state.options).await {
+                            Ok(events) => state.events.insert(events),
+                            Err(err @ Error::BadRequest(_)) => return Some((Err(err), None)),
+                            Err(err) => {
+                                state.backoff();
+                                return Some((Err(err), Some(state)));
+                            }
+                        }
+                    }
+                };
+
+                match events.next().await {
+                    Some(Ok(event)) => {
+                        if state.is_duplicate(&event) {
+                            continue;
+                        }
+                        state.reconnect_delay = Duration::ZERO;
+                        if let Some(time_nano) = event.time_nano {
+                            state.options.since = Some(format_timestamp(time_nano));
+                        }
+                        state.last_event = Some(event.clone());
+                        return Some((Ok(event), Some(state)));
+                    }
+                    // the connection is still usable, only this message is lost
+                    Some(Err(err @ Error::InvalidMessage(_))) => {
+                        warn!("docker events: {err}");
+                        return Some((Err(err), Some(state)));
+                    }
+                    Some(Err(err)) => {
+                        warn!("docker events: connection lost: {err}");
+                        state.events = None;
+                        state.backoff();
+                        return Some((Err(err), Some(state)));
+                    }
+                    None => {
+                        if state.options.until.is_some() {
+                            return None;
+                        }
+                        warn!("docker events: connection closed by the daemon");
+                        state.events = None;
+                        state.backoff();
+                    }
+                }
+            }
+        })));
+    }
+
+    async fn events_request(&self, options: &EventsOptions) -> Result<JsonStream<'static, EventMessage>, Error> {
+        let response = self
+            .request_stream(Method::GET, "/events", Some(options), Bytes::new().into(), HeaderMap::new())
+            .await?;
+
+        return Ok(response_json_stream(response));
+    }
+}
+
+impl EventsState<'_> {
+    fn backoff(&mut self) {
+        self.reconnect_delay = (self.reconnect_delay * 2).clamp(EVENTS_RECONNECT_MIN_DELAY, EVENTS_RECONNECT_MAX_DELAY);
+    }
+
+    /// After reconnecting, the daemon replays the events that happened at the `since` timestamp,
+    /// which include the last event that we received.
+    fn is_duplicate(&self, event: &EventMessage) -> bool {
+        let Some(last_event) = &self.last_event else {
+            return false;
+        };
+
+        return match (event.time_nano, last_event.time_nano) {
+            (Some(time), Some(last_time)) => time < last_time || (time == last_time && event == last_event),
+            _ => false,
+        };
+    }
+}
+
+/// Format a timestamp in nanoseconds as expected by the `since` and `until` parameters
+fn format_timestamp(time_nano: i64) -> String {
+    return format!(
+        "{}.{:09}",
+        time_nano.div_euclid(1_000_000_000),
+        time_nano.rem_euclid(1_000_000_000)
+    );
+}
+
+impl EventMessage {
+    /// The ID of the object emitting the event, e.g. the ID of the container for container events
+    /// or the ID of the network for network events
+    pub fn actor_id(&self) -> Option<&str> {
+        return self.actor.as_ref()?.id.as_deref();
+    }
+
+    /// Get an attribute of the object emitting the event, e.g. `name` or a label for container
+    /// events, or `container` (the ID of the connected container) for network events
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        return self.actor.as_ref()?.attributes.as_ref()?.get(key).map(String::as_str);
+    }
+
+    /// The new health status of a container for `health_status` events
+    pub fn health_status(&self) -> Option<HealthStatusEnum> {
+        let status = self.action.as_deref()?.strip_prefix("health_status:")?;
+        return status.trim().parse().ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::StatusCode;
+
+    use super::*;
+    use crate::mock_daemon::MockDaemon;
+
+    fn add_events_response(daemon: &MockDaemon, lines: &[&str]) {
+        let body: String = lines.iter().map(|line| format!("{line}\n")).collect();
+        daemon.add_raw_response(Method::GET, "/events", StatusCode::OK, "application/json", Bytes::from(body));
+    }
+
+    #[test]
+    fn parse_health_status_event() {
+        let event: EventMessage = serde_json::from_str(
+            r#"{"status":"health_status: healthy","id":"abc","from":"nginx","Type":"container",
+            "Action":"health_status: healthy","Actor":{"ID":"abc","Attributes":{"image":"nginx",
+            "name":"web","pingoo.service":"web"}},"scope":"local","time":1700000000,
+            "timeNano":1700000000000000042}"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.health_status(), Some(HealthStatusEnum::HEALTHY));
+        assert_eq!(event.actor_id(), Some("abc"));
+        assert_eq!(event.attribute("pingoo.service"), Some("web"));
+        assert_eq!(format_timestamp(event.time_nano.unwrap()), "1700000000.000000042");
+    }
+
+    #[tokio::test]
+    async fn skip_invalid_event() {
+        let daemon = MockDaemon::start().await.unwrap();
+        add_events_response(
+            &daemon,
+            &[
+                r#"{"Type":"container","Action":"start","timeNano":1000000000}"#,
+                "not json",
+                r#"{"Type":"container","Action":"die","timeNano":2000000000}"#,
+            ],
+        );
+        let client = daemon.client();
+
+        let events = client
+            .events(Some(EventsOptions {
+                until: Some("3".to_string()),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        let events: Vec<Result<EventMessage, Error>> = events.collect().await;
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].as_ref().unwrap().action.as_deref(), Some("start"));
+        assert!(matches!(events[1], Err(Error::InvalidMessage(_))));
+        assert_eq!(events[2].as_ref().unwrap().action.as_deref(), Some("die"));
+
+        // the stream was not reconnected
+        let requests = daemon.requests();
+        assert_eq!(requests.iter().filter(|request| request.path == "/events").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn resume_from_connection_time() {
+        let daemon = MockDaemon::start().await.unwrap();
+        // the daemon closes the first connection before sending any event
+        add_events_response(&daemon, &[]);
+        add_events_response(&daemon, &[r#"{"Type":"container","Action":"start","timeNano":1000000000}"#]);
+        let client = daemon.client();
+
+        let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let mut events = client.events(None).await.unwrap();
+        let event = events.next().await.unwrap().unwrap();
+        assert_eq!(event.action.as_deref(), Some("start"));
+
+        let requests: Vec<_> = daemon
+            .requests()
+            .into_iter()
+            .filter(|request| request.path == "/events")
+            .collect();
+        assert!(!requests[0].query.contains_key("since"));
+        let since: f64 = requests[1].query["since"].parse().unwrap();
+        assert!(since >= before as f64);
+    }
+}
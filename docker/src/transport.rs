@@ -0,0 +1,215 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use hyper::client::conn::http1::SendRequest;
+use hyper_utils::rt::TokioIo;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpStream, UnixStream},
+};
+use tokio_rustls::{
+    TlsConnector,
+    rustls::{
+        ClientConfig, RootCertStore,
+        crypto::ring,
+        pki_types::{CertificateDer, ServerName},
+    },
+};
+use tracing::{debug, error};
+
+use crate::{Error, client::RequestBody};
+
+pub(crate) const DEFAULT_SOCKET_PATH: &str = "/var/run/docker.sock";
+const DEFAULT_TCP_PORT: u16 = 2375;
+const DEFAULT_TLS_PORT: u16 = 2376;
+
+/// How to reach the Docker daemon
+pub(crate) enum Transport {
+    Unix(PathBuf),
+    Tcp {
+        /// `host:port`
+        address: String,
+        tls: Option<Tls>,
+    },
+}
+
+pub(crate) struct Tls {
+    connector: TlsConnector,
+    server_name: ServerName<'static>,
+}
+
+impl Transport {
+    /// Parse a `DOCKER_HOST`-like address: `unix:///path/to/docker.sock` or `tcp://host:port`.
+    /// If `cert_path` is set, TCP connections use mutual TLS with the `ca.pem`, `cert.pem` and
+    /// `key.pem` files of this directory, like the docker CLI.
+    pub(crate) fn parse(host: &str, cert_path: Option<&Path>) -> Result<Transport, Error> {
+        if let Some(socket_path) = host.strip_prefix("unix://") {
+            return Ok(Transport::Unix(PathBuf::from(socket_path)));
+        }
+
+        let Some(address) = host.strip_prefix("tcp://") else {
+            return Err(Error::InvalidConfig(format!(
+                "unsupported docker host: {host}. Only unix:// and tcp:// are supported"
+            )));
+        };
+        let address = address.trim_end_matches('/');
+
+        // the host may be an IPv6 address between brackets, which contains colons
+        let (hostname, address) = match address.rsplit_once(':') {
+            Some((hostname, port)) if !port.contains(']') => (hostname, address.to_string()),
+            _ => {
+                let port = if cert_path.is_some() {
+                    DEFAULT_TLS_PORT
+                } else {
+                    DEFAULT_TCP_PORT
+                };
+                (address, format!("{address}:{port}"))
+            }
+        };
+        let hostname = hostname.trim_start_matches('[').trim_end_matches(']');
+        if hostname.is_empty() {
+            return Err(Error::InvalidConfig(format!("invalid docker host: {host}")));
+        }
+
+        let tls = match cert_path {
+            Some(cert_path) => {
+                let server_name = ServerName::try_from(hostname.to_string())
+                    .map_err(|err| Error::InvalidConfig(format!("invalid docker hostname ({hostname}): {err}")))?;
+                Some(Tls {
+                    connector: TlsConnector::from(Arc::new(load_tls_config(cert_path)?)),
+                    server_name,
+                })
+            }
+            None => None,
+        };
+
+        return Ok(Transport::Tcp {
+            address,
+            tls,
+        });
+    }
+
+    /// Open a new HTTP connection to the docker host.
+    pub(crate) async fn connect(&self) -> Result<SendRequest<RequestBody>, Error> {
+        return match self {
+            Transport::Unix(socket_path) => {
+                let stream = UnixStream::connect(socket_path)
+                    .await
+                    .map_err(|err| Error::Connecting(err.into()))?;
+                handshake(stream).await
+            }
+            Transport::Tcp {
+                address,
+                tls,
+            } => {
+                let stream = TcpStream::connect(address)
+                    .await
+                    .map_err(|err| Error::Connecting(err.into()))?;
+                stream.set_nodelay(true).map_err(|err| Error::Connecting(err.into()))?;
+
+                match tls {
+                    Some(tls) => {
+                        let stream = tls
+                            .connector
+                            .connect(tls.server_name.clone(), stream)
+                            .await
+                            .map_err(|err| Error::Connecting(err.into()))?;
+                        handshake(stream).await
+                    }
+                    None => handshake(stream).await,
+                }
+            }
+        };
+    }
+}
+
+async fn handshake<T>(stream: T) -> Result<SendRequest<RequestBody>, Error>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(|err| Error::Connecting(err.into()))?;
+    debug!("connection established");
+
+    // spawn a task to poll the connection and drive the HTTP state
+    tokio::task::spawn(async move {
+        if let Err(err) = conn.with_upgrades().await {
+            error!("connection error: {:?}", err);
+        }
+    });
+
+    return Ok(sender);
+}
+
+/// Load the CA certificate used to verify the daemon, and the client certificate and key used to
+/// authenticate to the daemon
+fn load_tls_config(cert_path: &Path) -> Result<ClientConfig, Error> {
+    let mut roots = RootCertStore::empty();
+    for ca_certificate in load_certificates(&cert_path.join("ca.pem"))? {
+        roots
+            .add(ca_certificate)
+            .map_err(|err| Error::InvalidConfig(format!("invalid CA certificate: {err}")))?;
+    }
+
+    let certificates = load_certificates(&cert_path.join("cert.pem"))?;
+
+    let key_path = cert_path.join("key.pem");
+    let key = rustls_pemfile::private_key(&mut BufReader::new(open(&key_path)?))
+        .map_err(|err| Error::InvalidConfig(format!("reading {}: {err}", key_path.display())))?
+        .ok_or_else(|| Error::InvalidConfig(format!("no private key found in {}", key_path.display())))?;
+
+    return ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|err| Error::InvalidConfig(format!("configuring TLS: {err}")))?
+        .with_root_certificates(roots)
+        .with_client_auth_cert(certificates, key)
+        .map_err(|err| Error::InvalidConfig(format!("invalid client certificate: {err}")));
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>, Error> {
+    return rustls_pemfile::certs(&mut BufReader::new(open(path)?))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| Error::InvalidConfig(format!("reading {}: {err}", path.display())));
+}
+
+fn open(path: &Path) -> Result<File, Error> {
+    return File::open(path).map_err(|err| Error::InvalidConfig(format!("opening {}: {err}", path.display())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_docker_host() {
+        let Transport::Unix(socket_path) = Transport::parse("unix:///run/user/1000/docker.sock", None).unwrap() else {
+            panic!("expected a unix transport");
+        };
+        assert_eq!(socket_path, PathBuf::from("/run/user/1000/docker.sock"));
+
+        let addresses = [
+            ("tcp://10.0.0.2:2375", "10.0.0.2:2375"),
+            ("tcp://docker.internal", "docker.internal:2375"),
+            ("tcp://[::1]", "[::1]:2375"),
+            ("tcp://[::1]:4243/", "[::1]:4243"),
+        ];
+        for (host, expected) in addresses {
+            let Transport::Tcp {
+                address,
+                tls: None,
+            } = Transport::parse(host, None).unwrap()
+            else {
+                panic!("expected a plain tcp transport for {host}");
+            };
+            assert_eq!(address, expected);
+        }
+
+        assert!(Transport::parse("npipe:////./pipe/docker_engine", None).is_err());
+        assert!(Transport::parse("tcp://:2375", None).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use hyper::Method;
+
+use crate::{
+    Client, Error,
+    model::{
+        ListVolumesOptions, PruneVolumesOptions, RemoveVolumeOptions, Volume, VolumeCreateOptions, VolumeListResponse,
+        VolumePruneResponse,
+    },
+};
+
+impl Client {
+    pub async fn list_volumes(&self, options: Option<ListVolumesOptions>) -> Result<VolumeListResponse, Error> {
+        return self.send_request(Method::GET, "/volumes", options, None::<()>).await;
+    }
+
+    pub async fn create_volume(&self, config: VolumeCreateOptions) -> Result<Volume, Error> {
+        return self
+            .send_request(Method::POST, "/volumes/create", None::<()>, Some(config))
+            .await;
+    }
+
+    /// Remove a volume. Returns `Error::Conflict` if the volume is in use.
+    pub async fn remove_volume(&self, name: &str, options: Option<RemoveVolumeOptions>) -> Result<(), Error> {
+        return self
+            .send_request(Method::DELETE, &format!("/volumes/{name}"), options, None::<()>)
+            .await;
+    }
+
+    /// Delete all the volumes that are not used by at least one container.
+    pub async fn prune_volumes(&self, options: Option<PruneVolumesOptions>) -> Result<VolumePruneResponse, Error> {
+        return self
+            .send_request(Method::POST, "/volumes/prune", options, None::<()>)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+    use hyper::StatusCode;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::mock_daemon::MockDaemon;
+
+    #[tokio::test]
+    async fn volume_requests() {
+        let daemon = MockDaemon::start().await.unwrap();
+        let volume = json!({ "Name": "data", "Driver": "local", "Mountpoint": "/var/lib/docker/volumes/data/_data" });
+        daemon.add_response(
+            Method::GET,
+            "/volumes",
+            StatusCode::OK,
+            &json!({ "Volumes": [volume.clone()], "Warnings": [] }),
+        );
+        daemon.add_response(Method::POST, "/volumes/create", StatusCode::CREATED, &volume);
+        daemon.add_raw_response(
+            Method::DELETE,
+            "/volumes/data",
+            StatusCode::NO_CONTENT,
+            "text/plain",
+            Bytes::new(),
+        );
+        daemon.add_response(
+            Method::POST,
+            "/volumes/prune",
+            StatusCode::OK,
+            &json!({ "VolumesDeleted": ["old"], "SpaceReclaimed": 4096 }),
+        );
+        let client = daemon.client();
+
+        let options = ListVolumesOptions {
+            filters: HashMap::from([("dangling".to_string(), vec!["true".to_string()])]),
+        };
+        let volumes = client.list_volumes(Some(options)).await.unwrap();
+        assert_eq!(volumes.volumes[0].name, "data");
+
+        let config = VolumeCreateOptions {
+            name: Some("data".to_string()),
+            labels: Some(HashMap::from([("app".to_string(), "web".to_string())])),
+            ..Default::default()
+        };
+        assert_eq!(
+            client.create_volume(config).await.unwrap().mountpoint,
+            "/var/lib/docker/volumes/data/_data"
+        );
+
+        client
+            .remove_volume(
+                "data",
+                Some(RemoveVolumeOptions {
+                    force: true,
+                }),
+            )
+            .await
+            .unwrap();
+
+        let options = PruneVolumesOptions {
+            filters: HashMap::from([("label".to_string(), vec!["app=web".to_string()])]),
+        };
+        let pruned = client.prune_volumes(Some(options)).await.unwrap();
+        assert_eq!(pruned.volumes_deleted, Some(vec!["old".to_string()]));
+        assert_eq!(pruned.space_reclaimed, Some(4096));
+
+        let requests: Vec<_> = daemon.requests().into_iter().skip(1).collect();
+        let paths: Vec<_> = requests
+            .iter()
+            .map(|request| (request.method.as_str(), request.path.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("GET", "/volumes"),
+                ("POST", "/volumes/create"),
+                ("DELETE", "/volumes/data"),
+                ("POST", "/volumes/prune"),
+            ]
+        );
+        assert_eq!(requests[0].query["filters"], r#"{"dangling":["true"]}"#);
+        assert_eq!(
+            serde_json::from_slice::<Value>(&requests[1].body).unwrap(),
+            json!({ "Name": "data", "Labels": { "app": "web" } })
+        );
+        assert_eq!(requests[2].query["force"], "true");
+        assert_eq!(requests[3].query["filters"], r#"{"label":["app=web"]}"#);
+        assert!(requests[3].body.is_empty());
+    }
+}
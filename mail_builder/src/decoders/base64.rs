@@ -0,0 +1,70 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+/// Decodes base64 data, ignoring line breaks and whitespace.
+/// Returns `None` if the input contains invalid characters.
+pub fn base64_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buf: u32 = 0;
+    let mut bits = 0;
+
+    for &ch in input {
+        let value = match ch {
+            b'A'..=b'Z' => ch - b'A',
+            b'a'..=b'z' => ch - b'a' + 26,
+            b'0'..=b'9' => ch - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            b' ' | b'\t' | b'\r' | b'\n' => continue,
+            _ => return None,
+        };
+
+        buf = ((buf << 6) | u32::from(value)) & 0xffffff;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buf >> bits) as u8);
+        }
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encoders::base64::base64_encode_mime;
+
+    #[test]
+    fn decode_base64() {
+        for (encoded, expected_result) in [
+            ("", ""),
+            ("YQ==", "a"),
+            ("YWI=", "ab"),
+            ("YWJj", "abc"),
+            ("YWJj\r\nZGVm\r\n", "abcdef"),
+            ("w6HDqcOtw7PDug", "áéíóú"),
+        ] {
+            assert_eq!(
+                super::base64_decode(encoded.as_bytes()).unwrap(),
+                expected_result.as_bytes(),
+                "failed for {encoded:?}"
+            );
+        }
+
+        assert_eq!(super::base64_decode(b"YW*j"), None);
+
+        let input = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+        let mut encoded = Vec::new();
+        base64_encode_mime(&input, &mut encoded, false).unwrap();
+        assert_eq!(super::base64_decode(&encoded).unwrap(), input);
+    }
+}
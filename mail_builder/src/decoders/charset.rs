@@ -0,0 +1,56 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+// Code points of the 0x80 - 0x9F range of windows-1252, the rest of the
+// range matches ISO-8859-1.
+static WINDOWS_1252: &[char] = &[
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}', '\u{02C6}',
+    '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}', '\u{0090}', '\u{2018}',
+    '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}', '\u{02DC}', '\u{2122}', '\u{0161}',
+    '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Returns true when text in the charset can be read as UTF-8.
+pub fn is_utf8(charset: &str) -> bool {
+    ["utf-8", "utf8", "us-ascii", "ascii"]
+        .iter()
+        .any(|name| charset.trim().eq_ignore_ascii_case(name))
+}
+
+/// Converts text in the given charset to UTF-8. Supports UTF-8, US-ASCII,
+/// ISO-8859-1 and windows-1252; returns `None` for other charsets or for
+/// invalid UTF-8.
+pub fn decode_charset(charset: &str, input: &[u8]) -> Option<String> {
+    let charset = charset.trim().to_ascii_lowercase();
+
+    if is_utf8(&charset) {
+        String::from_utf8(input.to_vec()).ok()
+    } else {
+        match charset.as_str() {
+            "iso-8859-1" | "iso8859-1" | "iso_8859-1" | "latin1" | "l1" => {
+                Some(input.iter().map(|&ch| char::from(ch)).collect())
+            }
+            "windows-1252" | "cp1252" => Some(
+                input
+                    .iter()
+                    .map(|&ch| {
+                        if (0x80..0xa0).contains(&ch) {
+                            WINDOWS_1252[usize::from(ch - 0x80)]
+                        } else {
+                            char::from(ch)
+                        }
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
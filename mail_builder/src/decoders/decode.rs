@@ -0,0 +1,125 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::borrow::Cow;
+
+use super::{base64::base64_decode, charset::decode_charset, quoted_printable::quoted_printable_decode};
+
+/// Decodes the RFC2047 encoded words of a header value.
+///
+/// Whitespace between adjacent encoded words is dropped, and the bytes of
+/// adjacent encoded words sharing a charset are joined before being converted,
+/// as a multi-byte character may be split across two encoded words.
+pub fn rfc2047_decode(input: &str) -> Cow<'_, str> {
+    if !input.contains("=?") {
+        return Cow::Borrowed(input);
+    }
+
+    let mut output = String::with_capacity(input.len());
+    let mut pending: Option<(String, Vec<u8>)> = None;
+    let mut is_prev_word = false;
+    let mut rest = input;
+
+    while let Some(start) = rest.find("=?") {
+        let (before, candidate) = rest.split_at(start);
+
+        if let Some((charset, bytes, len)) = parse_encoded_word(candidate) {
+            if !is_prev_word || !before.chars().all(|ch| ch.is_ascii_whitespace()) {
+                flush_pending(&mut pending, &mut output);
+                output.push_str(before);
+            }
+            match &mut pending {
+                Some((pending_charset, pending_bytes)) if pending_charset.eq_ignore_ascii_case(&charset) => {
+                    pending_bytes.extend(bytes);
+                }
+                _ => {
+                    flush_pending(&mut pending, &mut output);
+                    pending = Some((charset, bytes));
+                }
+            }
+            rest = &candidate[len..];
+            is_prev_word = true;
+        } else {
+            flush_pending(&mut pending, &mut output);
+            output.push_str(before);
+            output.push_str("=?");
+            rest = &candidate[2..];
+            is_prev_word = false;
+        }
+    }
+
+    flush_pending(&mut pending, &mut output);
+    output.push_str(rest);
+
+    Cow::Owned(output)
+}
+
+fn flush_pending(pending: &mut Option<(String, Vec<u8>)>, output: &mut String) {
+    if let Some((charset, bytes)) = pending.take() {
+        match decode_charset(&charset, &bytes) {
+            Some(text) => output.push_str(&text),
+            None => output.push_str(&String::from_utf8_lossy(&bytes)),
+        }
+    }
+}
+
+// Parses an encoded word at the start of the input, returning its charset,
+// decoded bytes and length.
+fn parse_encoded_word(input: &str) -> Option<(String, Vec<u8>, usize)> {
+    let (charset, rest) = input.strip_prefix("=?")?.split_once('?')?;
+    let (encoding, rest) = rest.split_once('?')?;
+    let text = &rest[..rest.find("?=")?];
+
+    if charset.is_empty()
+        || [charset, encoding, text]
+            .iter()
+            .any(|s| s.contains(char::is_whitespace))
+    {
+        return None;
+    }
+
+    let bytes = match encoding {
+        "B" | "b" => base64_decode(text.as_bytes())?,
+        "Q" | "q" => quoted_printable_decode(text.as_bytes(), true),
+        _ => return None,
+    };
+
+    // RFC2231 allows a language suffix, as in "utf-8*en"
+    let len = charset.len() + encoding.len() + text.len() + 6;
+    let charset = charset.split('*').next().unwrap_or(charset).to_string();
+
+    Some((charset, bytes, len))
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn decode_rfc2047() {
+        for (input, expected_result) in [
+            ("plain text", "plain text"),
+            ("=?utf-8?Q?hello_world?=", "hello world"),
+            ("=?us-ascii?Q?hello_=3F_world?=", "hello ? world"),
+            ("=?UTF-8?B?w6HDqcOtw7PDug==?=", "áéíóú"),
+            ("before =?utf-8?Q?=C3=B1and=C3=BA?= after", "before ñandú after"),
+            ("=?utf-8?Q?a?= =?utf-8?Q?b?=\r\n\t=?utf-8?Q?c?=", "abc"),
+            ("=?utf-8?Q?a?= b =?utf-8?Q?c?=", "a b c"),
+            // Multi-byte character split between two encoded words
+            ("=?utf-8?Q?=EC=95?= =?utf-8?Q?=88?=", "안"),
+            ("=?iso-8859-1?Q?caf=E9?=", "café"),
+            ("=?windows-1252?Q?=93quoted=94?=", "\u{201C}quoted\u{201D}"),
+            ("=?utf-8*en?Q?language?=", "language"),
+            ("=?utf-8?X?unknown?= =?broken", "=?utf-8?X?unknown?= =?broken"),
+        ] {
+            assert_eq!(super::rfc2047_decode(input), expected_result, "failed for {input:?}");
+        }
+    }
+}
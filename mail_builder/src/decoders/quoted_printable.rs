@@ -0,0 +1,91 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+/// Decodes quoted-printable data. When `is_inline` is set, the input is
+/// treated as the text of an RFC2047 "Q" encoded word, where `_` is a space.
+/// Malformed escape sequences are copied as-is.
+pub fn quoted_printable_decode(input: &[u8], is_inline: bool) -> Vec<u8> {
+    let mut output = Vec::with_capacity(input.len());
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let ch = input[pos];
+        pos += 1;
+
+        match ch {
+            b'=' => {
+                if let Some(byte) = input
+                    .get(pos..pos + 2)
+                    .and_then(|hex| Some(hex_value(hex[0])? << 4 | hex_value(hex[1])?))
+                {
+                    output.push(byte);
+                    pos += 2;
+                } else {
+                    // Soft line break, possibly preceded by trailing whitespace
+                    let mut end = pos;
+                    while matches!(input.get(end), Some(b' ' | b'\t')) {
+                        end += 1;
+                    }
+                    match input.get(end..) {
+                        Some([b'\r', b'\n', ..]) => pos = end + 2,
+                        Some([b'\n', ..]) => pos = end + 1,
+                        _ => output.push(b'='),
+                    }
+                }
+            }
+            b'_' if is_inline => output.push(b' '),
+            _ => output.push(ch),
+        }
+    }
+
+    output
+}
+
+fn hex_value(ch: u8) -> Option<u8> {
+    match ch {
+        b'0'..=b'9' => Some(ch - b'0'),
+        b'A'..=b'F' => Some(ch - b'A' + 10),
+        b'a'..=b'f' => Some(ch - b'a' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::encoders::quoted_printable::quoted_printable_encode;
+
+    #[test]
+    fn decode_quoted_printable() {
+        for (encoded, expected_result, is_inline) in [
+            ("hello world", "hello world", false),
+            ("hello_world", "hello_world", false),
+            ("hello_=3F_world_=3F", "hello ? world ?", true),
+            ("hello =3D world =3D", "hello = world =", false),
+            ("hello  =20\r\nworld  =20\r\n", "hello   \r\nworld   \r\n", false),
+            ("soft =\r\nline =  \r\nbreaks", "soft line breaks", false),
+            ("=C3=A1=c3=a9", "áé", false),
+            ("bad =ZZ escape =", "bad =ZZ escape =", false),
+        ] {
+            assert_eq!(
+                String::from_utf8(super::quoted_printable_decode(encoded.as_bytes(), is_inline)).unwrap(),
+                expected_result,
+                "failed for {encoded:?}"
+            );
+        }
+
+        let input = "안녕하세요 세계 = ".repeat(20);
+        for (is_inline, is_body) in [(false, true), (false, false), (true, false)] {
+            let mut encoded = Vec::new();
+            quoted_printable_encode(input.as_bytes(), &mut encoded, is_inline, is_body).unwrap();
+            assert_eq!(super::quoted_printable_decode(&encoded, is_inline), input.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use super::Canonicalization;
+
+/// A header field as it appears in the message. `value` includes everything
+/// after the colon, folding and the final line break included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderField<'x> {
+    pub name: &'x [u8],
+    pub value: &'x [u8],
+}
+
+impl<'x> HeaderField<'x> {
+    pub fn name_eq(&self, name: &str) -> bool {
+        self.name.trim_ascii().eq_ignore_ascii_case(name.as_bytes())
+    }
+}
+
+/// Splits a message into its header fields and its body.
+pub fn split_message(message: &[u8]) -> (Vec<HeaderField<'_>>, &[u8]) {
+    // Start, colon and end offsets of each header
+    let mut fields: Vec<(usize, usize, usize)> = Vec::new();
+    let mut body_start = message.len();
+    let mut pos = 0;
+
+    while pos < message.len() {
+        let line_end = message[pos..]
+            .iter()
+            .position(|&ch| ch == b'\n')
+            .map_or(message.len(), |end| pos + end + 1);
+        let line = &message[pos..line_end];
+
+        if line == b"\r\n" || line == b"\n" {
+            body_start = line_end;
+            break;
+        } else if matches!(line[0], b' ' | b'\t') {
+            if let Some((_, _, end)) = fields.last_mut() {
+                *end = line_end;
+            }
+        } else if let Some(colon) = line.iter().position(|&ch| ch == b':') {
+            fields.push((pos, pos + colon, line_end));
+        }
+
+        pos = line_end;
+    }
+
+    (
+        fields
+            .into_iter()
+            .map(|(start, colon, end)| HeaderField {
+                name: &message[start..colon],
+                value: &message[colon + 1..end],
+            })
+            .collect(),
+        &message[body_start..],
+    )
+}
+
+/// Appends the canonicalized header to `output`, followed by CRLF.
+pub fn canonicalize_header(name: &[u8], value: &[u8], canonicalization: Canonicalization, output: &mut Vec<u8>) {
+    match canonicalization {
+        Canonicalization::Relaxed => {
+            output.extend(name.trim_ascii().iter().map(|ch| ch.to_ascii_lowercase()));
+            output.push(b':');
+
+            let mut is_space = false;
+            let mut is_empty = true;
+            for &ch in value {
+                match ch {
+                    b'\r' | b'\n' => (),
+                    b' ' | b'\t' => is_space = true,
+                    _ => {
+                        if is_space && !is_empty {
+                            output.push(b' ');
+                        }
+                        output.push(ch);
+                        is_space = false;
+                        is_empty = false;
+                    }
+                }
+            }
+            output.extend_from_slice(b"\r\n");
+        }
+        Canonicalization::Simple => {
+            output.extend_from_slice(name);
+            output.push(b':');
+            output.extend_from_slice(value);
+            if !value.ends_with(b"\n") {
+                output.extend_from_slice(b"\r\n");
+            }
+        }
+    }
+}
+
+/// Returns the canonicalized body.
+pub fn canonicalize_body(body: &[u8], canonicalization: Canonicalization) -> Vec<u8> {
+    let mut output = Vec::with_capacity(body.len());
+    let body = body.strip_suffix(b"\n").unwrap_or(body);
+
+    if !body.is_empty() {
+        for line in body.split(|&ch| ch == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            match canonicalization {
+                Canonicalization::Relaxed => {
+                    let mut is_space = false;
+                    for &ch in line {
+                        if ch == b' ' || ch == b'\t' {
+                            is_space = true;
+                        } else {
+                            if is_space {
+                                output.push(b' ');
+                            }
+                            output.push(ch);
+                            is_space = false;
+                        }
+                    }
+                }
+                Canonicalization::Simple => output.extend_from_slice(line),
+            }
+            output.extend_from_slice(b"\r\n");
+        }
+    }
+
+    // Remove the empty lines at the end of the body
+    while output.ends_with(b"\r\n\r\n") {
+        output.truncate(output.len() - 2);
+    }
+    if output == b"\r\n" && canonicalization == Canonicalization::Relaxed {
+        output.clear();
+    } else if output.is_empty() && canonicalization == Canonicalization::Simple {
+        output.extend_from_slice(b"\r\n");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonicalize_body, canonicalize_header, split_message};
+    use crate::dkim::Canonicalization;
+
+    #[test]
+    fn canonicalize() {
+        // Example from RFC 6376, section 3.4.5
+        let message = b"A: X\r\nB : Y\t\r\n\tZ  \r\n\r\n C \r\nD \t E\r\n\r\n\r\n";
+        let (headers, body) = split_message(message);
+        assert_eq!(headers.len(), 2);
+
+        for (canonicalization, expected_headers, expected_body) in [
+            (Canonicalization::Relaxed, "a:X\r\nb:Y Z\r\n", " C\r\nD E\r\n"),
+            (Canonicalization::Simple, "A: X\r\nB : Y\t\r\n\tZ  \r\n", " C \r\nD \t E\r\n"),
+        ] {
+            let mut output = Vec::new();
+            for header in &headers {
+                canonicalize_header(header.name, header.value, canonicalization, &mut output);
+            }
+            assert_eq!(String::from_utf8(output).unwrap(), expected_headers);
+            assert_eq!(
+                String::from_utf8(canonicalize_body(body, canonicalization)).unwrap(),
+                expected_body
+            );
+        }
+
+        for (body, relaxed, simple) in [
+            ("", "", "\r\n"),
+            ("\r\n\r\n", "", "\r\n"),
+            ("text", "text\r\n", "text\r\n"),
+            ("  \r\n", "", "  \r\n"),
+        ] {
+            assert_eq!(
+                canonicalize_body(body.as_bytes(), Canonicalization::Relaxed),
+                relaxed.as_bytes()
+            );
+            assert_eq!(canonicalize_body(body.as_bytes(), Canonicalization::Simple), simple.as_bytes());
+        }
+    }
+}
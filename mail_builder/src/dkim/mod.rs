@@ -0,0 +1,245 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+//! DKIM (_RFC 6376_) signing and verification, with the RSA-SHA256 and
+//! Ed25519-SHA256 (_RFC 8463_) algorithms.
+
+pub mod canonicalize;
+pub mod sign;
+pub mod verify;
+
+use std::{collections::HashMap, fmt, io};
+
+use aws_lc_rs::{
+    encoding::AsDer,
+    rsa::KeyPair as RsaKeyPair,
+    signature::{Ed25519KeyPair, KeyPair},
+};
+
+pub use self::{
+    sign::DkimSigner,
+    verify::{DkimVerification, verify},
+};
+use crate::{decoders::base64::base64_decode, encoders::base64::base64_encode};
+
+/// Headers signed by default, when present in the message.
+pub static DEFAULT_SIGNED_HEADERS: &[&str] = &[
+    "From",
+    "Sender",
+    "Reply-To",
+    "To",
+    "Cc",
+    "Subject",
+    "Date",
+    "Message-ID",
+    "In-Reply-To",
+    "References",
+    "MIME-Version",
+    "Content-Type",
+    "Content-Transfer-Encoding",
+    "List-Unsubscribe",
+    "List-Unsubscribe-Post",
+];
+
+/// Header and body canonicalization algorithms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Canonicalization {
+    #[default]
+    Relaxed,
+    Simple,
+}
+
+impl Canonicalization {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Canonicalization::Relaxed => "relaxed",
+            Canonicalization::Simple => "simple",
+        }
+    }
+}
+
+/// Signing algorithms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    RsaSha256,
+    Ed25519Sha256,
+}
+
+impl Algorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Algorithm::RsaSha256 => "rsa-sha256",
+            Algorithm::Ed25519Sha256 => "ed25519-sha256",
+        }
+    }
+
+    // The "k=" tag of the DNS record
+    fn key_type(&self) -> &'static str {
+        match self {
+            Algorithm::RsaSha256 => "rsa",
+            Algorithm::Ed25519Sha256 => "ed25519",
+        }
+    }
+}
+
+/// Private key used to sign messages
+pub enum DkimKey {
+    Rsa(RsaKeyPair),
+    Ed25519(Ed25519KeyPair),
+}
+
+impl DkimKey {
+    /// Load an RSA key from a DER encoded PKCS#1 `RSAPrivateKey`
+    pub fn rsa_from_pkcs1_der(der: &[u8]) -> Result<Self, DkimError> {
+        RsaKeyPair::from_der(der)
+            .map(DkimKey::Rsa)
+            .map_err(|_| DkimError::InvalidKey)
+    }
+
+    /// Load an RSA key from a DER encoded PKCS#8 document
+    pub fn rsa_from_pkcs8_der(der: &[u8]) -> Result<Self, DkimError> {
+        RsaKeyPair::from_pkcs8(der)
+            .map(DkimKey::Rsa)
+            .map_err(|_| DkimError::InvalidKey)
+    }
+
+    /// Load an Ed25519 key from a DER encoded PKCS#8 document
+    pub fn ed25519_from_pkcs8_der(der: &[u8]) -> Result<Self, DkimError> {
+        Ed25519KeyPair::from_pkcs8_maybe_unchecked(der)
+            .map(DkimKey::Ed25519)
+            .map_err(|_| DkimError::InvalidKey)
+    }
+
+    /// Load an Ed25519 key from its 32 bytes seed
+    pub fn ed25519_from_seed(seed: &[u8]) -> Result<Self, DkimError> {
+        Ed25519KeyPair::from_seed_unchecked(seed)
+            .map(DkimKey::Ed25519)
+            .map_err(|_| DkimError::InvalidKey)
+    }
+
+    /// Load a key from a PEM encoded "RSA PRIVATE KEY" (PKCS#1) or
+    /// "PRIVATE KEY" (PKCS#8, RSA or Ed25519) block.
+    pub fn from_pem(pem: &str) -> Result<Self, DkimError> {
+        let (label, contents) = pem
+            .trim()
+            .strip_prefix("-----BEGIN ")
+            .and_then(|pem| pem.split_once("-----"))
+            .ok_or(DkimError::InvalidKey)?;
+        let contents = contents
+            .split_once("-----END ")
+            .map(|(contents, _)| contents)
+            .ok_or(DkimError::InvalidKey)?;
+        let der = base64_decode(contents.as_bytes()).ok_or(DkimError::InvalidKey)?;
+
+        match label {
+            "RSA PRIVATE KEY" => Self::rsa_from_pkcs1_der(&der),
+            "PRIVATE KEY" => Self::ed25519_from_pkcs8_der(&der).or_else(|_| Self::rsa_from_pkcs8_der(&der)),
+            _ => Err(DkimError::InvalidKey),
+        }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        match self {
+            DkimKey::Rsa(_) => Algorithm::RsaSha256,
+            DkimKey::Ed25519(_) => Algorithm::Ed25519Sha256,
+        }
+    }
+
+    /// Returns the TXT record to publish at `<selector>._domainkey.<domain>`,
+    /// such as "v=DKIM1; k=ed25519; p=11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo=".
+    pub fn dns_record(&self) -> Result<String, DkimError> {
+        let public_key = match self {
+            DkimKey::Rsa(key) => key
+                .public_key()
+                .as_der()
+                .map_err(|_| DkimError::InvalidKey)?
+                .as_ref()
+                .to_vec(),
+            DkimKey::Ed25519(key) => key.public_key().as_ref().to_vec(),
+        };
+        let public_key = base64_encode(&public_key).map_err(|_| DkimError::InvalidKey)?;
+
+        Ok(format!(
+            "v=DKIM1; k={}; p={}",
+            self.algorithm().key_type(),
+            String::from_utf8_lossy(&public_key)
+        ))
+    }
+}
+
+/// Looks up the DNS TXT records holding the public keys of the signers.
+pub trait DnsResolver {
+    /// Returns the TXT records of `name`, with the strings of each record
+    /// concatenated. An empty list is returned if the name does not exist.
+    fn txt_lookup(&self, name: &str) -> io::Result<Vec<String>>;
+}
+
+/// An in-memory key store, mapping names to TXT records.
+impl DnsResolver for HashMap<String, String> {
+    fn txt_lookup(&self, name: &str) -> io::Result<Vec<String>> {
+        Ok(self
+            .get(name.trim_end_matches('.'))
+            .map(|record| vec![record.clone()])
+            .unwrap_or_default())
+    }
+}
+
+/// DKIM signing and verification errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DkimError {
+    /// The private or public key could not be parsed
+    InvalidKey,
+    /// The message could not be signed
+    SigningFailed,
+    /// The message has no From header, which must be signed
+    FromNotSigned,
+    /// The DKIM-Signature header is malformed or misses required tags
+    MalformedSignature,
+    UnsupportedVersion,
+    UnsupportedAlgorithm,
+    UnsupportedCanonicalization,
+    SignatureExpired,
+    /// The hash of the body does not match the "bh=" tag
+    BodyHashMismatch,
+    /// The signature does not match the signed headers
+    SignatureMismatch,
+    /// No public key record was found for the selector and domain
+    KeyNotFound,
+    /// The public key record has an empty "p=" tag
+    KeyRevoked,
+    /// The algorithm of the signature does not match the public key record
+    IncompatibleAlgorithms,
+    /// The DNS lookup failed, and may succeed later
+    Dns(String),
+}
+
+impl fmt::Display for DkimError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DkimError::InvalidKey => write!(f, "Invalid DKIM key"),
+            DkimError::SigningFailed => write!(f, "Failed to sign the message"),
+            DkimError::FromNotSigned => write!(f, "The From header is not signed"),
+            DkimError::MalformedSignature => write!(f, "Malformed DKIM-Signature header"),
+            DkimError::UnsupportedVersion => write!(f, "Unsupported DKIM version"),
+            DkimError::UnsupportedAlgorithm => write!(f, "Unsupported signature algorithm"),
+            DkimError::UnsupportedCanonicalization => write!(f, "Unsupported canonicalization"),
+            DkimError::SignatureExpired => write!(f, "The signature has expired"),
+            DkimError::BodyHashMismatch => write!(f, "The body hash does not match"),
+            DkimError::SignatureMismatch => write!(f, "The signature does not match"),
+            DkimError::KeyNotFound => write!(f, "No DKIM key found"),
+            DkimError::KeyRevoked => write!(f, "The DKIM key has been revoked"),
+            DkimError::IncompatibleAlgorithms => write!(f, "The signature and key algorithms differ"),
+            DkimError::Dns(err) => write!(f, "DNS lookup failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for DkimError {}
@@ -0,0 +1,203 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::time::SystemTime;
+
+use aws_lc_rs::{
+    digest::{SHA256, digest},
+    rand::SystemRandom,
+    signature::RSA_PKCS1_SHA256,
+};
+
+use super::{
+    Canonicalization, DEFAULT_SIGNED_HEADERS, DkimError, DkimKey,
+    canonicalize::{canonicalize_body, canonicalize_header, split_message},
+};
+use crate::encoders::base64::base64_encode;
+
+/// Signs messages with a DKIM key.
+pub struct DkimSigner {
+    key: DkimKey,
+    domain: String,
+    selector: String,
+    headers: Vec<String>,
+    header_canonicalization: Canonicalization,
+    body_canonicalization: Canonicalization,
+    expiration: Option<u64>,
+}
+
+impl DkimSigner {
+    /// Create a new signer for the domain. The public key has to be published
+    /// in a TXT record at `<selector>._domainkey.<domain>`.
+    pub fn new(key: DkimKey, domain: impl Into<String>, selector: impl Into<String>) -> Self {
+        Self {
+            key,
+            domain: domain.into(),
+            selector: selector.into(),
+            headers: DEFAULT_SIGNED_HEADERS.iter().map(|header| header.to_string()).collect(),
+            header_canonicalization: Canonicalization::Relaxed,
+            body_canonicalization: Canonicalization::Relaxed,
+            expiration: None,
+        }
+    }
+
+    /// Set the headers to sign, when present in the message. The From header
+    /// is always required.
+    pub fn headers<T, U>(mut self, headers: T) -> Self
+    where
+        T: IntoIterator<Item = U>,
+        U: Into<String>,
+    {
+        self.headers = headers.into_iter().map(|header| header.into()).collect();
+        self
+    }
+
+    /// Set the header canonicalization algorithm, relaxed by default.
+    pub fn header_canonicalization(mut self, value: Canonicalization) -> Self {
+        self.header_canonicalization = value;
+        self
+    }
+
+    /// Set the body canonicalization algorithm, relaxed by default.
+    pub fn body_canonicalization(mut self, value: Canonicalization) -> Self {
+        self.body_canonicalization = value;
+        self
+    }
+
+    /// Set the number of seconds after which the signature expires.
+    pub fn expiration(mut self, seconds: u64) -> Self {
+        self.expiration = Some(seconds);
+        self
+    }
+
+    /// Sign the message, returning the DKIM-Signature header to prepend to it,
+    /// including the final CRLF.
+    pub fn sign(&self, message: &[u8]) -> Result<String, DkimError> {
+        self.sign_at(
+            message,
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        )
+    }
+
+    /// Sign the message with the given signature timestamp.
+    pub fn sign_at(&self, message: &[u8], timestamp: u64) -> Result<String, DkimError> {
+        let (headers, body) = split_message(message);
+        let body_hash = digest(&SHA256, &canonicalize_body(body, self.body_canonicalization));
+
+        // Sign the last instance of each header first, as verifiers do
+        let mut data = Vec::with_capacity(1024);
+        let mut signed_headers = Vec::new();
+        let mut is_signed = vec![false; headers.len()];
+        for name in &self.headers {
+            if let Some(pos) = (0..headers.len())
+                .rev()
+                .find(|&pos| !is_signed[pos] && headers[pos].name_eq(name))
+            {
+                is_signed[pos] = true;
+                canonicalize_header(headers[pos].name, headers[pos].value, self.header_canonicalization, &mut data);
+                signed_headers.push(name.as_str());
+            }
+        }
+        if !signed_headers.iter().any(|name| name.eq_ignore_ascii_case("From")) {
+            return Err(DkimError::FromNotSigned);
+        }
+
+        let mut header = FoldedHeader::new("DKIM-Signature:");
+        header.push(" ", "v=1;");
+        header.push(" ", &format!("a={};", self.key.algorithm().as_str()));
+        header.push(" ", &format!("s={};", self.selector));
+        header.push(" ", &format!("d={};", self.domain));
+        header.push(
+            " ",
+            &format!(
+                "c={}/{};",
+                self.header_canonicalization.as_str(),
+                self.body_canonicalization.as_str()
+            ),
+        );
+        header.push(" ", &format!("t={};", timestamp));
+        if let Some(expiration) = self.expiration {
+            header.push(" ", &format!("x={};", timestamp + expiration));
+        }
+        for (pos, name) in signed_headers.iter().enumerate() {
+            match (pos == 0, pos == signed_headers.len() - 1) {
+                (true, true) => header.push(" ", &format!("h={};", name)),
+                (true, false) => header.push(" ", &format!("h={}:", name)),
+                (false, true) => header.push("", &format!("{};", name)),
+                (false, false) => header.push("", &format!("{}:", name)),
+            }
+        }
+        header.push(" ", &format!("bh={};", base64(body_hash.as_ref())?));
+        header.push(" ", "b=");
+
+        // The signature covers its own header, with an empty "b=" tag and no final CRLF
+        canonicalize_header(
+            b"DKIM-Signature",
+            &header.value.as_bytes()[15..],
+            self.header_canonicalization,
+            &mut data,
+        );
+        data.truncate(data.len() - 2);
+
+        let signature = match &self.key {
+            DkimKey::Rsa(key) => {
+                let mut signature = vec![0; key.public_modulus_len()];
+                key.sign(&RSA_PKCS1_SHA256, &SystemRandom::new(), &data, &mut signature)
+                    .map_err(|_| DkimError::SigningFailed)?;
+                signature
+            }
+            DkimKey::Ed25519(key) => key.sign(digest(&SHA256, &data).as_ref()).as_ref().to_vec(),
+        };
+        for chunk in base64(&signature)?.as_bytes().chunks(64) {
+            header.push("", &String::from_utf8_lossy(chunk));
+        }
+        header.value.push_str("\r\n");
+
+        Ok(header.value)
+    }
+}
+
+fn base64(input: &[u8]) -> Result<String, DkimError> {
+    base64_encode(input)
+        .ok()
+        .and_then(|output| String::from_utf8(output).ok())
+        .ok_or(DkimError::SigningFailed)
+}
+
+// Builds a header, folding lines before they reach 76 characters
+struct FoldedHeader {
+    value: String,
+    line_len: usize,
+}
+
+impl FoldedHeader {
+    fn new(name: &str) -> Self {
+        Self {
+            value: name.to_string(),
+            line_len: name.len(),
+        }
+    }
+
+    fn push(&mut self, separator: &str, text: &str) {
+        if self.line_len + separator.len() + text.len() > 76 {
+            self.value.push_str("\r\n\t");
+            self.line_len = 1;
+        } else {
+            self.value.push_str(separator);
+            self.line_len += separator.len();
+        }
+        self.value.push_str(text);
+        self.line_len += text.len();
+    }
+}
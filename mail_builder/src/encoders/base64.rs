@@ -9,7 +9,7 @@
  * except according to those terms.
  */
 
-use std::io::{self, Write};
+use std::io::{self, ErrorKind, Read, Write};
 
 const CHARPAD: u8 = b'=';
 
@@ -120,6 +120,37 @@ pub fn base64_encode_mime(input: &[u8], mut output: impl Write, is_inline: bool)
     Ok(bytes_written)
 }
 
+// A multiple of the 57 bytes encoded in each 76 character line
+const STREAM_BUF_SIZE: usize = 57 * 1024;
+
+/// Base64 encode a reader as a MIME body, without holding more than a
+/// fixed size buffer in memory.
+pub fn base64_encode_stream(mut input: impl Read, mut output: impl Write) -> io::Result<usize> {
+    let mut buf = vec![0u8; STREAM_BUF_SIZE];
+    let mut buf_len = 0;
+    let mut bytes_written = 0;
+
+    loop {
+        match input.read(&mut buf[buf_len..]) {
+            Ok(0) => break,
+            Ok(read) => {
+                buf_len += read;
+                if buf_len == STREAM_BUF_SIZE {
+                    bytes_written += base64_encode_mime(&buf, &mut output, false)?;
+                    buf_len = 0;
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::Interrupted => (),
+            Err(err) => return Err(err),
+        }
+    }
+    if buf_len > 0 {
+        bytes_written += base64_encode_mime(&buf[..buf_len], &mut output, false)?;
+    }
+
+    Ok(bytes_written)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -157,6 +188,19 @@ mod tests {
             assert_eq!(std::str::from_utf8(&output).unwrap(), expected_result);
         }
     }
+
+    #[test]
+    fn encode_base64_stream() {
+        for len in [0, 1, 57, super::STREAM_BUF_SIZE, super::STREAM_BUF_SIZE + 1, 200_000] {
+            let input = (0..len).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+            let mut expected_result = Vec::new();
+            super::base64_encode_mime(&input, &mut expected_result, false).unwrap();
+            let mut output = Vec::new();
+            super::base64_encode_stream(input.as_slice(), &mut output).unwrap();
+            assert!(output == expected_result, "length {}", len);
+        }
+    }
 }
 
 /*
@@ -9,7 +9,10 @@
  * except according to those terms.
  */
 
-use std::io::{self, Write};
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+// Longer lines are split with soft line breaks before being encoded
+const STREAM_MAX_LINE: usize = 8192;
 
 pub fn quoted_printable_encode(
     input: &[u8],
@@ -92,6 +95,37 @@ pub fn quoted_printable_encode(
     Ok(bytes_written)
 }
 
+/// Quoted-printable encode a reader as a MIME text body, one line at a
+/// time.
+pub fn quoted_printable_encode_stream(input: impl Read, mut output: impl Write) -> io::Result<()> {
+    let mut input = BufReader::new(input);
+    let mut line = Vec::with_capacity(STREAM_MAX_LINE);
+
+    loop {
+        let limit = (STREAM_MAX_LINE - line.len()) as u64;
+        let read = input.by_ref().take(limit).read_until(b'\n', &mut line)?;
+
+        if line.ends_with(b"\n") || (read == 0 && !line.is_empty()) {
+            quoted_printable_encode(&line, &mut output, false, true)?;
+            line.clear();
+        } else if line.len() == STREAM_MAX_LINE {
+            // Keep a trailing CR with its LF
+            let split = if line.ends_with(b"\r") {
+                line.len() - 1
+            } else {
+                line.len()
+            };
+            quoted_printable_encode(&line[..split], &mut output, false, true)?;
+            output.write_all(b"=\r\n")?;
+            line.drain(..split);
+        } else if read == 0 {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -173,4 +207,30 @@ mod tests {
             assert_eq!(std::str::from_utf8(&output).unwrap(), expected_result_inline, "inline");
         }
     }
+
+    #[test]
+    fn encode_quoted_printable_stream() {
+        for input in [
+            "".to_string(),
+            "hello world".to_string(),
+            "hello   \nworld   \r\n   ".to_string(),
+            "Café crème\r\n".repeat(1000),
+        ] {
+            let mut expected_result = Vec::new();
+            super::quoted_printable_encode(input.as_bytes(), &mut expected_result, false, true).unwrap();
+            let mut output = Vec::new();
+            super::quoted_printable_encode_stream(input.as_bytes(), &mut output).unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), String::from_utf8(expected_result).unwrap());
+        }
+
+        let input = format!("{}\r\n{}", "a".repeat(super::STREAM_MAX_LINE - 1), "b".repeat(10));
+        let mut output = Vec::new();
+        super::quoted_printable_encode_stream(input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let mut expected_result = Vec::new();
+        super::quoted_printable_encode(input.as_bytes(), &mut expected_result, false, true).unwrap();
+        let max_line = |text: &str| text.split("\r\n").map(str::len).max().unwrap_or(0);
+        assert_eq!(max_line(&output), max_line(std::str::from_utf8(&expected_result).unwrap()));
+        assert!(output.ends_with("aaa=\r\n\r\nbbbbbbbbbb"), "{}", &output[output.len() - 20..]);
+    }
 }
@@ -0,0 +1,512 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+//! Conversion of HTML bodies to plain text, used to generate the
+//! text/plain alternative of messages that only have an HTML body.
+
+use std::borrow::Cow;
+
+/// Convert an HTML document to readable plain text. Headings are prefixed
+/// with `#`, list items with `*` or their number and quotes with `>`, and
+/// links are followed by their URL in angle brackets.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = TextWriter::default();
+    let mut links: Vec<Option<(Cow<str>, usize)>> = Vec::new();
+    // The last number of each ordered list, None for unordered ones
+    let mut lists: Vec<Option<usize>> = Vec::new();
+    let mut pos = 0;
+
+    while pos < html.len() {
+        let Some(offset) = html[pos..].find('<') else {
+            text.push_text(&decode_entities(&html[pos..]));
+            break;
+        };
+        text.push_text(&decode_entities(&html[pos..pos + offset]));
+        pos += offset;
+
+        if let Some(comment) = html[pos..].strip_prefix("<!--") {
+            pos = comment
+                .find("-->")
+                .map_or(html.len(), |end| html.len() - comment.len() + end + 3);
+            continue;
+        }
+        let Some(tag) = Tag::parse(&html[pos..]) else {
+            text.push_text("<");
+            pos += 1;
+            continue;
+        };
+        pos += tag.len;
+
+        match tag.name.as_str() {
+            "head" | "script" | "style" | "template" | "title" if !tag.is_closing => {
+                pos += find_closing_tag(&html[pos..], &tag.name).unwrap_or(html.len() - pos);
+            }
+            "br" => text.line_break(),
+            "hr" => {
+                text.block(2);
+                text.push_text("---");
+                text.block(2);
+            }
+            "p" | "table" | "dl" => text.block(2),
+            "div" | "tr" | "dt" | "dd" | "section" | "article" | "header" | "footer" | "nav" | "main" | "aside"
+            | "figure" | "figcaption" | "address" | "form" | "center" => text.block(1),
+            "td" | "th" => text.space(),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                text.block(2);
+                if !tag.is_closing {
+                    let level = (tag.name.as_bytes()[1] - b'0') as usize;
+                    text.marker = Some(format!("{} ", "#".repeat(level)));
+                }
+            }
+            "ul" | "ol" => {
+                if !tag.is_closing {
+                    text.block(if lists.is_empty() { 2 } else { 1 });
+                    lists.push((tag.name == "ol").then(|| {
+                        tag.attribute("start")
+                            .and_then(|start| start.trim().parse::<usize>().ok())
+                            .map_or(0, |start| start.saturating_sub(1))
+                    }));
+                } else {
+                    lists.pop();
+                    text.block(if lists.is_empty() { 2 } else { 1 });
+                }
+            }
+            "li" => {
+                text.block(1);
+                if !tag.is_closing {
+                    let indent = "  ".repeat(lists.len().saturating_sub(1));
+                    text.marker = Some(match lists.last_mut() {
+                        Some(Some(number)) => {
+                            *number += 1;
+                            format!("{}{}. ", indent, number)
+                        }
+                        _ => format!("{}* ", indent),
+                    });
+                }
+            }
+            "blockquote" => {
+                text.block(2);
+                if !tag.is_closing {
+                    text.quote_depth += 1;
+                } else {
+                    text.quote_depth = text.quote_depth.saturating_sub(1);
+                }
+            }
+            "pre" => {
+                text.block(2);
+                if !tag.is_closing {
+                    text.pre_depth += 1;
+                } else {
+                    text.pre_depth = text.pre_depth.saturating_sub(1);
+                }
+            }
+            "a" if !tag.is_closing => {
+                let href = tag
+                    .attribute("href")
+                    .map(|href| decode_entities(href.trim()))
+                    .filter(|href| {
+                        !href.is_empty()
+                            && !href.starts_with('#')
+                            && !href
+                                .get(..11)
+                                .is_some_and(|scheme| scheme.eq_ignore_ascii_case("javascript:"))
+                    });
+                links.push(href.map(|href| (href, text.text.len())));
+            }
+            "a" => {
+                if let Some((href, start)) = links.pop().flatten() {
+                    let contents = text.text[start..].trim();
+                    if contents.is_empty() {
+                        text.push_text(&href);
+                    } else if contents != href && Some(contents) != href.strip_prefix("mailto:") {
+                        text.space();
+                        text.push_text(&format!("<{}>", href));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    text.finish()
+}
+
+#[derive(Default)]
+struct TextWriter {
+    text: String,
+    // Line breaks to write before the next character
+    breaks: usize,
+    space: bool,
+    // The heading or list item prefix of the next line
+    marker: Option<String>,
+    quote_depth: usize,
+    // The quote depth of the last line written
+    line_quote_depth: usize,
+    pre_depth: usize,
+}
+
+impl TextWriter {
+    fn block(&mut self, breaks: usize) {
+        if !self.text.is_empty() {
+            self.breaks = self.breaks.max(breaks);
+        }
+        self.space = false;
+    }
+
+    fn line_break(&mut self) {
+        if !self.text.is_empty() {
+            self.breaks += 1;
+        }
+        self.space = false;
+    }
+
+    fn space(&mut self) {
+        self.space = true;
+    }
+
+    fn push_text(&mut self, text: &str) {
+        for ch in text.chars() {
+            if self.pre_depth > 0 {
+                match ch {
+                    '\n' => self.line_break(),
+                    '\r' => (),
+                    _ => self.push_char(ch),
+                }
+            } else if ch.is_whitespace() {
+                self.space = true;
+            } else {
+                self.push_char(ch);
+            }
+        }
+    }
+
+    fn push_char(&mut self, ch: char) {
+        let is_line_start = if self.text.is_empty() {
+            self.push_quote();
+            true
+        } else if self.breaks > 0 {
+            // Only preformatted text has more than one blank line
+            let breaks = if self.pre_depth > 0 {
+                self.breaks
+            } else {
+                self.breaks.min(2)
+            };
+            for line in 1..=breaks {
+                self.text.push('\n');
+                // Blank lines entering or leaving a quote belong to the outer one
+                if line == breaks {
+                    self.push_quote();
+                } else {
+                    self.push_quote_prefix(self.quote_depth.min(self.line_quote_depth));
+                }
+            }
+            self.breaks = 0;
+            true
+        } else {
+            false
+        };
+
+        if is_line_start {
+            if let Some(marker) = self.marker.take() {
+                self.text.push_str(&marker);
+            }
+        } else if self.space && !self.text.ends_with(' ') {
+            self.text.push(' ');
+        }
+        self.space = false;
+        self.text.push(ch);
+    }
+
+    fn push_quote(&mut self) {
+        self.line_quote_depth = self.quote_depth;
+        self.push_quote_prefix(self.quote_depth);
+    }
+
+    fn push_quote_prefix(&mut self, depth: usize) {
+        for _ in 0..depth {
+            self.text.push_str("> ");
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut text = String::with_capacity(self.text.len());
+        for line in self.text.lines() {
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+        text.truncate(text.trim_end().len());
+        text
+    }
+}
+
+struct Tag<'x> {
+    name: String,
+    is_closing: bool,
+    attributes: Vec<(String, &'x str)>,
+    len: usize,
+}
+
+impl<'x> Tag<'x> {
+    // Parses a tag at the start of the input, or returns None if the '<'
+    // does not start one
+    fn parse(input: &'x str) -> Option<Self> {
+        let bytes = input.as_bytes();
+        let is_closing = bytes.get(1) == Some(&b'/');
+        let name_start = if is_closing { 2 } else { 1 };
+
+        if !bytes.get(name_start).is_some_and(|ch| ch.is_ascii_alphabetic()) {
+            // Declarations and processing instructions are skipped
+            return matches!(bytes.get(1), Some(b'!' | b'?')).then(|| Tag {
+                name: String::new(),
+                is_closing: false,
+                attributes: Vec::new(),
+                len: input.find('>').map_or(input.len(), |end| end + 1),
+            });
+        }
+
+        let mut pos = name_start;
+        while bytes.get(pos).is_some_and(|ch| ch.is_ascii_alphanumeric()) {
+            pos += 1;
+        }
+        let name = input[name_start..pos].to_ascii_lowercase();
+
+        let mut attributes = Vec::new();
+        loop {
+            while bytes.get(pos).is_some_and(|&ch| ch.is_ascii_whitespace() || ch == b'/') {
+                pos += 1;
+            }
+            match bytes.get(pos) {
+                Some(b'>') => {
+                    pos += 1;
+                    break;
+                }
+                None => break,
+                _ => (),
+            }
+
+            let attribute_start = pos;
+            while bytes
+                .get(pos)
+                .is_some_and(|&ch| !ch.is_ascii_whitespace() && !matches!(ch, b'=' | b'>' | b'/'))
+            {
+                pos += 1;
+            }
+            let attribute = input[attribute_start..pos].to_ascii_lowercase();
+            while bytes.get(pos).is_some_and(|ch| ch.is_ascii_whitespace()) {
+                pos += 1;
+            }
+
+            let mut value = "";
+            if bytes.get(pos) == Some(&b'=') {
+                pos += 1;
+                while bytes.get(pos).is_some_and(|ch| ch.is_ascii_whitespace()) {
+                    pos += 1;
+                }
+                if let Some(&quote @ (b'"' | b'\'')) = bytes.get(pos) {
+                    let end = input[pos + 1..]
+                        .find(quote as char)
+                        .map_or(input.len(), |end| pos + 1 + end);
+                    value = &input[pos + 1..end];
+                    pos = (end + 1).min(input.len());
+                } else {
+                    let value_start = pos;
+                    while bytes
+                        .get(pos)
+                        .is_some_and(|&ch| !ch.is_ascii_whitespace() && ch != b'>')
+                    {
+                        pos += 1;
+                    }
+                    value = &input[value_start..pos];
+                }
+            }
+            attributes.push((attribute, value));
+        }
+
+        Some(Tag {
+            name,
+            is_closing,
+            attributes,
+            len: pos,
+        })
+    }
+
+    fn attribute(&self, name: &str) -> Option<&'x str> {
+        self.attributes
+            .iter()
+            .find_map(|(attribute, value)| (attribute == name).then_some(*value))
+    }
+}
+
+fn find_closing_tag(html: &str, name: &str) -> Option<usize> {
+    html.match_indices("</").map(|(pos, _)| pos).find(|&pos| {
+        html[pos + 2..]
+            .get(..name.len())
+            .is_some_and(|tag| tag.eq_ignore_ascii_case(name))
+    })
+}
+
+fn decode_entities(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('&') {
+        result.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        match rest[1..]
+            .find(';')
+            .filter(|&end| end <= 32)
+            .and_then(|end| Some((decode_entity(&rest[1..end + 1])?, end + 2)))
+        {
+            Some((ch, len)) => {
+                result.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+
+    Cow::Owned(result)
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(number) = entity.strip_prefix('#') {
+        return char::from_u32(match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        });
+    }
+
+    Some(match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "bull" => '•',
+        "middot" => '·',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "euro" => '€',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::html_to_text;
+    use crate::MessageBuilder;
+
+    #[test]
+    fn convert_html() {
+        for (html, expected_text) in [
+            ("", ""),
+            ("Hello,   <b>world</b>!", "Hello, world!"),
+            (
+                concat!(
+                    "<!DOCTYPE html>\n<html><head><title>Newsletter</title>",
+                    "<style>p { color: red; }</style></head>\n<body>\n",
+                    "<h1>Monthly   update</h1>\n<p>Hi Jane,<br>here is what&rsquo;s new:</p>\n",
+                    "<ul>\n  <li>Faster <a href=\"https://example.com/sync\">sync</a></li>\n",
+                    "  <li>Dark mode\n    <ol start=\"3\"><li>Auto</li><li>Manual</li></ol>\n  </li>\n</ul>\n",
+                    "<!-- tracking <p>pixel</p> -->\n<h2>Questions?</h2>\n",
+                    "<p>Write to <a href=\"mailto:help@example.com\">help@example.com</a> or ",
+                    "<a href=\"https://example.com/faq?a=1&amp;b=2\">read the FAQ</a>.</p>\n",
+                    "<blockquote><p>Great release!</p><p>&mdash; A user</p></blockquote>\n",
+                    "<pre>fn main() {\n    println!(\"&lt;3\");\n}</pre>\n",
+                    "<p><a href=\"#top\">Back to top</a> <a href=\"https://example.com/u\"></a></p>\n",
+                    "<script>alert('<p>');</script></body></html>"
+                ),
+                concat!(
+                    "# Monthly update\n",
+                    "\n",
+                    "Hi Jane,\n",
+                    "here is what’s new:\n",
+                    "\n",
+                    "* Faster sync <https://example.com/sync>\n",
+                    "* Dark mode\n",
+                    "  3. Auto\n",
+                    "  4. Manual\n",
+                    "\n",
+                    "## Questions?\n",
+                    "\n",
+                    "Write to help@example.com or read the FAQ <https://example.com/faq?a=1&b=2>.\n",
+                    "\n",
+                    "> Great release!\n",
+                    ">\n",
+                    "> — A user\n",
+                    "\n",
+                    "fn main() {\n",
+                    "    println!(\"<3\");\n",
+                    "}\n",
+                    "\n",
+                    "Back to top https://example.com/u"
+                ),
+            ),
+            (
+                "<table><tr><td>Total</td><td>&euro;10</td></tr><tr><td>a < b &unknown; &#x41;</td></tr></table>",
+                "Total €10\na < b &unknown; A",
+            ),
+        ] {
+            assert_eq!(html_to_text(html), expected_text, "{}", html);
+        }
+    }
+
+    #[test]
+    fn build_bulk_message() {
+        let message = MessageBuilder::new()
+            .from("news@example.com")
+            .to("jane@example.com")
+            .list_unsubscribe(vec!["https://example.com/u/1234", "mailto:unsubscribe@example.com"])
+            .list_unsubscribe_post()
+            .html_body("<h1>News</h1><p>Read <a href=\"https://example.com/1\">more</a></p>")
+            .generate_text_body(true);
+
+        let message = message.write_to_string().unwrap();
+        assert!(message.contains("Content-Type: multipart/alternative;"));
+        assert!(message.contains(concat!(
+            "Content-Type: text/plain; charset=\"utf-8\"\r\n",
+            "Content-Transfer-Encoding: 7bit\r\n\r\n",
+            "# News\r\n\r\nRead more <https://example.com/1>\r\n"
+        )));
+        assert!(message.contains(concat!(
+            "List-Unsubscribe: <https://example.com/u/1234>,\r\n\t<mailto:unsubscribe@example.com>\r\n",
+            "List-Unsubscribe-Post: List-Unsubscribe=One-Click\r\n"
+        )));
+
+        // An explicit text body is kept
+        let message = MessageBuilder::new()
+            .text_body("Plain")
+            .html_body("<p>HTML</p>")
+            .generate_text_body(true)
+            .write_to_string()
+            .unwrap();
+        assert!(message.contains("\r\n\r\nPlain\r\n"));
+        assert!(!message.contains("\r\n\r\nHTML\r\n"));
+    }
+}
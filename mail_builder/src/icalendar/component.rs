@@ -0,0 +1,448 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::borrow::Cow;
+
+use super::{DateTime, LocalDateTime, RRule, escape_text, write_property};
+use crate::headers::date::Date;
+
+/// The role of an attendee
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Role {
+    Chair,
+    #[default]
+    RequiredParticipant,
+    OptionalParticipant,
+    NonParticipant,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Chair => "CHAIR",
+            Role::RequiredParticipant => "REQ-PARTICIPANT",
+            Role::OptionalParticipant => "OPT-PARTICIPANT",
+            Role::NonParticipant => "NON-PARTICIPANT",
+        }
+    }
+}
+
+/// Whether an attendee will attend, as set in their replies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParticipationStatus {
+    #[default]
+    NeedsAction,
+    Accepted,
+    Declined,
+    Tentative,
+    Delegated,
+}
+
+impl ParticipationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParticipationStatus::NeedsAction => "NEEDS-ACTION",
+            ParticipationStatus::Accepted => "ACCEPTED",
+            ParticipationStatus::Declined => "DECLINED",
+            ParticipationStatus::Tentative => "TENTATIVE",
+            ParticipationStatus::Delegated => "DELEGATED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventStatus {
+    Tentative,
+    Confirmed,
+    Cancelled,
+}
+
+impl EventStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventStatus::Tentative => "TENTATIVE",
+            EventStatus::Confirmed => "CONFIRMED",
+            EventStatus::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TodoStatus {
+    NeedsAction,
+    InProcess,
+    Completed,
+    Cancelled,
+}
+
+impl TodoStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TodoStatus::NeedsAction => "NEEDS-ACTION",
+            TodoStatus::InProcess => "IN-PROCESS",
+            TodoStatus::Completed => "COMPLETED",
+            TodoStatus::Cancelled => "CANCELLED",
+        }
+    }
+}
+
+/// The ORGANIZER of an event or to-do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Organizer<'x> {
+    pub email: Cow<'x, str>,
+    pub name: Option<Cow<'x, str>>,
+}
+
+impl<'x> Organizer<'x> {
+    pub fn new(email: impl Into<Cow<'x, str>>) -> Self {
+        Organizer {
+            email: email.into(),
+            name: None,
+        }
+    }
+
+    /// Set the common name (CN parameter).
+    pub fn name(mut self, name: impl Into<Cow<'x, str>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    fn write(&self, output: &mut String) {
+        let mut params = Vec::new();
+        if let Some(name) = &self.name {
+            params.push(("CN", name.as_ref()));
+        }
+        write_property(output, "ORGANIZER", &params, &format!("mailto:{}", self.email));
+    }
+}
+
+/// An ATTENDEE of an event or to-do
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attendee<'x> {
+    pub email: Cow<'x, str>,
+    pub name: Option<Cow<'x, str>>,
+    pub role: Role,
+    pub status: ParticipationStatus,
+    /// Whether a reply is expected
+    pub rsvp: bool,
+}
+
+impl<'x> Attendee<'x> {
+    pub fn new(email: impl Into<Cow<'x, str>>) -> Self {
+        Attendee {
+            email: email.into(),
+            name: None,
+            role: Role::default(),
+            status: ParticipationStatus::default(),
+            rsvp: false,
+        }
+    }
+
+    /// Set the common name (CN parameter).
+    pub fn name(mut self, name: impl Into<Cow<'x, str>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Set the participation status (PARTSTAT parameter).
+    pub fn status(mut self, status: ParticipationStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn rsvp(mut self, rsvp: bool) -> Self {
+        self.rsvp = rsvp;
+        self
+    }
+
+    fn write(&self, output: &mut String) {
+        let mut params = Vec::with_capacity(4);
+        if let Some(name) = &self.name {
+            params.push(("CN", name.as_ref()));
+        }
+        params.push(("ROLE", self.role.as_str()));
+        params.push(("PARTSTAT", self.status.as_str()));
+        if self.rsvp {
+            params.push(("RSVP", "TRUE"));
+        }
+        write_property(output, "ATTENDEE", &params, &format!("mailto:{}", self.email));
+    }
+}
+
+// The properties shared by events and to-dos
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Properties<'x> {
+    uid: Cow<'x, str>,
+    timestamp: i64,
+    sequence: u32,
+    summary: Option<Cow<'x, str>>,
+    description: Option<Cow<'x, str>>,
+    location: Option<Cow<'x, str>>,
+    url: Option<Cow<'x, str>>,
+    organizer: Option<Organizer<'x>>,
+    attendees: Vec<Attendee<'x>>,
+    rrule: Option<RRule<'x>>,
+}
+
+impl<'x> Properties<'x> {
+    fn new(uid: Cow<'x, str>) -> Self {
+        Properties {
+            uid,
+            timestamp: Date::now().date,
+            sequence: 0,
+            summary: None,
+            description: None,
+            location: None,
+            url: None,
+            organizer: None,
+            attendees: Vec::new(),
+            rrule: None,
+        }
+    }
+
+    fn write_head(&self, output: &mut String) {
+        write_property(output, "UID", &[], &escape_text(&self.uid));
+        write_property(
+            output,
+            "DTSTAMP",
+            &[],
+            &format!("{}Z", LocalDateTime::from_timestamp(self.timestamp)),
+        );
+        if self.sequence > 0 {
+            write_property(output, "SEQUENCE", &[], &self.sequence.to_string());
+        }
+    }
+
+    fn write_text(&self, output: &mut String) {
+        for (name, value) in [
+            ("SUMMARY", &self.summary),
+            ("DESCRIPTION", &self.description),
+            ("LOCATION", &self.location),
+        ] {
+            if let Some(value) = value {
+                write_property(output, name, &[], &escape_text(value));
+            }
+        }
+        if let Some(url) = &self.url {
+            write_property(output, "URL", &[], url);
+        }
+    }
+
+    fn write_tail(&self, output: &mut String) {
+        if let Some(organizer) = &self.organizer {
+            organizer.write(output);
+        }
+        for attendee in &self.attendees {
+            attendee.write(output);
+        }
+        if let Some(rrule) = &self.rrule {
+            write_property(output, "RRULE", &[], &rrule.to_string());
+        }
+    }
+}
+
+macro_rules! shared_properties {
+    () => {
+        /// Set the DTSTAMP property, the time the object was created or
+        /// last sent. Defaults to the current time.
+        pub fn timestamp(mut self, timestamp: i64) -> Self {
+            self.properties.timestamp = timestamp;
+            self
+        }
+
+        /// Set the SEQUENCE property, which must be incremented each time
+        /// the organizer sends a significant update.
+        pub fn sequence(mut self, sequence: u32) -> Self {
+            self.properties.sequence = sequence;
+            self
+        }
+
+        pub fn summary(mut self, value: impl Into<Cow<'x, str>>) -> Self {
+            self.properties.summary = Some(value.into());
+            self
+        }
+
+        pub fn description(mut self, value: impl Into<Cow<'x, str>>) -> Self {
+            self.properties.description = Some(value.into());
+            self
+        }
+
+        pub fn location(mut self, value: impl Into<Cow<'x, str>>) -> Self {
+            self.properties.location = Some(value.into());
+            self
+        }
+
+        pub fn url(mut self, value: impl Into<Cow<'x, str>>) -> Self {
+            self.properties.url = Some(value.into());
+            self
+        }
+
+        pub fn organizer(mut self, organizer: Organizer<'x>) -> Self {
+            self.properties.organizer = Some(organizer);
+            self
+        }
+
+        pub fn attendee(mut self, attendee: Attendee<'x>) -> Self {
+            self.properties.attendees.push(attendee);
+            self
+        }
+
+        /// Set the recurrence rule (RRULE property).
+        pub fn rrule(mut self, rrule: RRule<'x>) -> Self {
+            self.properties.rrule = Some(rrule);
+            self
+        }
+    };
+}
+
+/// A calendar event (VEVENT)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event<'x> {
+    properties: Properties<'x>,
+    start: DateTime<'x>,
+    end: Option<DateTime<'x>>,
+    status: Option<EventStatus>,
+}
+
+impl<'x> Event<'x> {
+    /// Create an event. The UID must be globally unique and stay the same
+    /// in updates, cancellations and replies.
+    pub fn new(uid: impl Into<Cow<'x, str>>, start: DateTime<'x>) -> Self {
+        Event {
+            properties: Properties::new(uid.into()),
+            start,
+            end: None,
+            status: None,
+        }
+    }
+
+    /// Set the DTEND property.
+    pub fn end(mut self, end: DateTime<'x>) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn status(mut self, status: EventStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    shared_properties!();
+
+    pub(crate) fn write(&self, output: &mut String) {
+        write_property(output, "BEGIN", &[], "VEVENT");
+        self.properties.write_head(output);
+        self.start.write_property(output, "DTSTART");
+        if let Some(end) = &self.end {
+            end.write_property(output, "DTEND");
+        }
+        self.properties.write_text(output);
+        if let Some(status) = self.status {
+            write_property(output, "STATUS", &[], status.as_str());
+        }
+        self.properties.write_tail(output);
+        write_property(output, "END", &[], "VEVENT");
+    }
+}
+
+/// A to-do (VTODO)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Todo<'x> {
+    properties: Properties<'x>,
+    start: Option<DateTime<'x>>,
+    due: Option<DateTime<'x>>,
+    completed: Option<i64>,
+    status: Option<TodoStatus>,
+    priority: Option<u8>,
+    percent_complete: Option<u8>,
+}
+
+impl<'x> Todo<'x> {
+    /// Create a to-do. The UID must be globally unique and stay the same
+    /// in updates.
+    pub fn new(uid: impl Into<Cow<'x, str>>) -> Self {
+        Todo {
+            properties: Properties::new(uid.into()),
+            start: None,
+            due: None,
+            completed: None,
+            status: None,
+            priority: None,
+            percent_complete: None,
+        }
+    }
+
+    /// Set the DTSTART property.
+    pub fn start(mut self, start: DateTime<'x>) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    pub fn due(mut self, due: DateTime<'x>) -> Self {
+        self.due = Some(due);
+        self
+    }
+
+    /// Set the time the to-do was completed.
+    pub fn completed(mut self, timestamp: i64) -> Self {
+        self.completed = Some(timestamp);
+        self
+    }
+
+    pub fn status(mut self, status: TodoStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Set the priority, from 1 (highest) to 9 (lowest).
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority.min(9));
+        self
+    }
+
+    pub fn percent_complete(mut self, percent: u8) -> Self {
+        self.percent_complete = Some(percent.min(100));
+        self
+    }
+
+    shared_properties!();
+
+    pub(crate) fn write(&self, output: &mut String) {
+        write_property(output, "BEGIN", &[], "VTODO");
+        self.properties.write_head(output);
+        if let Some(start) = &self.start {
+            start.write_property(output, "DTSTART");
+        }
+        if let Some(due) = &self.due {
+            due.write_property(output, "DUE");
+        }
+        if let Some(completed) = self.completed {
+            DateTime::Utc(completed).write_property(output, "COMPLETED");
+        }
+        self.properties.write_text(output);
+        if let Some(status) = self.status {
+            write_property(output, "STATUS", &[], status.as_str());
+        }
+        if let Some(priority) = self.priority {
+            write_property(output, "PRIORITY", &[], &priority.to_string());
+        }
+        if let Some(percent) = self.percent_complete {
+            write_property(output, "PERCENT-COMPLETE", &[], &percent.to_string());
+        }
+        self.properties.write_tail(output);
+        write_property(output, "END", &[], "VTODO");
+    }
+}
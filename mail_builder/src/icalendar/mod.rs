@@ -0,0 +1,654 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+//! iCalendar (_RFC 5545_) objects for meeting invitations and scheduling
+//! messages (_RFC 5546_, _RFC 6047_).
+//!
+//! ```rust
+//! use mail_builder::{
+//!     MessageBuilder,
+//!     icalendar::{Attendee, Calendar, DateTime, Event, Method, Organizer},
+//! };
+//!
+//! let calendar = Calendar::new()
+//!     .method(Method::Request)
+//!     .event(
+//!         Event::new("meeting-42@example.com", DateTime::utc(1700042400))
+//!             .end(DateTime::utc(1700046000))
+//!             .summary("Project review")
+//!             .organizer(Organizer::new("john@example.com").name("John Doe"))
+//!             .attendee(Attendee::new("jane@example.com").name("Jane Doe").rsvp(true)),
+//!     );
+//!
+//! let eml = MessageBuilder::new()
+//!     .from(("John Doe", "john@example.com"))
+//!     .to("jane@example.com")
+//!     .subject("Invitation: Project review")
+//!     .text_body("You are invited to the project review.")
+//!     .calendar(&calendar)
+//!     .write_to_string()
+//!     .unwrap();
+//! ```
+
+pub mod component;
+pub mod recur;
+pub mod timezone;
+
+use std::{borrow::Cow, fmt};
+
+pub use self::{
+    component::{Attendee, Event, EventStatus, Organizer, ParticipationStatus, Role, Todo, TodoStatus},
+    recur::{Frequency, RRule, Weekday},
+    timezone::{TimeZone, TimeZoneRule},
+};
+use crate::{headers::content_type::ContentType, mime::MimePart};
+
+// Content lines are folded after this many octets
+const MAX_LINE_LEN: usize = 75;
+
+/// The iTIP method of a calendar sent by e-mail (_RFC 5546_)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Publish,
+    Request,
+    Reply,
+    Cancel,
+}
+
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Publish => "PUBLISH",
+            Method::Request => "REQUEST",
+            Method::Reply => "REPLY",
+            Method::Cancel => "CANCEL",
+        }
+    }
+}
+
+/// A date and time without time zone, as seen on a wall clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LocalDateTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl LocalDateTime {
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Self {
+        LocalDateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    /// Convert a UNIX timestamp to the UTC date and time.
+    pub fn from_timestamp(timestamp: i64) -> Self {
+        // Ported from http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let (days, seconds) = (timestamp.div_euclid(86400), timestamp.rem_euclid(86400));
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097); // [0, 146096]
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+        let mp = (5 * doy + 2) / 153; // [0, 11]
+        let day = doy - (153 * mp + 2) / 5 + 1; // [1, 31]
+        let month = if mp < 10 { mp + 3 } else { mp - 9 }; // [1, 12]
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        LocalDateTime {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hour: (seconds / 3600) as u8,
+            minute: ((seconds / 60) % 60) as u8,
+            second: (seconds % 60) as u8,
+        }
+    }
+}
+
+impl fmt::Display for LocalDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}{:02}{:02}T{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// The DATE-TIME or DATE value of a property
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateTime<'x> {
+    /// A UNIX timestamp, written in UTC
+    Utc(i64),
+    /// A local time in a time zone, which should be described by a
+    /// `TimeZone` of the calendar
+    Local {
+        tzid: Cow<'x, str>,
+        datetime: LocalDateTime,
+    },
+    /// A time that is the same in every time zone
+    Floating(LocalDateTime),
+    /// A whole day, used by all-day events
+    Date { year: u16, month: u8, day: u8 },
+}
+
+impl<'x> DateTime<'x> {
+    pub fn utc(timestamp: i64) -> Self {
+        DateTime::Utc(timestamp)
+    }
+
+    pub fn local(tzid: impl Into<Cow<'x, str>>, datetime: LocalDateTime) -> Self {
+        DateTime::Local {
+            tzid: tzid.into(),
+            datetime,
+        }
+    }
+
+    pub fn date(year: u16, month: u8, day: u8) -> Self {
+        DateTime::Date {
+            year,
+            month,
+            day,
+        }
+    }
+
+    // The parameters and value of the property
+    pub(crate) fn write_property(&self, output: &mut String, name: &str) {
+        match self {
+            DateTime::Utc(timestamp) => {
+                write_property(output, name, &[], &format!("{}Z", LocalDateTime::from_timestamp(*timestamp)))
+            }
+            DateTime::Local {
+                tzid,
+                datetime,
+            } => write_property(output, name, &[("TZID", tzid)], &datetime.to_string()),
+            DateTime::Floating(datetime) => write_property(output, name, &[], &datetime.to_string()),
+            DateTime::Date {
+                year,
+                month,
+                day,
+            } => write_property(
+                output,
+                name,
+                &[("VALUE", "DATE")],
+                &format!("{:04}{:02}{:02}", year, month, day),
+            ),
+        }
+    }
+
+    // The value alone, as used in the UNTIL part of recurrence rules
+    pub(crate) fn value(&self) -> String {
+        match self {
+            DateTime::Utc(timestamp) => format!("{}Z", LocalDateTime::from_timestamp(*timestamp)),
+            DateTime::Local {
+                datetime, ..
+            }
+            | DateTime::Floating(datetime) => datetime.to_string(),
+            DateTime::Date {
+                year,
+                month,
+                day,
+            } => format!("{:04}{:02}{:02}", year, month, day),
+        }
+    }
+}
+
+/// An iCalendar object (VCALENDAR) holding events, to-dos and the time
+/// zones they use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calendar<'x> {
+    pub prod_id: Cow<'x, str>,
+    pub method: Option<Method>,
+    pub timezones: Vec<TimeZone<'x>>,
+    pub events: Vec<Event<'x>>,
+    pub todos: Vec<Todo<'x>>,
+}
+
+impl<'x> Default for Calendar<'x> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'x> Calendar<'x> {
+    pub fn new() -> Self {
+        Calendar {
+            prod_id: "-//Stalwart Labs//mail-builder//EN".into(),
+            method: None,
+            timezones: Vec::new(),
+            events: Vec::new(),
+            todos: Vec::new(),
+        }
+    }
+
+    /// Set the PRODID property, identifying the application that created
+    /// the calendar.
+    pub fn prod_id(mut self, value: impl Into<Cow<'x, str>>) -> Self {
+        self.prod_id = value.into();
+        self
+    }
+
+    /// Set the METHOD property. Calendars sent by e-mail must have one.
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = Some(method);
+        self
+    }
+
+    /// Add a time zone definition (VTIMEZONE).
+    pub fn timezone(mut self, timezone: TimeZone<'x>) -> Self {
+        self.timezones.push(timezone);
+        self
+    }
+
+    /// Add an event (VEVENT).
+    pub fn event(mut self, event: Event<'x>) -> Self {
+        self.events.push(event);
+        self
+    }
+
+    /// Add a to-do (VTODO).
+    pub fn todo(mut self, todo: Todo<'x>) -> Self {
+        self.todos.push(todo);
+        self
+    }
+
+    /// Returns a text/calendar MIME part, with the method parameter that
+    /// calendar clients use to show the invitation. It is meant to be an
+    /// alternative to the text and HTML bodies.
+    pub fn mime_part(&self) -> MimePart<'static> {
+        let mut content_type = ContentType::new("text/calendar").attribute("charset", "utf-8");
+        if let Some(method) = self.method {
+            content_type = content_type.attribute("method", method.as_str());
+        }
+        MimePart::new(content_type, self.to_string())
+    }
+
+    /// Returns the calendar as an application/ics attachment.
+    pub fn attachment(&self, filename: impl Into<Cow<'static, str>>) -> MimePart<'static> {
+        let filename = filename.into();
+        MimePart::new(
+            ContentType::new("application/ics").attribute("name", filename.clone()),
+            self.to_string().into_bytes(),
+        )
+        .attachment(filename)
+    }
+}
+
+impl fmt::Display for Calendar<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut output = String::new();
+        write_property(&mut output, "BEGIN", &[], "VCALENDAR");
+        write_property(&mut output, "VERSION", &[], "2.0");
+        write_property(&mut output, "PRODID", &[], &escape_text(&self.prod_id));
+        write_property(&mut output, "CALSCALE", &[], "GREGORIAN");
+        if let Some(method) = self.method {
+            write_property(&mut output, "METHOD", &[], method.as_str());
+        }
+        for timezone in &self.timezones {
+            timezone.write(&mut output);
+        }
+        for event in &self.events {
+            event.write(&mut output);
+        }
+        for todo in &self.todos {
+            todo.write(&mut output);
+        }
+        write_property(&mut output, "END", &[], "VCALENDAR");
+        f.write_str(&output)
+    }
+}
+
+/// Write a content line, folding it every 75 octets without splitting
+/// UTF-8 sequences. The value must already be escaped. Control characters
+/// other than HTAB, which can't appear in parameters or values, are dropped
+/// so that they can't end the line early.
+pub(crate) fn write_property(output: &mut String, name: &str, params: &[(&str, &str)], value: &str) {
+    let mut line = String::with_capacity(name.len() + value.len() + 2);
+    line.push_str(name);
+    for (key, param) in params {
+        line.push(';');
+        line.push_str(key);
+        line.push('=');
+        // Double quotes can't be escaped, they are dropped
+        let param = param.chars().filter(|&ch| ch != '"' && is_safe_char(ch));
+        if param.clone().any(|ch| matches!(ch, ':' | ';' | ',')) {
+            line.push('"');
+            line.extend(param);
+            line.push('"');
+        } else {
+            line.extend(param);
+        }
+    }
+    line.push(':');
+    line.extend(value.chars().filter(|&ch| is_safe_char(ch)));
+
+    let mut line_len = 0;
+    for ch in line.chars() {
+        if line_len + ch.len_utf8() > MAX_LINE_LEN {
+            output.push_str("\r\n ");
+            line_len = 1;
+        }
+        output.push(ch);
+        line_len += ch.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+fn is_safe_char(ch: char) -> bool {
+    !ch.is_ascii_control() || ch == '\t'
+}
+
+/// Escape a TEXT value.
+pub(crate) fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\r' | '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Attendee, Calendar, DateTime, Event, EventStatus, Frequency, LocalDateTime, Method, Organizer,
+        ParticipationStatus, RRule, Role, TimeZone, TimeZoneRule, Todo, TodoStatus, Weekday, escape_text,
+        write_property,
+    };
+    use crate::{
+        MessageBuilder,
+        headers::content_type::ContentType,
+        mime::{BodyPart, MimePart},
+    };
+
+    #[test]
+    fn fold_and_escape() {
+        assert_eq!(
+            escape_text("Meeting; room 3, floor 2\r\nBring C:\\notes\n"),
+            "Meeting\\; room 3\\, floor 2\\nBring C:\\\\notes\\n"
+        );
+
+        let mut output = String::new();
+        write_property(&mut output, "DESCRIPTION", &[], &escape_text(&"Ça va? ".repeat(15)));
+        let lines = output.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(lines[2], "");
+        assert_eq!(output.replace("\r\n ", ""), format!("DESCRIPTION:{}\r\n", "Ça va? ".repeat(15)));
+
+        let mut output = String::new();
+        write_property(
+            &mut output,
+            "ATTENDEE",
+            &[("CN", "Doe, \"Jane\""), ("ROLE", "CHAIR")],
+            "mailto:jane@example.com",
+        );
+        assert_eq!(output, "ATTENDEE;CN=\"Doe, Jane\";ROLE=CHAIR:mailto:jane@example.com\r\n");
+
+        let calendar = Calendar::new()
+            .event(
+                Event::new("meeting@example.com", DateTime::utc(1700042400))
+                    .url("https://example.com/meeting\r\nMETHOD:CANCEL")
+                    .organizer(Organizer::new("john@example.com\nMETHOD:CANCEL"))
+                    .attendee(Attendee::new("jane@example.com").name("Jane\r\nATTENDEE:mailto:x")),
+            )
+            .to_string()
+            .replace("\r\n ", "");
+        assert!(calendar.contains(
+            "ATTENDEE;CN=\"JaneATTENDEE:mailto:x\";ROLE=REQ-PARTICIPANT;PARTSTAT=NEEDS-ACTION:mailto:jane@example.com\r\n"
+        ));
+        assert!(calendar.contains("ORGANIZER:mailto:john@example.comMETHOD:CANCEL\r\n"));
+        assert!(calendar.contains("URL:https://example.com/meetingMETHOD:CANCEL\r\n"));
+        assert!(!calendar.contains("\nATTENDEE:mailto:x"));
+        assert!(!calendar.contains("\nMETHOD:"));
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(LocalDateTime::from_timestamp(0), LocalDateTime::new(1970, 1, 1, 0, 0, 0));
+        assert_eq!(LocalDateTime::from_timestamp(951825845).to_string(), "20000229T120405");
+        assert_eq!(LocalDateTime::from_timestamp(-1).to_string(), "19691231T235959");
+    }
+
+    #[test]
+    fn build_request() {
+        let calendar = Calendar::new()
+            .method(Method::Request)
+            .timezone(
+                TimeZone::new("Europe/Paris")
+                    .rule(
+                        TimeZoneRule::daylight(LocalDateTime::new(1981, 3, 29, 2, 0, 0), 60, 120)
+                            .name("CEST")
+                            .rrule(
+                                RRule::new(Frequency::Yearly)
+                                    .by_month(3)
+                                    .by_nth_day(-1, Weekday::Sunday),
+                            ),
+                    )
+                    .rule(
+                        TimeZoneRule::standard(LocalDateTime::new(1996, 10, 27, 3, 0, 0), 120, 60)
+                            .name("CET")
+                            .rrule(
+                                RRule::new(Frequency::Yearly)
+                                    .by_month(10)
+                                    .by_nth_day(-1, Weekday::Sunday),
+                            ),
+                    ),
+            )
+            .event(
+                Event::new(
+                    "1700042400-42@example.com",
+                    DateTime::local("Europe/Paris", LocalDateTime::new(2023, 11, 15, 11, 0, 0)),
+                )
+                .end(DateTime::local("Europe/Paris", LocalDateTime::new(2023, 11, 15, 12, 0, 0)))
+                .timestamp(1699999200)
+                .sequence(1)
+                .summary("Weekly sync, room 3")
+                .description("Agenda:\n- Status\n- Next steps")
+                .location("Room 3; 2nd floor")
+                .status(EventStatus::Confirmed)
+                .organizer(Organizer::new("john@example.com").name("John Doe"))
+                .attendee(Attendee::new("jane@example.com").name("Jane Doe").rsvp(true))
+                .attendee(
+                    Attendee::new("jim@example.com")
+                        .role(Role::OptionalParticipant)
+                        .status(ParticipationStatus::Tentative),
+                )
+                .rrule(
+                    RRule::new(Frequency::Weekly)
+                        .interval(2)
+                        .count(10)
+                        .by_day(Weekday::Monday)
+                        .by_day(Weekday::Wednesday),
+                ),
+            )
+            .todo(
+                Todo::new("todo-1@example.com")
+                    .timestamp(1699999200)
+                    .due(DateTime::date(2023, 11, 20))
+                    .summary("Prepare slides")
+                    .priority(1)
+                    .status(TodoStatus::NeedsAction),
+            );
+
+        assert_eq!(
+            calendar.to_string(),
+            concat!(
+                "BEGIN:VCALENDAR\r\n",
+                "VERSION:2.0\r\n",
+                "PRODID:-//Stalwart Labs//mail-builder//EN\r\n",
+                "CALSCALE:GREGORIAN\r\n",
+                "METHOD:REQUEST\r\n",
+                "BEGIN:VTIMEZONE\r\n",
+                "TZID:Europe/Paris\r\n",
+                "BEGIN:DAYLIGHT\r\n",
+                "DTSTART:19810329T020000\r\n",
+                "TZOFFSETFROM:+0100\r\n",
+                "TZOFFSETTO:+0200\r\n",
+                "TZNAME:CEST\r\n",
+                "RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=3\r\n",
+                "END:DAYLIGHT\r\n",
+                "BEGIN:STANDARD\r\n",
+                "DTSTART:19961027T030000\r\n",
+                "TZOFFSETFROM:+0200\r\n",
+                "TZOFFSETTO:+0100\r\n",
+                "TZNAME:CET\r\n",
+                "RRULE:FREQ=YEARLY;BYDAY=-1SU;BYMONTH=10\r\n",
+                "END:STANDARD\r\n",
+                "END:VTIMEZONE\r\n",
+                "BEGIN:VEVENT\r\n",
+                "UID:1700042400-42@example.com\r\n",
+                "DTSTAMP:20231114T220000Z\r\n",
+                "SEQUENCE:1\r\n",
+                "DTSTART;TZID=Europe/Paris:20231115T110000\r\n",
+                "DTEND;TZID=Europe/Paris:20231115T120000\r\n",
+                "SUMMARY:Weekly sync\\, room 3\r\n",
+                "DESCRIPTION:Agenda:\\n- Status\\n- Next steps\r\n",
+                "LOCATION:Room 3\\; 2nd floor\r\n",
+                "STATUS:CONFIRMED\r\n",
+                "ORGANIZER;CN=John Doe:mailto:john@example.com\r\n",
+                "ATTENDEE;CN=Jane Doe;ROLE=REQ-PARTICIPANT;PARTSTAT=NEEDS-ACTION;RSVP=TRUE:m\r\n",
+                " ailto:jane@example.com\r\n",
+                "ATTENDEE;ROLE=OPT-PARTICIPANT;PARTSTAT=TENTATIVE:mailto:jim@example.com\r\n",
+                "RRULE:FREQ=WEEKLY;INTERVAL=2;COUNT=10;BYDAY=MO,WE\r\n",
+                "END:VEVENT\r\n",
+                "BEGIN:VTODO\r\n",
+                "UID:todo-1@example.com\r\n",
+                "DTSTAMP:20231114T220000Z\r\n",
+                "DUE;VALUE=DATE:20231120\r\n",
+                "SUMMARY:Prepare slides\r\n",
+                "STATUS:NEEDS-ACTION\r\n",
+                "PRIORITY:1\r\n",
+                "END:VTODO\r\n",
+                "END:VCALENDAR\r\n",
+            )
+        );
+    }
+
+    #[test]
+    fn build_cancel_and_reply() {
+        let event = Event::new("meeting@example.com", DateTime::date(2024, 1, 31))
+            .timestamp(1706659200)
+            .organizer(Organizer::new("john@example.com"));
+
+        let cancel = Calendar::new().method(Method::Cancel).event(
+            event
+                .clone()
+                .sequence(2)
+                .status(EventStatus::Cancelled)
+                .attendee(Attendee::new("jane@example.com")),
+        );
+        let cancel = cancel.to_string();
+        assert!(cancel.contains("\r\nMETHOD:CANCEL\r\n"));
+        assert!(cancel.contains("\r\nDTSTART;VALUE=DATE:20240131\r\n"));
+        assert!(cancel.contains("\r\nSTATUS:CANCELLED\r\n"));
+        assert!(cancel.contains("\r\nSEQUENCE:2\r\n"));
+        assert!(cancel.contains("\r\nORGANIZER:mailto:john@example.com\r\n"));
+
+        let reply = Calendar::new().method(Method::Reply).event(
+            event.attendee(
+                Attendee::new("jane@example.com")
+                    .name("Jane Doe")
+                    .status(ParticipationStatus::Accepted),
+            ),
+        );
+        assert!(reply.to_string().contains(
+            "\r\nATTENDEE;CN=Jane Doe;ROLE=REQ-PARTICIPANT;PARTSTAT=ACCEPTED:mailto:jane@exa\r\n mple.com\r\n"
+        ));
+
+        let mut part = Vec::new();
+        reply.mime_part().write_part(&mut part).unwrap();
+        let part = String::from_utf8(part).unwrap();
+        assert!(part.starts_with("Content-Type: text/calendar; charset=\"utf-8\"; method=\"REPLY\"\r\n"));
+        assert!(part.contains("BEGIN:VCALENDAR"));
+
+        let mut part = Vec::new();
+        reply.attachment("invite.ics").write_part(&mut part).unwrap();
+        let part = String::from_utf8(part).unwrap();
+        assert!(part.starts_with("Content-Type: application/ics; name=\"invite.ics\"\r\n"));
+        assert!(part.contains("Content-Disposition: attachment; filename=\"invite.ics\"\r\n"));
+    }
+
+    #[test]
+    fn build_invitation_message() {
+        let calendar = Calendar::new().method(Method::Request).event(
+            Event::new("meeting@example.com", DateTime::utc(1700042400))
+                .summary("Project review")
+                .organizer(Organizer::new("john@example.com"))
+                .attendee(Attendee::new("jane@example.com").rsvp(true)),
+        );
+        let message = MessageBuilder::new()
+            .from("john@example.com")
+            .to("jane@example.com")
+            .subject("Invitation: Project review")
+            .text_body("You are invited.")
+            .html_body("<p>You are invited.</p>")
+            .calendar(&calendar)
+            .write_to_vec()
+            .unwrap();
+
+        let body = MessageBuilder::parse(&message).unwrap().body.unwrap();
+        let BodyPart::Multipart(parts) = body.contents else {
+            panic!("Expected multipart/mixed");
+        };
+        assert_eq!(parts.len(), 2);
+        let BodyPart::Multipart(alternatives) = &parts[0].contents else {
+            panic!("Expected multipart/alternative");
+        };
+        assert_eq!(
+            alternatives
+                .iter()
+                .map(|part| content_type(part).c_type.to_string())
+                .collect::<Vec<_>>(),
+            ["text/plain", "text/html", "text/calendar"]
+        );
+        assert!(
+            content_type(&alternatives[2])
+                .attributes
+                .contains(&("method".into(), "REQUEST".into()))
+        );
+        match &alternatives[2].contents {
+            BodyPart::Text(text) => assert_eq!(text, &calendar.to_string()),
+            _ => panic!("Expected a text part"),
+        }
+
+        assert_eq!(content_type(&parts[1]).c_type, "application/ics");
+        match &parts[1].contents {
+            BodyPart::Binary(ics) => assert_eq!(ics.as_ref(), calendar.to_string().as_bytes()),
+            _ => panic!("Expected a binary part"),
+        }
+    }
+
+    fn content_type<'x>(part: &'x MimePart<'_>) -> &'x ContentType<'x> {
+        part.headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("Content-Type"))
+            .and_then(|(_, value)| value.as_content_type())
+            .unwrap()
+    }
+}
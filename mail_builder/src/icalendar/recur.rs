@@ -0,0 +1,207 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::fmt;
+
+use super::DateTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Frequency::Secondly => "SECONDLY",
+            Frequency::Minutely => "MINUTELY",
+            Frequency::Hourly => "HOURLY",
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "MO",
+            Weekday::Tuesday => "TU",
+            Weekday::Wednesday => "WE",
+            Weekday::Thursday => "TH",
+            Weekday::Friday => "FR",
+            Weekday::Saturday => "SA",
+            Weekday::Sunday => "SU",
+        }
+    }
+}
+
+/// A recurrence rule (RRULE property)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule<'x> {
+    pub frequency: Frequency,
+    pub interval: Option<u32>,
+    pub count: Option<u32>,
+    /// The last occurrence. Must be in UTC if the start of the event is.
+    pub until: Option<DateTime<'x>>,
+    /// Days of the week, with their position in the month or year, such as
+    /// -1 for the last one, or 0 for all of them.
+    pub by_day: Vec<(i8, Weekday)>,
+    pub by_month_day: Vec<i8>,
+    pub by_month: Vec<u8>,
+    pub week_start: Option<Weekday>,
+}
+
+impl<'x> RRule<'x> {
+    pub fn new(frequency: Frequency) -> Self {
+        RRule {
+            frequency,
+            interval: None,
+            count: None,
+            until: None,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            week_start: None,
+        }
+    }
+
+    /// Repeat every `interval` periods of the frequency.
+    pub fn interval(mut self, interval: u32) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Stop after `count` occurrences.
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Stop after the given date.
+    pub fn until(mut self, until: DateTime<'x>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Repeat on every given day of the week.
+    pub fn by_day(self, weekday: Weekday) -> Self {
+        self.by_nth_day(0, weekday)
+    }
+
+    /// Repeat on the nth given day of the week of the month, or year for
+    /// yearly rules. Negative positions count from the end.
+    pub fn by_nth_day(mut self, position: i8, weekday: Weekday) -> Self {
+        self.by_day.push((position, weekday));
+        self
+    }
+
+    /// Repeat on the given day of the month. Negative days count from the
+    /// end of the month.
+    pub fn by_month_day(mut self, day: i8) -> Self {
+        self.by_month_day.push(day);
+        self
+    }
+
+    /// Repeat in the given month, from 1 to 12.
+    pub fn by_month(mut self, month: u8) -> Self {
+        self.by_month.push(month);
+        self
+    }
+
+    /// Set the day weeks start on, Monday by default.
+    pub fn week_start(mut self, weekday: Weekday) -> Self {
+        self.week_start = Some(weekday);
+        self
+    }
+}
+
+impl fmt::Display for RRule<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.as_str())?;
+        if let Some(interval) = self.interval {
+            write!(f, ";INTERVAL={}", interval)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = &self.until {
+            write!(f, ";UNTIL={}", until.value())?;
+        }
+        for (pos, (position, weekday)) in self.by_day.iter().enumerate() {
+            f.write_str(if pos == 0 { ";BYDAY=" } else { "," })?;
+            if *position != 0 {
+                write!(f, "{}", position)?;
+            }
+            f.write_str(weekday.as_str())?;
+        }
+        for (pos, day) in self.by_month_day.iter().enumerate() {
+            write!(f, "{}{}", if pos == 0 { ";BYMONTHDAY=" } else { "," }, day)?;
+        }
+        for (pos, month) in self.by_month.iter().enumerate() {
+            write!(f, "{}{}", if pos == 0 { ";BYMONTH=" } else { "," }, month)?;
+        }
+        if let Some(weekday) = self.week_start {
+            write!(f, ";WKST={}", weekday.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Frequency, RRule, Weekday};
+    use crate::icalendar::DateTime;
+
+    #[test]
+    fn write_rrule() {
+        assert_eq!(RRule::new(Frequency::Daily).to_string(), "FREQ=DAILY");
+        assert_eq!(
+            RRule::new(Frequency::Monthly)
+                .until(DateTime::utc(1735689599))
+                .by_nth_day(2, Weekday::Tuesday)
+                .by_nth_day(-1, Weekday::Friday)
+                .week_start(Weekday::Sunday)
+                .to_string(),
+            "FREQ=MONTHLY;UNTIL=20241231T235959Z;BYDAY=2TU,-1FR;WKST=SU"
+        );
+        assert_eq!(
+            RRule::new(Frequency::Yearly)
+                .interval(4)
+                .by_month_day(1)
+                .by_month_day(-1)
+                .by_month(2)
+                .by_month(11)
+                .until(DateTime::date(2040, 1, 1))
+                .to_string(),
+            "FREQ=YEARLY;INTERVAL=4;UNTIL=20400101;BYMONTHDAY=1,-1;BYMONTH=2,11"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::borrow::Cow;
+
+use super::{LocalDateTime, RRule, escape_text, write_property};
+
+/// A time zone definition (VTIMEZONE), which must be included for each
+/// TZID used by the calendar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeZone<'x> {
+    pub tzid: Cow<'x, str>,
+    pub rules: Vec<TimeZoneRule<'x>>,
+}
+
+impl<'x> TimeZone<'x> {
+    pub fn new(tzid: impl Into<Cow<'x, str>>) -> Self {
+        TimeZone {
+            tzid: tzid.into(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Add a STANDARD or DAYLIGHT observance.
+    pub fn rule(mut self, rule: TimeZoneRule<'x>) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub(crate) fn write(&self, output: &mut String) {
+        write_property(output, "BEGIN", &[], "VTIMEZONE");
+        write_property(output, "TZID", &[], &escape_text(&self.tzid));
+        for rule in &self.rules {
+            rule.write(output);
+        }
+        write_property(output, "END", &[], "VTIMEZONE");
+    }
+}
+
+/// An observance of a time zone: the offset from UTC that applies from
+/// `start`, and optionally repeats with a recurrence rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeZoneRule<'x> {
+    pub daylight: bool,
+    /// The local time, in the previous offset, when the observance begins
+    pub start: LocalDateTime,
+    /// The offsets from UTC before and after the start, in minutes
+    pub offset_from: i32,
+    pub offset_to: i32,
+    pub name: Option<Cow<'x, str>>,
+    pub rrule: Option<RRule<'x>>,
+}
+
+impl<'x> TimeZoneRule<'x> {
+    pub fn standard(start: LocalDateTime, offset_from: i32, offset_to: i32) -> Self {
+        TimeZoneRule {
+            daylight: false,
+            start,
+            offset_from,
+            offset_to,
+            name: None,
+            rrule: None,
+        }
+    }
+
+    pub fn daylight(start: LocalDateTime, offset_from: i32, offset_to: i32) -> Self {
+        TimeZoneRule {
+            daylight: true,
+            ..Self::standard(start, offset_from, offset_to)
+        }
+    }
+
+    /// Set the abbreviation of the observance, such as "CEST".
+    pub fn name(mut self, name: impl Into<Cow<'x, str>>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn rrule(mut self, rrule: RRule<'x>) -> Self {
+        self.rrule = Some(rrule);
+        self
+    }
+
+    fn write(&self, output: &mut String) {
+        let component = if self.daylight { "DAYLIGHT" } else { "STANDARD" };
+        write_property(output, "BEGIN", &[], component);
+        write_property(output, "DTSTART", &[], &self.start.to_string());
+        write_property(output, "TZOFFSETFROM", &[], &utc_offset(self.offset_from));
+        write_property(output, "TZOFFSETTO", &[], &utc_offset(self.offset_to));
+        if let Some(name) = &self.name {
+            write_property(output, "TZNAME", &[], &escape_text(name));
+        }
+        if let Some(rrule) = &self.rrule {
+            write_property(output, "RRULE", &[], &rrule.to_string());
+        }
+        write_property(output, "END", &[], component);
+    }
+}
+
+fn utc_offset(minutes: i32) -> String {
+    format!(
+        "{}{:02}{:02}",
+        if minutes < 0 { '-' } else { '+' },
+        minutes.unsigned_abs() / 60,
+        minutes.unsigned_abs() % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::utc_offset;
+
+    #[test]
+    fn utc_offsets() {
+        assert_eq!(utc_offset(0), "+0000");
+        assert_eq!(utc_offset(330), "+0530");
+        assert_eq!(utc_offset(-210), "-0330");
+    }
+}
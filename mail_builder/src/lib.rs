@@ -21,9 +21,16 @@
 //! - Generates **e-mail** messages conforming to the Internet Message Format standard (_RFC 5322_).
 //! - Full **MIME** support (_RFC 2045 - 2049_) with automatic selection of the most optimal encoding for each message body part.
 //! - **Fast Base64 encoding** based on Chromium's decoder ([the fastest non-SIMD encoder](https://github.com/lemire/fastbase64)).
+//! - Attachments and bodies can be **streamed** from files or readers, and are encoded as the message is written.
+//! - Parses existing messages back into a `MessageBuilder`, decoding encoded words, transfer encodings and RFC2231 parameters.
+//! - **iCalendar** invitations (_RFC 5545_, _RFC 6047_) with events, to-dos, attendees, recurrence rules and time zones.
+//! - **Delivery status** and **disposition notifications** (_RFC 3464_, _RFC 8098_) as `multipart/report` messages.
+//! - Generates a **plain text alternative** from HTML bodies, and `List-Unsubscribe` headers for one-click unsubscription (_RFC 8058_).
+//! - **DKIM** signing and verification (_RFC 6376_) with RSA-SHA256 and Ed25519-SHA256 keys, behind the `dkim` feature.
+//! - Async **SMTP** client with STARTTLS, implicit TLS, AUTH PLAIN/LOGIN/XOAUTH2, pipelining and delivery status notifications, behind the `smtp` feature.
 //! - Minimal dependencies.
 //!
-//! Please note that this library does not support sending or parsing e-mail messages as these functionalities are provided by the crates [`mail-send`](https://crates.io/crates/mail-send) and [`mail-parser`](https://crates.io/crates/mail-parser).
+//! For a full-featured SMTP client, with DNS lookups and connection pooling, see [`mail-send`](https://crates.io/crates/mail-send). For full-featured parsing, see [`mail-parser`](https://crates.io/crates/mail-parser).
 //!
 //! ## Usage Example
 //!
@@ -90,6 +97,8 @@
 //!        .subject("Testing multipart messages")
 //!        .in_reply_to(vec!["message-id-1", "message-id-2"])
 //!        .header("List-Archive", URL::new("http://example.com/archive"))
+//!        .list_unsubscribe(vec!["https://example.com/unsubscribe", "mailto:unsubscribe@example.com"])
+//!        .list_unsubscribe_post()
 //!
 //!        // Set HTML and plain text bodies
 //!        .text_body("This is the text body!\n")
@@ -210,9 +219,18 @@
 //!
 //! [COPYING]: https://github.com/stalwartlabs/mail-builder/blob/main/COPYING
 //!
+pub mod decoders;
+#[cfg(feature = "dkim")]
+pub mod dkim;
 pub mod encoders;
 pub mod headers;
+pub mod html;
+pub mod icalendar;
 pub mod mime;
+pub mod parsers;
+pub mod report;
+#[cfg(feature = "smtp")]
+pub mod smtp;
 
 use std::{
     borrow::Cow,
@@ -225,7 +243,9 @@ use headers::{
     content_type::ContentType,
     date::Date,
     message_id::{MessageId, generate_message_id_header},
+    raw::Raw,
     text::Text,
+    url::URL,
 };
 use mime::{BodyPart, MimePart};
 
@@ -235,8 +255,10 @@ pub struct MessageBuilder<'x> {
     pub headers: Vec<(Cow<'x, str>, HeaderType<'x>)>,
     pub html_body: Option<MimePart<'x>>,
     pub text_body: Option<MimePart<'x>>,
+    pub calendar_body: Option<MimePart<'x>>,
     pub attachments: Option<Vec<MimePart<'x>>>,
     pub body: Option<MimePart<'x>>,
+    pub generate_text_body: bool,
 }
 
 impl<'x> Default for MessageBuilder<'x> {
@@ -252,8 +274,10 @@ impl<'x> MessageBuilder<'x> {
             headers: Vec::new(),
             html_body: None,
             text_body: None,
+            calendar_body: None,
             attachments: None,
             body: None,
+            generate_text_body: false,
         }
     }
 
@@ -303,6 +327,19 @@ impl<'x> MessageBuilder<'x> {
         self.header("Reply-To", value.into())
     }
 
+    /// Set the List-Unsubscribe header (_RFC 2369_), usually an HTTPS URL
+    /// and a mailto: address.
+    pub fn list_unsubscribe(self, value: impl Into<URL<'x>>) -> Self {
+        self.header("List-Unsubscribe", value.into())
+    }
+
+    /// Set the List-Unsubscribe-Post header to request one-click
+    /// unsubscription (_RFC 8058_), by POSTing to the HTTPS URL of the
+    /// List-Unsubscribe header.
+    pub fn list_unsubscribe_post(self) -> Self {
+        self.header("List-Unsubscribe-Post", Raw::new("List-Unsubscribe=One-Click"))
+    }
+
     /// Set the Subject header.
     pub fn subject(self, value: impl Into<Text<'x>>) -> Self {
         self.header("Subject", value.into())
@@ -352,6 +389,27 @@ impl<'x> MessageBuilder<'x> {
         self
     }
 
+    /// Generate the plain text body from the HTML body when no plain text
+    /// body is set, keeping its links, lists and headings.
+    ///
+    /// Only HTML bodies held in memory are converted. No text body is
+    /// generated for an HTML body streamed from a file or a reader, which
+    /// is not read ahead of time, or for a multipart HTML body.
+    pub fn generate_text_body(mut self, value: bool) -> Self {
+        self.generate_text_body = value;
+        self
+    }
+
+    /// Add a calendar invitation or reply: a text/calendar alternative to
+    /// the text and HTML bodies, and an "invite.ics" attachment.
+    pub fn calendar(mut self, calendar: &icalendar::Calendar<'_>) -> Self {
+        self.calendar_body = Some(calendar.mime_part());
+        self.attachments
+            .get_or_insert_with(Vec::new)
+            .push(calendar.attachment("invite.ics"));
+        self
+    }
+
     /// Add a binary attachment to the message.
     pub fn attachment(
         mut self,
@@ -385,7 +443,17 @@ impl<'x> MessageBuilder<'x> {
     }
 
     /// Build the message.
-    pub fn write_to(self, mut output: impl Write) -> io::Result<()> {
+    pub fn write_to(self, output: impl Write) -> io::Result<()> {
+        self.write_to_with(output, &mut mime::write_stream)
+    }
+
+    // Writes the message, calling `write_stream` to write the contents of
+    // the streamed parts.
+    pub(crate) fn write_to_with<W: Write>(
+        self,
+        mut output: W,
+        write_stream: &mut mime::WriteStream<'_, W>,
+    ) -> io::Result<()> {
         let mut has_date = false;
         let mut has_message_id = false;
         let mut has_mime_version = false;
@@ -422,44 +490,75 @@ impl<'x> MessageBuilder<'x> {
             output.write_all(b"MIME-Version: 1.0\r\n")?;
         }
 
-        self.write_body(output)
+        self.into_body().write_part_with(output, write_stream)?;
+
+        Ok(())
     }
 
     /// Write the message body without headers.
     pub fn write_body(self, output: impl Write) -> io::Result<()> {
-        (if let Some(body) = self.body {
+        self.into_body().write_part(output)?;
+
+        Ok(())
+    }
+
+    // Assembles the body of the message from its alternatives and
+    // attachments.
+    fn into_body(self) -> MimePart<'x> {
+        if let Some(body) = self.body {
             body
         } else {
-            match (self.text_body, self.html_body, self.attachments) {
-                (Some(text), Some(html), Some(attachments)) => {
-                    let mut parts = Vec::with_capacity(attachments.len() + 1);
-                    parts.push(MimePart::new("multipart/alternative", vec![text, html]));
-                    parts.extend(attachments);
-
-                    MimePart::new("multipart/mixed", parts)
-                }
-                (Some(text), Some(html), None) => MimePart::new("multipart/alternative", vec![text, html]),
-                (Some(text), None, Some(attachments)) => {
-                    let mut parts = Vec::with_capacity(attachments.len() + 1);
-                    parts.push(text);
-                    parts.extend(attachments);
-                    MimePart::new("multipart/mixed", parts)
+            let text_body = match (self.text_body, &self.html_body) {
+                (None, Some(html_body)) if self.generate_text_body => {
+                    let html = match &html_body.contents {
+                        BodyPart::Text(html) => Some(html.clone()),
+                        BodyPart::Binary(html) => Some(String::from_utf8_lossy(html)),
+                        BodyPart::Multipart(_) | BodyPart::Stream(_) => None,
+                    };
+                    html.map(|html| MimePart::new("text/plain", BodyPart::Text(html::html_to_text(&html).into())))
                 }
-                (Some(text), None, None) => text,
-                (None, Some(html), Some(attachments)) => {
+                (text_body, _) => text_body,
+            };
+            let mut alternatives = [text_body, self.html_body, self.calendar_body]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            let body = match alternatives.len() {
+                0 => None,
+                1 => alternatives.pop(),
+                _ => Some(MimePart::new("multipart/alternative", alternatives)),
+            };
+
+            match (body, self.attachments) {
+                (Some(body), Some(attachments)) => {
                     let mut parts = Vec::with_capacity(attachments.len() + 1);
-                    parts.push(html);
+                    parts.push(body);
                     parts.extend(attachments);
                     MimePart::new("multipart/mixed", parts)
                 }
-                (None, Some(html), None) => html,
-                (None, None, Some(attachments)) => MimePart::new("multipart/mixed", attachments),
-                (None, None, None) => MimePart::new("text/plain", "\n"),
+                (Some(body), None) => body,
+                (None, Some(attachments)) => MimePart::new("multipart/mixed", attachments),
+                (None, None) => MimePart::new("text/plain", "\n"),
             }
-        })
-        .write_part(output)?;
+        }
+    }
 
-        Ok(())
+    /// Build the message, prepending a DKIM-Signature header. The signature
+    /// covers the hash of the whole body, so the message is built in memory
+    /// before being written, including streamed body parts.
+    #[cfg(feature = "dkim")]
+    pub fn write_to_signed(self, mut output: impl Write, signer: &dkim::DkimSigner) -> io::Result<()> {
+        let message = self.write_to_vec()?;
+        let signature = signer.sign(&message).map_err(io::Error::other)?;
+        output.write_all(signature.as_bytes())?;
+        output.write_all(&message)
+    }
+
+    /// Parse an RFC5322 message, such as one written by `write_to`. The
+    /// Content-* headers of the message are set on the `body` MIME part,
+    /// whose parts are decoded. Returns `None` if the message has no headers.
+    pub fn parse(input: &[u8]) -> Option<MessageBuilder<'static>> {
+        parsers::parse_message(input)
     }
 
     /// Build message to a Vec<u8>.
@@ -11,7 +11,11 @@
 
 use std::{
     borrow::Cow,
-    io::{self, Write},
+    fmt::Debug,
+    fs::File,
+    io::{self, Read, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
@@ -19,9 +23,9 @@ use rand::Rng;
 
 use crate::{
     encoders::{
-        base64::base64_encode_mime,
+        base64::{base64_encode_mime, base64_encode_stream},
         encode::{EncodingType, get_encoding_type},
-        quoted_printable::quoted_printable_encode,
+        quoted_printable::{quoted_printable_encode, quoted_printable_encode_stream},
     },
     headers::{Header, HeaderType, content_type::ContentType, message_id::MessageId, raw::Raw, text::Text},
 };
@@ -38,6 +42,116 @@ pub enum BodyPart<'x> {
     Text(Cow<'x, str>),
     Binary(Cow<'x, [u8]>),
     Multipart(Vec<MimePart<'x>>),
+    Stream(Stream),
+}
+
+/// Contents that are read and encoded while the part is written, instead
+/// of being held in memory. Text bodies are quoted-printable encoded, any
+/// other part is base64 encoded.
+#[derive(Clone)]
+pub struct Stream {
+    source: StreamSource,
+    size: Option<u64>,
+}
+
+#[derive(Clone)]
+enum StreamSource {
+    Path(PathBuf),
+    // Shared by the clones of a part, the first one written takes it
+    Reader(Arc<Mutex<Option<Box<dyn Read + Send>>>>),
+}
+
+impl Stream {
+    /// Stream the contents of a file, which is opened when the part is
+    /// written.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        Stream {
+            source: StreamSource::Path(path.into()),
+            size: None,
+        }
+    }
+
+    /// Stream the contents of a reader. A reader can only be written once,
+    /// writing a clone of the part afterwards fails.
+    pub fn from_reader(reader: impl Read + Send + 'static) -> Self {
+        Stream {
+            source: StreamSource::Reader(Arc::new(Mutex::new(Some(Box::new(reader))))),
+            size: None,
+        }
+    }
+
+    /// Set the size of the contents returned by `MimePart::size`, which
+    /// otherwise is the length of the file, or zero for readers.
+    pub fn size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    // The size of the contents, if known
+    pub(crate) fn content_size(&self) -> usize {
+        match (&self.source, self.size) {
+            (_, Some(size)) => size as usize,
+            (StreamSource::Path(path), None) => path.metadata().map_or(0, |m| m.len() as usize),
+            (StreamSource::Reader(_), None) => 0,
+        }
+    }
+
+    /// Open the file, or take the reader.
+    pub fn open(&self) -> io::Result<Box<dyn Read + Send>> {
+        match &self.source {
+            StreamSource::Path(path) => Ok(Box::new(File::open(path)?)),
+            StreamSource::Reader(reader) => reader
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .take()
+                .ok_or_else(|| io::Error::other("the stream has already been read")),
+        }
+    }
+}
+
+/// How the contents of a streamed part are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StreamEncoding {
+    QuotedPrintable,
+    Base64,
+    // Written as they are, the part has its own Content-Transfer-Encoding
+    Raw,
+}
+
+impl StreamEncoding {
+    pub(crate) fn encode(self, mut input: impl Read, mut output: impl Write) -> io::Result<()> {
+        match self {
+            StreamEncoding::QuotedPrintable => quoted_printable_encode_stream(input, output),
+            StreamEncoding::Base64 => base64_encode_stream(input, output).map(|_| ()),
+            StreamEncoding::Raw => io::copy(&mut input, &mut output).map(|_| ()),
+        }
+    }
+
+    // An estimate of the size of `size` bytes once encoded
+    pub(crate) fn encoded_size(self, size: usize) -> usize {
+        match self {
+            // 4 characters for 3 bytes, and a CRLF every 76 characters
+            StreamEncoding::Base64 => size.div_ceil(3) * 4 * 78 / 76,
+            StreamEncoding::QuotedPrintable | StreamEncoding::Raw => size,
+        }
+    }
+}
+
+/// Writes the contents of a streamed part, once its headers are written
+pub(crate) type WriteStream<'a, W> = dyn FnMut(&mut W, Stream, StreamEncoding) -> io::Result<()> + 'a;
+
+// Reads and encodes the contents of a streamed part
+pub(crate) fn write_stream<W: Write>(output: &mut W, stream: Stream, encoding: StreamEncoding) -> io::Result<()> {
+    encoding.encode(stream.open()?, output)
+}
+
+impl Debug for Stream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.source {
+            StreamSource::Path(path) => f.debug_tuple("Stream").field(path).finish(),
+            StreamSource::Reader(_) => f.write_str("Stream(<reader>)"),
+        }
+    }
 }
 
 impl<'x> From<&'x str> for BodyPart<'x> {
@@ -82,6 +196,12 @@ impl<'x> From<Vec<MimePart<'x>>> for BodyPart<'x> {
     }
 }
 
+impl<'x> From<Stream> for BodyPart<'x> {
+    fn from(value: Stream) -> Self {
+        BodyPart::Stream(value)
+    }
+}
+
 impl<'x> From<&'x str> for ContentType<'x> {
     fn from(value: &'x str) -> Self {
         ContentType::new(value)
@@ -156,6 +276,12 @@ impl<'x> MimePart<'x> {
         }
     }
 
+    /// Parse a MIME part, including its headers. Transfer encodings are
+    /// decoded and text parts are converted to UTF-8.
+    pub fn parse(input: &[u8]) -> MimePart<'static> {
+        crate::parsers::parse_part(input)
+    }
+
     /// Create a new raw MIME part that includes both headers and body.
     pub fn raw(contents: impl Into<BodyPart<'x>>) -> Self {
         Self {
@@ -217,6 +343,7 @@ impl<'x> MimePart<'x> {
             BodyPart::Text(b) => b.len(),
             BodyPart::Binary(b) => b.len(),
             BodyPart::Multipart(bl) => bl.iter().map(|b| b.size()).sum(),
+            BodyPart::Stream(stream) => stream.content_size(),
         }
     }
 
@@ -228,7 +355,17 @@ impl<'x> MimePart<'x> {
     }
 
     /// Write the MIME part to a writer.
-    pub fn write_part(self, mut output: impl Write) -> io::Result<usize> {
+    pub fn write_part(self, output: impl Write) -> io::Result<usize> {
+        self.write_part_with(output, &mut write_stream)
+    }
+
+    // Writes the MIME part, calling `write_stream` to write the contents of
+    // the streamed parts once their headers are written.
+    pub(crate) fn write_part_with<W: Write>(
+        self,
+        mut output: W,
+        write_stream: &mut WriteStream<'_, W>,
+    ) -> io::Result<usize> {
         let mut stack = Vec::new();
         let mut it = vec![self].into_iter();
         let mut boundary: Option<Cow<str>> = None;
@@ -302,6 +439,43 @@ impl<'x> MimePart<'x> {
                             output.write_all(binary.as_ref())?;
                         }
                     }
+                    BodyPart::Stream(stream) => {
+                        let mut is_text = false;
+                        let mut is_attachment = false;
+                        let mut is_raw = part.headers.is_empty();
+
+                        for (header_name, header_value) in &part.headers {
+                            output.write_all(header_name.as_bytes())?;
+                            output.write_all(b": ")?;
+                            if !is_text && header_name == "Content-Type" {
+                                is_text = header_value.as_content_type().map(|v| v.is_text()).unwrap_or(false);
+                            } else if !is_attachment && header_name == "Content-Disposition" {
+                                is_attachment = header_value
+                                    .as_content_type()
+                                    .map(|v| v.is_attachment())
+                                    .unwrap_or(false);
+                            } else if !is_raw && header_name == "Content-Transfer-Encoding" {
+                                is_raw = true;
+                            }
+                            header_value.write_header(&mut output, header_name.len() + 2)?;
+                        }
+
+                        // The contents can't be inspected before writing them, so
+                        // text bodies are always quoted-printable encoded
+                        let encoding = if is_raw {
+                            if !part.headers.is_empty() {
+                                output.write_all(b"\r\n")?;
+                            }
+                            StreamEncoding::Raw
+                        } else if is_text && !is_attachment {
+                            output.write_all(b"Content-Transfer-Encoding: quoted-printable\r\n\r\n")?;
+                            StreamEncoding::QuotedPrintable
+                        } else {
+                            output.write_all(b"Content-Transfer-Encoding: base64\r\n\r\n")?;
+                            StreamEncoding::Base64
+                        };
+                        write_stream(&mut output, stream, encoding)?;
+                    }
                     BodyPart::Multipart(parts) => {
                         if boundary.is_some() {
                             stack.push((it, boundary.take()));
@@ -411,3 +585,57 @@ fn detect_encoding(input: &[u8], mut output: impl Write, is_body: bool) -> io::R
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{BodyPart, MimePart, Stream};
+
+    #[test]
+    fn write_stream() {
+        let part = MimePart::new("text/plain", Stream::from_reader(io::Cursor::new("Café crème\n".repeat(3))));
+        let mut output = Vec::new();
+        part.clone().write_part(&mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                "Content-Type: text/plain\r\n",
+                "Content-Transfer-Encoding: quoted-printable\r\n\r\n",
+                "Caf=C3=A9 cr=C3=A8me\r\n",
+                "Caf=C3=A9 cr=C3=A8me\r\n",
+                "Caf=C3=A9 cr=C3=A8me\r\n",
+            )
+        );
+        assert!(part.write_part(Vec::new()).is_err());
+
+        let contents = (0..10_000).map(|i| (i % 256) as u8).collect::<Vec<_>>();
+        let path = std::env::temp_dir().join(format!("mail_builder_stream_{}.bin", std::process::id()));
+        std::fs::write(&path, &contents).unwrap();
+
+        let streamed = MimePart::new("text/csv", Stream::from_path(&path)).attachment("data.csv");
+        assert_eq!(streamed.size(), contents.len());
+        let mut output = Vec::new();
+        streamed.write_part(&mut output).unwrap();
+        let mut expected_output = Vec::new();
+        MimePart::new("text/csv", BodyPart::Binary(contents.into()))
+            .attachment("data.csv")
+            .write_part(&mut expected_output)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), String::from_utf8(expected_output).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let raw = MimePart::new(
+            "message/rfc822",
+            Stream::from_reader(b"Subject: Hi\r\n\r\nHi!".as_ref()).size(19),
+        )
+        .transfer_encoding("7bit");
+        assert_eq!(raw.size(), 19);
+        let mut output = Vec::new();
+        raw.write_part(&mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Content-Type: message/rfc822\r\nContent-Transfer-Encoding: 7bit\r\n\r\nSubject: Hi\r\n\r\nHi!"
+        );
+    }
+}
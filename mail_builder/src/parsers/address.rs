@@ -0,0 +1,213 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use crate::{decoders::decode::rfc2047_decode, headers::address::Address};
+
+// A word of a display name, along with whether it was quoted.
+struct Word {
+    text: String,
+    is_quoted: bool,
+}
+
+/// Parses an RFC5322 address list, including groups. Returns a single
+/// `Address::Address` or `Address::Group` when the header contains only one
+/// item, and an `Address::List` otherwise.
+pub fn parse_address(value: &str) -> Option<Address<'static>> {
+    let mut items = Vec::new();
+    let mut group: Option<(Option<String>, Vec<Address<'static>>)> = None;
+    let mut words: Vec<Word> = Vec::new();
+    let mut word = String::new();
+    let mut email: Option<String> = None;
+    let mut chars = value.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                push_word(&mut words, &mut word);
+                let mut text = String::new();
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '"' => break,
+                        '\\' => text.extend(chars.next()),
+                        '\r' | '\n' => (),
+                        _ => text.push(ch),
+                    }
+                }
+                words.push(Word {
+                    text: rfc2047_decode(&text).into_owned(),
+                    is_quoted: true,
+                });
+            }
+            '<' => {
+                push_word(&mut words, &mut word);
+                email = Some(
+                    chars
+                        .by_ref()
+                        .take_while(|&ch| ch != '>')
+                        .collect::<String>()
+                        .trim()
+                        .to_string(),
+                );
+            }
+            '(' => {
+                push_word(&mut words, &mut word);
+                let mut depth = 1;
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '(' => depth += 1,
+                        ')' if depth == 1 => break,
+                        ')' => depth -= 1,
+                        '\\' => {
+                            chars.next();
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            ':' if group.is_none() && email.is_none() => {
+                push_word(&mut words, &mut word);
+                group = Some((display_name(&mut words), Vec::new()));
+            }
+            ',' | ';' => {
+                push_word(&mut words, &mut word);
+                if let Some(address) = take_address(&mut words, &mut email) {
+                    match &mut group {
+                        Some((_, addresses)) => addresses.push(address),
+                        None => items.push(address),
+                    }
+                }
+                if ch == ';'
+                    && let Some((name, addresses)) = group.take()
+                {
+                    items.push(Address::new_group(name, addresses));
+                }
+            }
+            _ if ch.is_whitespace() => push_word(&mut words, &mut word),
+            _ => word.push(ch),
+        }
+    }
+
+    push_word(&mut words, &mut word);
+    if let Some(address) = take_address(&mut words, &mut email) {
+        match &mut group {
+            Some((_, addresses)) => addresses.push(address),
+            None => items.push(address),
+        }
+    }
+    if let Some((name, addresses)) = group.take() {
+        items.push(Address::new_group(name, addresses));
+    }
+
+    match items.len() {
+        0 => None,
+        1 => items.pop(),
+        _ => Some(Address::new_list(items)),
+    }
+}
+
+fn push_word(words: &mut Vec<Word>, word: &mut String) {
+    if !word.is_empty() {
+        words.push(Word {
+            text: std::mem::take(word),
+            is_quoted: false,
+        });
+    }
+}
+
+fn take_address(words: &mut Vec<Word>, email: &mut Option<String>) -> Option<Address<'static>> {
+    if let Some(email) = email.take() {
+        Some(Address::new_address(display_name(words), email))
+    } else if !words.is_empty() {
+        // An addr-spec without angle brackets
+        let email = words.drain(..).map(|word| word.text).collect::<String>();
+        Some(Address::new_address(None::<String>, email))
+    } else {
+        None
+    }
+}
+
+fn display_name(words: &mut Vec<Word>) -> Option<String> {
+    let mut name = String::new();
+    let mut unquoted = Vec::new();
+
+    for word in words.drain(..) {
+        if word.is_quoted {
+            if !unquoted.is_empty() {
+                append_word(&mut name, &rfc2047_decode(&unquoted.join(" ")));
+                unquoted.clear();
+            }
+            append_word(&mut name, &word.text);
+        } else {
+            unquoted.push(word.text);
+        }
+    }
+    if !unquoted.is_empty() {
+        append_word(&mut name, &rfc2047_decode(&unquoted.join(" ")));
+    }
+
+    if !name.is_empty() { Some(name) } else { None }
+}
+
+fn append_word(name: &mut String, word: &str) {
+    if !name.is_empty() {
+        name.push(' ');
+    }
+    name.push_str(word);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::headers::address::Address;
+
+    #[test]
+    fn parse_addresses() {
+        for (input, expected_result) in [
+            ("john@doe.com", Address::from("john@doe.com")),
+            ("<john@doe.com>", Address::from("john@doe.com")),
+            ("\"John Doe\" <john@doe.com>", Address::from(("John Doe", "john@doe.com"))),
+            ("John (the) Doe <john@doe.com>", Address::from(("John Doe", "john@doe.com"))),
+            (
+                "\"Escaped \\\"quote\\\"\" <john@doe.com>",
+                Address::from(("Escaped \"quote\"", "john@doe.com")),
+            ),
+            (
+                "\"=?utf-8?Q?Antoine_de_Saint-Exup=C3=A9ry?=\" <antoine@exupery.com>",
+                Address::from(("Antoine de Saint-Exupéry", "antoine@exupery.com")),
+            ),
+            (
+                "=?utf-8?B?7JWI64WV7ZWY7IS47JqU?= =?utf-8?B?IOyEuOqzhA==?= <test@test.com>",
+                Address::from(("안녕하세요 세계", "test@test.com")),
+            ),
+            (
+                "\"Jane\" <jane@doe.com>, john@doe.com",
+                Address::new_list(vec![Address::from(("Jane", "jane@doe.com")), Address::from("john@doe.com")]),
+            ),
+            (
+                "\"My Group\": \"A\" <a@a.com>, <b@b.com>; \"Other\": <c@c.com>",
+                Address::new_list(vec![
+                    Address::new_group(
+                        Some("My Group"),
+                        vec![Address::from(("A", "a@a.com")), Address::from("b@b.com")],
+                    ),
+                    Address::new_group(Some("Other"), vec![Address::from("c@c.com")]),
+                ]),
+            ),
+            (
+                "Undisclosed recipients:;",
+                Address::new_group(Some("Undisclosed recipients"), vec![]),
+            ),
+        ] {
+            assert_eq!(super::parse_address(input), Some(expected_result), "failed for {input:?}");
+        }
+
+        assert_eq!(super::parse_address(" "), None);
+    }
+}
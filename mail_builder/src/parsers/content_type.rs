@@ -0,0 +1,214 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::borrow::Cow;
+
+use crate::{
+    decoders::{charset::decode_charset, decode::rfc2047_decode},
+    headers::content_type::ContentType,
+};
+
+enum Value {
+    Plain(String),
+    // RFC2231 sections, as (section number, is percent-encoded, value)
+    Sections(Vec<(u32, bool, String)>),
+}
+
+/// Parses a Content-Type or Content-Disposition header. The type and the
+/// attribute names are lowercased, RFC2231 continuations and charsets are
+/// decoded, as well as RFC2047 encoded words in quoted values.
+pub fn parse_content_type(value: &str) -> Option<ContentType<'static>> {
+    let (c_type, params) = value.split_once(';').unwrap_or((value, ""));
+    let c_type = c_type.trim();
+    if c_type.is_empty() {
+        return None;
+    }
+
+    let mut attributes: Vec<(String, Value)> = Vec::new();
+    for (key, value) in split_params(params) {
+        let (key, is_extended) = match key.strip_suffix('*') {
+            Some(key) => (key, true),
+            None => (key.as_str(), false),
+        };
+        let (key, section) = match key.rsplit_once('*') {
+            Some((name, section)) if !section.is_empty() && section.bytes().all(|ch| ch.is_ascii_digit()) => {
+                (name, section.parse().ok())
+            }
+            _ => (key, None),
+        };
+
+        if !is_extended && section.is_none() {
+            attributes.push((key.to_string(), Value::Plain(rfc2047_decode(&value).into_owned())));
+        } else if let Some((_, Value::Sections(sections))) = attributes
+            .iter_mut()
+            .find(|(name, value)| name == key && matches!(value, Value::Sections(_)))
+        {
+            sections.push((section.unwrap_or(0), is_extended, value));
+        } else {
+            attributes.push((
+                key.to_string(),
+                Value::Sections(vec![(section.unwrap_or(0), is_extended, value)]),
+            ));
+        }
+    }
+
+    Some(ContentType {
+        c_type: c_type.to_ascii_lowercase().into(),
+        attributes: attributes
+            .into_iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Value::Plain(value) => value,
+                    Value::Sections(sections) => join_sections(sections),
+                };
+                (Cow::from(key), Cow::from(value))
+            })
+            .collect(),
+    })
+}
+
+// Splits "; key=value; key="quoted value"" into lowercased keys and unquoted values.
+fn split_params(params: &str) -> Vec<(String, String)> {
+    let mut result = Vec::new();
+    let mut chars = params.chars().peekable();
+
+    loop {
+        while chars.next_if(|&ch| ch == ';' || ch.is_whitespace()).is_some() {}
+        let key = chars
+            .by_ref()
+            .take_while(|&ch| ch != '=')
+            .collect::<String>()
+            .trim()
+            .to_ascii_lowercase();
+        if key.is_empty() {
+            break;
+        }
+
+        while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            while let Some(ch) = chars.next() {
+                match ch {
+                    '"' => break,
+                    '\\' => value.extend(chars.next()),
+                    _ => value.push(ch),
+                }
+            }
+            while chars.next_if(|&ch| ch != ';').is_some() {}
+        } else {
+            while let Some(ch) = chars.next_if(|&ch| ch != ';') {
+                value.push(ch);
+            }
+            value.truncate(value.trim_end().len());
+        }
+
+        result.push((key, value));
+    }
+
+    result
+}
+
+fn join_sections(mut sections: Vec<(u32, bool, String)>) -> String {
+    sections.sort_by_key(|(section, _, _)| *section);
+
+    let mut charset = None;
+    let mut bytes = Vec::new();
+    for (pos, (_, is_extended, value)) in sections.iter().enumerate() {
+        if *is_extended {
+            let mut value = value.as_str();
+            // The first section of an extended value starts with charset'language'
+            if pos == 0
+                && let Some((value_charset, rest)) = value.split_once('\'')
+                && let Some((_, rest)) = rest.split_once('\'')
+            {
+                charset = Some(value_charset);
+                value = rest;
+            }
+            percent_decode(value.as_bytes(), &mut bytes);
+        } else {
+            bytes.extend_from_slice(value.as_bytes());
+        }
+    }
+
+    charset
+        .filter(|charset| !charset.is_empty())
+        .and_then(|charset| decode_charset(charset, &bytes))
+        .unwrap_or_else(|| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn percent_decode(input: &[u8], output: &mut Vec<u8>) {
+    let mut pos = 0;
+    while pos < input.len() {
+        if input[pos] == b'%'
+            && let Some(byte) = input
+                .get(pos + 1..pos + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            output.push(byte);
+            pos += 3;
+            continue;
+        }
+        output.push(input[pos]);
+        pos += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::headers::content_type::ContentType;
+
+    #[test]
+    fn parse_content_types() {
+        for (input, expected_result) in [
+            ("text/plain", ContentType::new("text/plain")),
+            (
+                "Text/Plain; Charset=\"utf-8\"",
+                ContentType::new("text/plain").attribute("charset", "utf-8"),
+            ),
+            (
+                "multipart/mixed; boundary=simple; format=flowed ",
+                ContentType::new("multipart/mixed")
+                    .attribute("boundary", "simple")
+                    .attribute("format", "flowed"),
+            ),
+            (
+                "attachment; filename=\"a \\\"quoted\\\" name; really.txt\"",
+                ContentType::new("attachment").attribute("filename", "a \"quoted\" name; really.txt"),
+            ),
+            (
+                "attachment; filename=\"=?utf-8?Q?my_f=C3=ADle.txt?=\"",
+                ContentType::new("attachment").attribute("filename", "my fíle.txt"),
+            ),
+            (
+                "attachment; filename*=iso-8859-1'es'%A1Hola%2C%20se%F1or!.txt",
+                ContentType::new("attachment").attribute("filename", "¡Hola, señor!.txt"),
+            ),
+            (
+                concat!(
+                    "message/external-body; access-type=URL;\r\n",
+                    "\tURL*0=\"ftp://\"; URL*1=\"cs.utk.edu/pub/moore/bulk-mailer/bulk-mailer.tar\"",
+                ),
+                ContentType::new("message/external-body")
+                    .attribute("access-type", "URL")
+                    .attribute("url", "ftp://cs.utk.edu/pub/moore/bulk-mailer/bulk-mailer.tar"),
+            ),
+            (
+                "attachment; filename*1*=%E2%82%AC.txt; filename*0*=utf-8''price%20",
+                ContentType::new("attachment").attribute("filename", "price €.txt"),
+            ),
+        ] {
+            assert_eq!(super::parse_content_type(input), Some(expected_result), "failed for {input:?}");
+        }
+
+        assert_eq!(super::parse_content_type(" ; charset=utf-8"), None);
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use crate::headers::date::{Date, MONTH};
+
+/// Parses an RFC5322 date such as "Tue, 1 Jul 2003 10:52:37 +0200" into a
+/// UTC timestamp. Obsolete two-digit years and named time zones are accepted.
+pub fn parse_date(value: &str) -> Option<Date> {
+    let mut tokens = value
+        .split(|ch: char| ch.is_ascii_whitespace() || ch == ',')
+        .filter(|token| !token.is_empty())
+        .peekable();
+
+    // Skip the optional day of the week
+    tokens.next_if(|token| token.starts_with(|ch: char| ch.is_ascii_alphabetic()));
+
+    let day: i64 = tokens.next()?.parse().ok()?;
+    let month = tokens.next()?;
+    let month = MONTH
+        .iter()
+        .position(|name| month.len() >= 3 && name.eq_ignore_ascii_case(&month[..3]))? as i64
+        + 1;
+    let year = tokens.next()?;
+    let year = match (year.parse::<i64>().ok()?, year.len()) {
+        (year, 1 | 2) if year < 50 => year + 2000,
+        (year, 1..=3) => year + 1900,
+        (year, _) => year,
+    };
+
+    let mut time = tokens.next()?.split(':');
+    let hour: i64 = time.next()?.parse().ok()?;
+    let minute: i64 = time.next()?.parse().ok()?;
+    let second: i64 = time.next().map_or(Some(0), |second| second.parse().ok())?;
+
+    if !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let offset = tokens.next().map_or(0, parse_zone);
+
+    Some(Date::new(
+        days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset,
+    ))
+}
+
+// Returns the offset from UTC in seconds
+fn parse_zone(zone: &str) -> i64 {
+    if let Some(sign) = zone
+        .strip_prefix('+')
+        .map(|_| 1)
+        .or_else(|| zone.strip_prefix('-').map(|_| -1))
+    {
+        zone.get(1..5)
+            .and_then(|offset| offset.parse::<i64>().ok())
+            .map_or(0, |offset| sign * ((offset / 100) * 3600 + (offset % 100) * 60))
+    } else {
+        let hours = match zone.to_ascii_uppercase().as_str() {
+            "EDT" => -4,
+            "EST" | "CDT" => -5,
+            "CST" | "MDT" => -6,
+            "MST" | "PDT" => -7,
+            "PST" => -8,
+            _ => 0,
+        };
+        hours * 3600
+    }
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // Ported from http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let yoe = year - era * 400; // [0, 399]
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1; // [0, 365]
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // [0, 146096]
+    era * 146097 + doe - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::headers::date::Date;
+
+    #[test]
+    fn parse_dates() {
+        for (input, expected_result) in [
+            ("Thu, 1 Jan 1970 00:00:00 +0000", 0),
+            ("Tue, 1 Jul 2003 10:52:37 +0200", 1057049557),
+            ("1 Jul 2003 10:52:37 +0200", 1057049557),
+            ("Tue,  1 Jul 2003 03:52:37 -0500 (CDT)", 1057049557),
+            ("Tue, 1 Jul 03 08:52 GMT", 1057049520),
+            ("Tue, 1 Jul 2003 01:52:37 PDT", 1057049557),
+            ("Thu, 29 Feb 2024 23:59:59 +0000", 1709251199),
+        ] {
+            assert_eq!(
+                super::parse_date(input),
+                Some(Date::new(expected_result)),
+                "failed for {input:?}"
+            );
+        }
+
+        for timestamp in [0, 951782400, 1057049557, 1709251199, 4102444800] {
+            let date = Date::new(timestamp);
+            assert_eq!(super::parse_date(&date.to_rfc822()), Some(date));
+        }
+
+        for input in ["", "Tue, 1 Foo 2003 10:52:37 +0200", "Tue, 32 Jul 2003 10:52:37 +0200"] {
+            assert_eq!(super::parse_date(input), None, "failed for {input:?}");
+        }
+    }
+}
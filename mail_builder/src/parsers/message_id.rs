@@ -0,0 +1,34 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use crate::headers::message_id::MessageId;
+
+/// Parses a list of message ids, removing the angle brackets.
+/// Ids without angle brackets are separated by whitespace.
+pub fn parse_message_id(value: &str) -> Option<MessageId<'static>> {
+    let ids = if value.contains('<') {
+        value
+            .split('<')
+            .skip(1)
+            .filter_map(|id| id.split_once('>'))
+            .map(|(id, _)| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .collect::<Vec<_>>()
+    } else {
+        value.split_whitespace().map(|id| id.to_string()).collect()
+    };
+
+    if !ids.is_empty() {
+        Some(MessageId::from(ids))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,482 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+pub mod address;
+pub mod content_type;
+pub mod date;
+pub mod message_id;
+pub mod url;
+
+use std::borrow::Cow;
+
+use self::{
+    address::parse_address, content_type::parse_content_type, date::parse_date, message_id::parse_message_id,
+    url::parse_url,
+};
+use crate::{
+    MessageBuilder,
+    decoders::{
+        base64::base64_decode,
+        charset::{decode_charset, is_utf8},
+        decode::rfc2047_decode,
+        quoted_printable::quoted_printable_decode,
+    },
+    headers::{HeaderType, content_type::ContentType, raw::Raw, text::Text},
+    mime::{BodyPart, MimePart},
+};
+
+/// Parses an RFC5322 message. The Content-* headers of the message are set
+/// on the `body` MIME part and the remaining ones on the message.
+/// Returns `None` if the message has no headers.
+pub fn parse_message(input: &[u8]) -> Option<MessageBuilder<'static>> {
+    let (headers, body) = split_headers(input);
+    if headers.is_empty() {
+        return None;
+    }
+
+    let mut message = MessageBuilder::new();
+    let mut part_headers = Vec::new();
+    for (name, value) in headers {
+        if is_content_header(&name) {
+            part_headers.push((name, value));
+        } else {
+            let value = parse_header(&name, &value);
+            message.headers.push((name.into(), value));
+        }
+    }
+    message.body = Some(parse_body(part_headers, body, "text/plain"));
+
+    Some(message)
+}
+
+/// Parses a MIME part, including its headers.
+pub fn parse_part(input: &[u8]) -> MimePart<'static> {
+    let (headers, body) = split_headers(input);
+    parse_body(headers, body, "text/plain")
+}
+
+/// Parses the value of a header into the `HeaderType` used by the builder for
+/// that header, falling back to `HeaderType::Raw` if the value is malformed.
+pub fn parse_header(name: &str, value: &str) -> HeaderType<'static> {
+    let parsed = match name.to_ascii_lowercase().as_str() {
+        "from"
+        | "to"
+        | "cc"
+        | "bcc"
+        | "reply-to"
+        | "sender"
+        | "resent-from"
+        | "resent-to"
+        | "resent-cc"
+        | "resent-bcc"
+        | "resent-sender"
+        | "return-path"
+        | "disposition-notification-to" => parse_address(value).map(HeaderType::from),
+        "date" | "resent-date" => parse_date(value).map(HeaderType::from),
+        "message-id" | "in-reply-to" | "references" | "content-id" | "resent-message-id" => {
+            parse_message_id(value).map(HeaderType::from)
+        }
+        "list-archive" | "list-help" | "list-owner" | "list-post" | "list-subscribe" | "list-unsubscribe" => {
+            parse_url(value).map(HeaderType::from)
+        }
+        "content-type" | "content-disposition" => parse_content_type(value).map(HeaderType::from),
+        "subject" | "comments" | "keywords" | "content-description" | "content-language" => {
+            Some(Text::new(rfc2047_decode(value).into_owned()).into())
+        }
+        _ => match rfc2047_decode(value) {
+            Cow::Owned(text) if text != value => Some(Text::new(text).into()),
+            _ => None,
+        },
+    };
+
+    parsed.unwrap_or_else(|| Raw::new(value.to_string()).into())
+}
+
+fn is_content_header(name: &str) -> bool {
+    name.len() > 8 && name.as_bytes()[..8].eq_ignore_ascii_case(b"content-")
+}
+
+// Splits the unfolded headers from the body, which starts after the first
+// empty line. Both CRLF and LF line endings are accepted.
+fn split_headers(input: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut body_start = input.len();
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let line_end = input[pos..]
+            .iter()
+            .position(|&ch| ch == b'\n')
+            .map_or(input.len(), |end| pos + end);
+        let line = input[pos..line_end]
+            .strip_suffix(b"\r")
+            .unwrap_or(&input[pos..line_end]);
+        let next_pos = (line_end + 1).min(input.len());
+
+        if line.is_empty() {
+            body_start = next_pos;
+            break;
+        } else if matches!(line[0], b' ' | b'\t') {
+            if let Some((_, value)) = headers.last_mut() {
+                // The builder folds lines by inserting "\r\n\t" before whitespace
+                let line = if line.len() > 1 && line[0] == b'\t' && matches!(line[1], b' ' | b'\t') {
+                    &line[1..]
+                } else {
+                    line
+                };
+                value.push_str(&String::from_utf8_lossy(line));
+            }
+        } else if let Some(colon) = line.iter().position(|&ch| ch == b':') {
+            headers.push((
+                String::from_utf8_lossy(&line[..colon]).trim().to_string(),
+                String::from_utf8_lossy(&line[colon + 1..]).into_owned(),
+            ));
+        } else if headers.is_empty() {
+            // Not a header block, treat the whole input as the body
+            return (headers, input);
+        }
+
+        pos = next_pos;
+    }
+
+    for (_, value) in &mut headers {
+        *value = value.trim().to_string();
+    }
+
+    (headers, &input[body_start..])
+}
+
+fn parse_body(headers: Vec<(String, String)>, body: &[u8], default_type: &str) -> MimePart<'static> {
+    let mut part_headers: Vec<(Cow<'static, str>, HeaderType<'static>)> = Vec::with_capacity(headers.len() + 1);
+    let mut encoding = None;
+
+    for (name, value) in headers {
+        if encoding.is_none() && name.eq_ignore_ascii_case("Content-Transfer-Encoding") {
+            encoding = Some(value.to_ascii_lowercase());
+        } else {
+            let value = parse_header(&name, &value);
+            part_headers.push((name.into(), value));
+        }
+    }
+
+    let ct_pos = match part_headers
+        .iter()
+        .position(|(name, value)| name.eq_ignore_ascii_case("Content-Type") && value.as_content_type().is_some())
+    {
+        Some(pos) => pos,
+        None => {
+            part_headers.insert(0, ("Content-Type".into(), ContentType::new(default_type.to_string()).into()));
+            0
+        }
+    };
+    let content_type = part_headers[ct_pos].1.as_content_type().unwrap();
+    let c_type = content_type.c_type.to_string();
+    let boundary = attribute(content_type, "boundary").map(str::to_string);
+    let charset = attribute(content_type, "charset").unwrap_or("us-ascii").to_string();
+
+    if let (true, Some(boundary)) = (c_type.starts_with("multipart/"), boundary) {
+        let default_type = if c_type == "multipart/digest" {
+            "message/rfc822"
+        } else {
+            "text/plain"
+        };
+        let parts = split_multipart(body, &boundary)
+            .into_iter()
+            .map(|part| {
+                let (headers, body) = split_headers(part);
+                parse_body(headers, body, default_type)
+            })
+            .collect::<Vec<_>>();
+
+        return MimePart {
+            headers: part_headers,
+            contents: BodyPart::Multipart(parts),
+        };
+    }
+
+    let decoded = match encoding.as_deref() {
+        Some("base64") => base64_decode(body),
+        Some("quoted-printable") => Some(quoted_printable_decode(body, false)),
+        None | Some("7bit" | "8bit" | "binary") => Some(body.to_vec()),
+        _ => None,
+    };
+    let Some(decoded) = decoded else {
+        // Keep the part as-is, the builder writes parts with a
+        // Content-Transfer-Encoding header without encoding them
+        part_headers.push((
+            "Content-Transfer-Encoding".into(),
+            Raw::new(encoding.unwrap_or_default()).into(),
+        ));
+        return MimePart {
+            headers: part_headers,
+            contents: BodyPart::Binary(body.to_vec().into()),
+        };
+    };
+
+    let contents = if c_type.starts_with("text/") {
+        match decode_charset(&charset, &decoded) {
+            Some(text) => {
+                if !is_utf8(&charset)
+                    && let HeaderType::ContentType(content_type) = &mut part_headers[ct_pos].1
+                {
+                    for (key, value) in &mut content_type.attributes {
+                        if key.eq_ignore_ascii_case("charset") {
+                            *value = "utf-8".into();
+                        }
+                    }
+                }
+                BodyPart::Text(text.into())
+            }
+            None => BodyPart::Binary(decoded.into()),
+        }
+    } else if encoding.as_deref() == Some("base64") {
+        BodyPart::Binary(decoded.into())
+    } else {
+        match String::from_utf8(decoded) {
+            Ok(text) => BodyPart::Text(text.into()),
+            Err(err) => BodyPart::Binary(err.into_bytes().into()),
+        }
+    };
+
+    MimePart {
+        headers: part_headers,
+        contents,
+    }
+}
+
+fn attribute<'x>(content_type: &'x ContentType, name: &str) -> Option<&'x str> {
+    content_type
+        .attributes
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_ref())
+}
+
+// Returns the parts of a multipart body, without the line break that precedes
+// each delimiter line.
+fn split_multipart<'x>(body: &'x [u8], boundary: &str) -> Vec<&'x [u8]> {
+    let delimiter = format!("--{boundary}");
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut pos = 0;
+
+    while pos < body.len() {
+        let line_end = body[pos..]
+            .iter()
+            .position(|&ch| ch == b'\n')
+            .map_or(body.len(), |end| pos + end);
+
+        if let Some(rest) = body[pos..line_end].strip_prefix(delimiter.as_bytes()) {
+            let is_last = rest.starts_with(b"--");
+            let padding = if is_last { &rest[2..] } else { rest };
+
+            if padding.iter().all(|ch| ch.is_ascii_whitespace()) {
+                if let Some(start) = start {
+                    let mut end = pos;
+                    if end > start && body[end - 1] == b'\n' {
+                        end -= 1;
+                        if end > start && body[end - 1] == b'\r' {
+                            end -= 1;
+                        }
+                    }
+                    parts.push(&body[start..end]);
+                }
+                if is_last {
+                    return parts;
+                }
+                start = Some((line_end + 1).min(body.len()));
+            }
+        }
+
+        pos = line_end + 1;
+    }
+
+    // Missing closing delimiter
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        MessageBuilder,
+        headers::{
+            HeaderType, address::Address, content_type::ContentType, date::Date, message_id::MessageId, raw::Raw,
+            text::Text, url::URL,
+        },
+        mime::{BodyPart, MimePart},
+    };
+
+    fn header<'x>(headers: &'x [(std::borrow::Cow<str>, HeaderType<'x>)], name: &str) -> &'x HeaderType<'x> {
+        &headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn parse_built_message() {
+        let bcc = Address::from(vec![
+            (
+                "My Group",
+                vec![("ASCII name", "addr1@addr7.com"), ("áéíóú", "addr3@addr5.com")],
+            ),
+            (
+                "Another Group",
+                vec![("שלום עולם", "addr5@addr3.com"), ("Recipient", "addr7@addr1.com")],
+            ),
+        ]);
+        let output = MessageBuilder::new()
+            .message_id("unique-id@doe.com")
+            .date(Date::new(1057049557))
+            .from(("John Doe", "john@doe.com"))
+            .to(vec![
+                ("Antoine de Saint-Exupéry", "antoine@exupery.com"),
+                ("안녕하세요 세계", "test@test.com"),
+            ])
+            .bcc(bcc.clone())
+            .subject("¡El ñandú comió ñoquis! This subject is long enough to be split over several lines")
+            .in_reply_to(vec!["message-id-1", "message-id-2"])
+            .header("List-Archive", URL::new("http://example.com/archive"))
+            .header("X-Custom", Raw::new("a custom header"))
+            .text_body("This is the text body!\n")
+            .html_body("<p>¡Hola Mundo!</p>".repeat(20))
+            .inline("image/png", "cid:my-image", [0, 1, 2, 3, 4, 5].as_ref())
+            .attachment("text/plain", "my fíle.txt", "안녕하세요 세계".repeat(20))
+            .attachment("application/octet-stream", "data.bin", [0xffu8; 100].as_ref())
+            .write_to_vec()
+            .unwrap();
+
+        let message = MessageBuilder::parse(&output).unwrap();
+        assert_eq!(
+            header(&message.headers, "From"),
+            &HeaderType::from(Address::from(("John Doe", "john@doe.com")))
+        );
+        assert_eq!(header(&message.headers, "Bcc"), &HeaderType::from(bcc));
+        assert_eq!(
+            header(&message.headers, "Subject"),
+            &HeaderType::from(Text::new(
+                "¡El ñandú comió ñoquis! This subject is long enough to be split over several lines"
+            ))
+        );
+        assert_eq!(
+            header(&message.headers, "In-Reply-To"),
+            &HeaderType::from(MessageId::from(vec!["message-id-1", "message-id-2"]))
+        );
+        assert_eq!(header(&message.headers, "Date"), &HeaderType::from(Date::new(1057049557)));
+
+        let body = message.body.as_ref().unwrap();
+        let BodyPart::Multipart(parts) = &body.contents else {
+            panic!("Expected a multipart body, got {:?}", body.contents);
+        };
+        assert_eq!(parts.len(), 4);
+        assert!(matches!(&parts[0].contents, BodyPart::Multipart(alternative) if alternative.len() == 2));
+        assert!(matches!(&parts[1].contents, BodyPart::Binary(image) if image.as_ref() == [0, 1, 2, 3, 4, 5]));
+        assert!(matches!(&parts[2].contents, BodyPart::Text(text) if text == &"안녕하세요 세계".repeat(20)));
+        assert!(matches!(&parts[3].contents, BodyPart::Binary(data) if data.as_ref() == [0xffu8; 100]));
+        assert_eq!(
+            header(&parts[2].headers, "Content-Disposition"),
+            &HeaderType::from(ContentType::new("attachment").attribute("filename", "my fíle.txt"))
+        );
+
+        // Writing the parsed message produces the same output
+        assert_eq!(
+            String::from_utf8(message.write_to_vec().unwrap()).unwrap(),
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_external_message() {
+        let message = MessageBuilder::parse(
+            concat!(
+                "From: =?iso-8859-1?Q?Andr=E9?= Pirard <PIRARD@vm1.ulg.ac.be>\n",
+                "To: Undisclosed recipients:;\n",
+                "Subject: =?utf-8?B?SGVsbG8s?=\n",
+                " =?utf-8?B?IHdvcmxk?=\n",
+                "Date: Tue, 1 Jul 2003 10:52:37 +0200\n",
+                "Message-ID: <1234@local.machine.example>\n",
+                "MIME-Version: 1.0\n",
+                "Content-Type: multipart/mixed; boundary=\"outer\"\n",
+                "\n",
+                "This is the preamble.\n",
+                "--outer\n",
+                "Content-Type: text/plain; charset=iso-8859-1\n",
+                "Content-Transfer-Encoding: quoted-printable\n",
+                "\n",
+                "Caf=E9 cr=E8me, a long line that is =\n",
+                "softly broken.\n",
+                "--outer\n",
+                "Content-Type: application/pdf\n",
+                "Content-Disposition: attachment;\n",
+                " filename*0*=utf-8''r%C3%A9sum;\n",
+                " filename*1*=%C3%A9.pdf\n",
+                "Content-Transfer-Encoding: base64\n",
+                "\n",
+                "JVBERi0xLjQ=\n",
+                "--outer--\n",
+                "This is the epilogue.\n",
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            header(&message.headers, "From"),
+            &HeaderType::from(Address::from(("André Pirard", "PIRARD@vm1.ulg.ac.be")))
+        );
+        assert_eq!(
+            header(&message.headers, "To"),
+            &HeaderType::from(Address::new_group(Some("Undisclosed recipients"), vec![]))
+        );
+        assert_eq!(
+            header(&message.headers, "Subject"),
+            &HeaderType::from(Text::new("Hello, world"))
+        );
+        assert_eq!(header(&message.headers, "Date"), &HeaderType::from(Date::new(1057049557)));
+        assert_eq!(
+            header(&message.headers, "Message-ID"),
+            &HeaderType::from(MessageId::new("1234@local.machine.example"))
+        );
+
+        let body = message.body.unwrap();
+        let BodyPart::Multipart(parts) = body.contents else {
+            panic!("Expected a multipart body, got {:?}", body.contents);
+        };
+        assert_eq!(parts.len(), 2);
+        assert!(
+            matches!(&parts[0].contents, BodyPart::Text(text) if text == "Café crème, a long line that is softly broken.")
+        );
+        assert_eq!(
+            header(&parts[0].headers, "Content-Type"),
+            &HeaderType::from(ContentType::new("text/plain").attribute("charset", "utf-8"))
+        );
+        assert!(
+            !parts[0]
+                .headers
+                .iter()
+                .any(|(name, _)| name == "Content-Transfer-Encoding")
+        );
+        assert!(matches!(&parts[1].contents, BodyPart::Binary(pdf) if pdf.as_ref() == b"%PDF-1.4"));
+        assert_eq!(
+            header(&parts[1].headers, "Content-Disposition"),
+            &HeaderType::from(ContentType::new("attachment").attribute("filename", "résumé.pdf"))
+        );
+
+        let part = MimePart::parse(b"Content-Type: text/html\r\n\r\n<p>Hello</p>");
+        assert!(matches!(part.contents, BodyPart::Text(text) if text == "<p>Hello</p>"));
+        assert_eq!(MessageBuilder::parse(b"no headers here\r\n").map(|_| ()), None);
+    }
+}
@@ -0,0 +1,26 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use crate::headers::url::URL;
+
+/// Parses a List-* header such as "<mailto:list@host.com>, <https://host.com/list>".
+/// Returns `None` if the header contains no URLs in angle brackets.
+pub fn parse_url(value: &str) -> Option<URL<'static>> {
+    let urls = value
+        .split('<')
+        .skip(1)
+        .filter_map(|url| url.split_once('>'))
+        .map(|(url, _)| url.split_whitespace().collect::<String>())
+        .filter(|url| !url.is_empty())
+        .collect::<Vec<_>>();
+
+    if !urls.is_empty() { Some(URL::from(urls)) } else { None }
+}
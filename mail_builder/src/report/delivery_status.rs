@@ -0,0 +1,321 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::borrow::Cow;
+
+use super::{address_field, fields_part, report};
+use crate::{
+    headers::{HeaderType, date::Date, raw::Raw},
+    mime::MimePart,
+};
+
+/// What the reporting MTA did with the message for a recipient
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// The message could not be delivered
+    Failed,
+    /// Delivery failed for now, and will be retried
+    Delayed,
+    Delivered,
+    /// The message was relayed to a system that does not send DSNs
+    Relayed,
+    /// The message was delivered to a mailing list or alias
+    Expanded,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::Failed => "failed",
+            Action::Delayed => "delayed",
+            Action::Delivered => "delivered",
+            Action::Relayed => "relayed",
+            Action::Expanded => "expanded",
+        }
+    }
+}
+
+/// The per-recipient fields of a delivery status notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipientStatus<'x> {
+    /// The address given in the ORCPT parameter
+    pub original_recipient: Option<Cow<'x, str>>,
+    pub final_recipient: Cow<'x, str>,
+    pub action: Action,
+    /// The enhanced status code (_RFC 3463_), such as "5.1.1"
+    pub status: Cow<'x, str>,
+    pub remote_mta: Option<Cow<'x, str>>,
+    /// The reply of the remote server, such as "550 5.1.1 User unknown"
+    pub diagnostic_code: Option<Cow<'x, str>>,
+    pub last_attempt_date: Option<Date>,
+    pub will_retry_until: Option<Date>,
+    /// Extension fields, written after the standard ones
+    pub headers: Vec<(Cow<'x, str>, HeaderType<'x>)>,
+}
+
+impl<'x> RecipientStatus<'x> {
+    pub fn new(final_recipient: impl Into<Cow<'x, str>>, action: Action, status: impl Into<Cow<'x, str>>) -> Self {
+        RecipientStatus {
+            original_recipient: None,
+            final_recipient: final_recipient.into(),
+            action,
+            status: status.into(),
+            remote_mta: None,
+            diagnostic_code: None,
+            last_attempt_date: None,
+            will_retry_until: None,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Set the Original-Recipient field.
+    pub fn original_recipient(mut self, address: impl Into<Cow<'x, str>>) -> Self {
+        self.original_recipient = Some(address.into());
+        self
+    }
+
+    /// Set the Remote-MTA field, the host name of the server that
+    /// rejected the message.
+    pub fn remote_mta(mut self, host: impl Into<Cow<'x, str>>) -> Self {
+        self.remote_mta = Some(host.into());
+        self
+    }
+
+    /// Set the Diagnostic-Code field from the SMTP reply of the remote
+    /// server.
+    pub fn diagnostic_code(mut self, response: impl Into<Cow<'x, str>>) -> Self {
+        self.diagnostic_code = Some(response.into());
+        self
+    }
+
+    pub fn last_attempt_date(mut self, date: impl Into<Date>) -> Self {
+        self.last_attempt_date = Some(date.into());
+        self
+    }
+
+    /// Set the Will-Retry-Until field of delayed recipients.
+    pub fn will_retry_until(mut self, date: impl Into<Date>) -> Self {
+        self.will_retry_until = Some(date.into());
+        self
+    }
+
+    /// Add an extension field.
+    pub fn header(mut self, header: impl Into<Cow<'x, str>>, value: impl Into<HeaderType<'x>>) -> Self {
+        self.headers.push((header.into(), value.into()));
+        self
+    }
+
+    fn fields(&self) -> Vec<(&str, HeaderType<'_>)> {
+        let mut fields = Vec::with_capacity(8 + self.headers.len());
+        if let Some(address) = &self.original_recipient {
+            fields.push(("Original-Recipient", Raw::new(address_field(address)).into()));
+        }
+        fields.push(("Final-Recipient", Raw::new(address_field(&self.final_recipient)).into()));
+        fields.push(("Action", Raw::new(self.action.as_str()).into()));
+        fields.push(("Status", Raw::new(self.status.as_ref()).into()));
+        if let Some(host) = &self.remote_mta {
+            fields.push(("Remote-MTA", Raw::new(format!("dns; {}", host)).into()));
+        }
+        if let Some(response) = &self.diagnostic_code {
+            fields.push(("Diagnostic-Code", Raw::new(format!("smtp; {}", response)).into()));
+        }
+        if let Some(date) = &self.last_attempt_date {
+            fields.push(("Last-Attempt-Date", date.clone().into()));
+        }
+        if let Some(date) = &self.will_retry_until {
+            fields.push(("Will-Retry-Until", date.clone().into()));
+        }
+        for (name, value) in &self.headers {
+            fields.push((name.as_ref(), value.clone()));
+        }
+        fields
+    }
+}
+
+/// A delivery status notification (message/delivery-status), sent by an
+/// MTA when a message is delayed, could not be delivered, or was delivered
+/// and a notification was requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryStatus<'x> {
+    /// The host name of the MTA sending the report
+    pub reporting_mta: Cow<'x, str>,
+    /// The ENVID parameter of the original message
+    pub envelope_id: Option<Cow<'x, str>>,
+    /// The host name of the MTA the message was received from
+    pub received_from_mta: Option<Cow<'x, str>>,
+    pub arrival_date: Option<Date>,
+    pub recipients: Vec<RecipientStatus<'x>>,
+    /// Extension fields, written after the standard per-message ones
+    pub headers: Vec<(Cow<'x, str>, HeaderType<'x>)>,
+}
+
+impl<'x> DeliveryStatus<'x> {
+    pub fn new(reporting_mta: impl Into<Cow<'x, str>>) -> Self {
+        DeliveryStatus {
+            reporting_mta: reporting_mta.into(),
+            envelope_id: None,
+            received_from_mta: None,
+            arrival_date: None,
+            recipients: Vec::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Set the Original-Envelope-Id field.
+    pub fn envelope_id(mut self, envelope_id: impl Into<Cow<'x, str>>) -> Self {
+        self.envelope_id = Some(envelope_id.into());
+        self
+    }
+
+    pub fn received_from_mta(mut self, host: impl Into<Cow<'x, str>>) -> Self {
+        self.received_from_mta = Some(host.into());
+        self
+    }
+
+    pub fn arrival_date(mut self, date: impl Into<Date>) -> Self {
+        self.arrival_date = Some(date.into());
+        self
+    }
+
+    pub fn recipient(mut self, recipient: RecipientStatus<'x>) -> Self {
+        self.recipients.push(recipient);
+        self
+    }
+
+    /// Add a per-message extension field.
+    pub fn header(mut self, header: impl Into<Cow<'x, str>>, value: impl Into<HeaderType<'x>>) -> Self {
+        self.headers.push((header.into(), value.into()));
+        self
+    }
+
+    /// Returns the message/delivery-status part.
+    pub fn mime_part(&self) -> MimePart<'x> {
+        let mut fields = Vec::with_capacity(4 + self.headers.len());
+        if let Some(envelope_id) = &self.envelope_id {
+            fields.push(("Original-Envelope-Id", Raw::new(envelope_id.as_ref()).into()));
+        }
+        fields.push(("Reporting-MTA", Raw::new(format!("dns; {}", self.reporting_mta)).into()));
+        if let Some(host) = &self.received_from_mta {
+            fields.push(("Received-From-MTA", Raw::new(format!("dns; {}", host)).into()));
+        }
+        if let Some(date) = &self.arrival_date {
+            fields.push(("Arrival-Date", date.clone().into()));
+        }
+        for (name, value) in &self.headers {
+            fields.push((name.as_ref(), value.clone()));
+        }
+
+        let mut groups = vec![fields];
+        groups.extend(self.recipients.iter().map(RecipientStatus::fields));
+        fields_part("message/delivery-status", &groups)
+    }
+
+    /// Returns a multipart/report with a text/plain explanation, the
+    /// delivery status and, optionally, the original message or its
+    /// headers, to use as the body of the notification.
+    pub fn into_report(self, explanation: impl Into<Cow<'x, str>>, original: Option<MimePart<'x>>) -> MimePart<'x> {
+        report("delivery-status", explanation.into(), self.mime_part(), original)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, DeliveryStatus, RecipientStatus};
+    use crate::{
+        MessageBuilder,
+        headers::{raw::Raw, text::Text},
+        report::original_headers,
+    };
+
+    #[test]
+    fn build_delivery_status() {
+        let status = DeliveryStatus::new("mx.example.com")
+            .envelope_id("QQ314159")
+            .received_from_mta("mail.example.org")
+            .arrival_date(1700042400i64)
+            .header("X-Postfix-Queue-ID", Raw::new("4Z1Xb20Qz"))
+            .recipient(
+                RecipientStatus::new("jane@example.com", Action::Failed, "5.1.1")
+                    .original_recipient("jane+lists@example.com")
+                    .remote_mta("mx.example.net")
+                    .diagnostic_code("550 5.1.1 <jane@example.com>: Recipient address rejected: User unknown in virtual mailbox table")
+                    .last_attempt_date(1700042460i64),
+            )
+            .recipient(
+                RecipientStatus::new("jöhn@example.com", Action::Delayed, "4.4.1")
+                    .will_retry_until(1700474400i64)
+                    .header("X-Attempts", Text::new("3")),
+            );
+
+        let mut part = Vec::new();
+        status.mime_part().write_part(&mut part).unwrap();
+        assert_eq!(
+            String::from_utf8(part).unwrap(),
+            concat!(
+                "Content-Type: message/delivery-status\r\n",
+                "Content-Transfer-Encoding: 8bit\r\n\r\n",
+                "Original-Envelope-Id: QQ314159\r\n",
+                "Reporting-MTA: dns; mx.example.com\r\n",
+                "Received-From-MTA: dns; mail.example.org\r\n",
+                "Arrival-Date: Wed, 15 Nov 2023 10:00:00 +0000\r\n",
+                "X-Postfix-Queue-ID: 4Z1Xb20Qz\r\n",
+                "\r\n",
+                "Original-Recipient: rfc822; jane+lists@example.com\r\n",
+                "Final-Recipient: rfc822; jane@example.com\r\n",
+                "Action: failed\r\n",
+                "Status: 5.1.1\r\n",
+                "Remote-MTA: dns; mx.example.net\r\n",
+                "Diagnostic-Code: smtp; 550 5.1.1 <jane@example.com>: Recipient address rejected:\r\n",
+                "\t User unknown in virtual mailbox table\r\n",
+                "Last-Attempt-Date: Wed, 15 Nov 2023 10:01:00 +0000\r\n",
+                "\r\n",
+                "Final-Recipient: utf-8; jöhn@example.com\r\n",
+                "Action: delayed\r\n",
+                "Status: 4.4.1\r\n",
+                "Will-Retry-Until: Mon, 20 Nov 2023 10:00:00 +0000\r\n",
+                "X-Attempts: 3\r\n",
+            )
+        );
+    }
+
+    #[test]
+    fn build_bounce() {
+        let original = b"From: john@example.com\r\nTo: jane@example.com\r\nSubject: Hi\r\n\r\nHello!\r\n";
+        let bounce = MessageBuilder::new()
+            .from("mailer-daemon@mx.example.com")
+            .to("john@example.com")
+            .subject("Undelivered Mail Returned to Sender")
+            .body(
+                DeliveryStatus::new("mx.example.com")
+                    .recipient(RecipientStatus::new("jane@example.com", Action::Failed, "5.1.1"))
+                    .into_report("Your message could not be delivered.", Some(original_headers(original))),
+            )
+            .write_to_string()
+            .unwrap();
+
+        let boundary = bounce
+            .split_once("Content-Type: multipart/report; report-type=\"delivery-status\";")
+            .and_then(|(_, rest)| rest.split_once("boundary=\""))
+            .and_then(|(_, rest)| rest.split_once('"'))
+            .map(|(boundary, _)| boundary)
+            .unwrap();
+        let parts = bounce.split(&format!("\r\n--{}", boundary)).collect::<Vec<_>>();
+
+        assert_eq!(parts.len(), 5);
+        assert!(parts[1].starts_with("\r\nContent-Type: text/plain; charset=\"utf-8\"\r\n"));
+        assert!(parts[1].ends_with("\r\n\r\nYour message could not be delivered."));
+        assert!(parts[2].starts_with("\r\nContent-Type: message/delivery-status\r\n"));
+        assert!(parts[2].contains("\r\n\r\nFinal-Recipient: rfc822; jane@example.com\r\nAction: failed\r\n"));
+        assert!(parts[3].starts_with("\r\nContent-Type: text/rfc822-headers\r\n"));
+        assert!(parts[3].ends_with("\r\n\r\nFrom: john@example.com\r\nTo: jane@example.com\r\nSubject: Hi\r\n"));
+        assert_eq!(parts[4], "--\r\n");
+    }
+}
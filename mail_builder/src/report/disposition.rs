@@ -0,0 +1,228 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::{borrow::Cow, fmt::Display};
+
+use super::{address_field, fields_part, report};
+use crate::{
+    headers::{HeaderType, message_id::MessageId, raw::Raw},
+    mime::MimePart,
+};
+
+/// Whether the disposition was the result of a user action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActionMode {
+    #[default]
+    Manual,
+    Automatic,
+}
+
+/// Whether the user was asked before the notification was sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SendingMode {
+    #[default]
+    Manual,
+    Automatic,
+}
+
+/// What happened to the message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispositionType {
+    Displayed,
+    Deleted,
+    Dispatched,
+    Processed,
+}
+
+/// The Disposition field of a message disposition notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disposition {
+    pub action_mode: ActionMode,
+    pub sending_mode: SendingMode,
+    pub disposition_type: DispositionType,
+}
+
+impl Disposition {
+    /// Create a disposition for an action taken by the user, such as
+    /// opening the message, who also agreed to send the notification.
+    pub fn new(disposition_type: DispositionType) -> Self {
+        Disposition {
+            action_mode: ActionMode::Manual,
+            sending_mode: SendingMode::Manual,
+            disposition_type,
+        }
+    }
+
+    pub fn action_mode(mut self, action_mode: ActionMode) -> Self {
+        self.action_mode = action_mode;
+        self
+    }
+
+    pub fn sending_mode(mut self, sending_mode: SendingMode) -> Self {
+        self.sending_mode = sending_mode;
+        self
+    }
+}
+
+impl From<DispositionType> for Disposition {
+    fn from(disposition_type: DispositionType) -> Self {
+        Disposition::new(disposition_type)
+    }
+}
+
+impl Display for Disposition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}; {}",
+            match self.action_mode {
+                ActionMode::Manual => "manual-action",
+                ActionMode::Automatic => "automatic-action",
+            },
+            match self.sending_mode {
+                SendingMode::Manual => "MDN-sent-manually",
+                SendingMode::Automatic => "MDN-sent-automatically",
+            },
+            match self.disposition_type {
+                DispositionType::Displayed => "displayed",
+                DispositionType::Deleted => "deleted",
+                DispositionType::Dispatched => "dispatched",
+                DispositionType::Processed => "processed",
+            }
+        )
+    }
+}
+
+/// A message disposition notification (message/disposition-notification),
+/// sent by a mail user agent when a message requesting one with
+/// Disposition-Notification-To is displayed or otherwise processed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispositionNotification<'x> {
+    /// The name of the mail user agent sending the report
+    pub reporting_ua: Option<Cow<'x, str>>,
+    /// The Original-Recipient header of the message, if any
+    pub original_recipient: Option<Cow<'x, str>>,
+    pub final_recipient: Cow<'x, str>,
+    pub original_message_id: Option<MessageId<'x>>,
+    pub disposition: Disposition,
+    /// Extension fields, written after the standard ones
+    pub headers: Vec<(Cow<'x, str>, HeaderType<'x>)>,
+}
+
+impl<'x> DispositionNotification<'x> {
+    pub fn new(final_recipient: impl Into<Cow<'x, str>>, disposition: impl Into<Disposition>) -> Self {
+        DispositionNotification {
+            reporting_ua: None,
+            original_recipient: None,
+            final_recipient: final_recipient.into(),
+            original_message_id: None,
+            disposition: disposition.into(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn reporting_ua(mut self, reporting_ua: impl Into<Cow<'x, str>>) -> Self {
+        self.reporting_ua = Some(reporting_ua.into());
+        self
+    }
+
+    pub fn original_recipient(mut self, address: impl Into<Cow<'x, str>>) -> Self {
+        self.original_recipient = Some(address.into());
+        self
+    }
+
+    /// Set the Original-Message-ID field, the Message-ID of the message
+    /// being acknowledged.
+    pub fn original_message_id(mut self, message_id: impl Into<MessageId<'x>>) -> Self {
+        self.original_message_id = Some(message_id.into());
+        self
+    }
+
+    /// Add an extension field.
+    pub fn header(mut self, header: impl Into<Cow<'x, str>>, value: impl Into<HeaderType<'x>>) -> Self {
+        self.headers.push((header.into(), value.into()));
+        self
+    }
+
+    /// Returns the message/disposition-notification part.
+    pub fn mime_part(&self) -> MimePart<'x> {
+        let mut fields = Vec::with_capacity(5 + self.headers.len());
+        if let Some(reporting_ua) = &self.reporting_ua {
+            fields.push(("Reporting-UA", Raw::new(reporting_ua.as_ref()).into()));
+        }
+        if let Some(address) = &self.original_recipient {
+            fields.push(("Original-Recipient", Raw::new(address_field(address)).into()));
+        }
+        fields.push(("Final-Recipient", Raw::new(address_field(&self.final_recipient)).into()));
+        if let Some(message_id) = &self.original_message_id {
+            fields.push(("Original-Message-ID", message_id.clone().into()));
+        }
+        fields.push(("Disposition", Raw::new(self.disposition.to_string()).into()));
+        for (name, value) in &self.headers {
+            fields.push((name.as_ref(), value.clone()));
+        }
+
+        fields_part("message/disposition-notification", &[fields])
+    }
+
+    /// Returns a multipart/report with a text/plain explanation, the
+    /// disposition notification and, optionally, the original message or
+    /// its headers, to use as the body of the notification.
+    pub fn into_report(self, explanation: impl Into<Cow<'x, str>>, original: Option<MimePart<'x>>) -> MimePart<'x> {
+        report("disposition-notification", explanation.into(), self.mime_part(), original)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActionMode, Disposition, DispositionNotification, DispositionType, SendingMode};
+
+    #[test]
+    fn build_disposition_notification() {
+        let mdn = DispositionNotification::new("jane@example.com", DispositionType::Displayed)
+            .reporting_ua("mail.example.com; Example Mail 1.0")
+            .original_recipient("jane+lists@example.com")
+            .original_message_id("1234@example.com");
+
+        let mut part = Vec::new();
+        mdn.mime_part().write_part(&mut part).unwrap();
+        assert_eq!(
+            String::from_utf8(part).unwrap(),
+            concat!(
+                "Content-Type: message/disposition-notification\r\n",
+                "Content-Transfer-Encoding: 7bit\r\n\r\n",
+                "Reporting-UA: mail.example.com; Example Mail 1.0\r\n",
+                "Original-Recipient: rfc822; jane+lists@example.com\r\n",
+                "Final-Recipient: rfc822; jane@example.com\r\n",
+                "Original-Message-ID: <1234@example.com>\r\n",
+                "Disposition: manual-action/MDN-sent-manually; displayed\r\n",
+            )
+        );
+
+        assert_eq!(
+            Disposition::new(DispositionType::Deleted)
+                .action_mode(ActionMode::Automatic)
+                .sending_mode(SendingMode::Automatic)
+                .to_string(),
+            "automatic-action/MDN-sent-automatically; deleted"
+        );
+
+        let mut report = Vec::new();
+        DispositionNotification::new("jane@example.com", DispositionType::Processed)
+            .into_report("Your message was processed.", None)
+            .write_part(&mut report)
+            .unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(report.starts_with("Content-Type: multipart/report; report-type=\"disposition-notification\";"));
+        assert!(report.contains("Disposition: manual-action/MDN-sent-manually; processed\r\n"));
+        assert!(!report.contains("text/rfc822-headers"));
+    }
+}
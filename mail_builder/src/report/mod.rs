@@ -0,0 +1,156 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+//! multipart/report messages (_RFC 6522_): delivery status notifications
+//! (_RFC 3464_) and message disposition notifications (_RFC 8098_).
+//!
+//! ```rust
+//! use mail_builder::{
+//!     MessageBuilder,
+//!     report::{Action, DeliveryStatus, RecipientStatus, original_headers},
+//! };
+//!
+//! let original = b"From: john@example.com\r\nTo: jane@example.com\r\nSubject: Hi\r\n\r\nHello!\r\n";
+//!
+//! let bounce = MessageBuilder::new()
+//!     .from(("Mail Delivery System", "mailer-daemon@mx.example.com"))
+//!     .to("john@example.com")
+//!     .subject("Undelivered Mail Returned to Sender")
+//!     .body(
+//!         DeliveryStatus::new("mx.example.com")
+//!             .recipient(
+//!                 RecipientStatus::new("jane@example.com", Action::Failed, "5.1.1")
+//!                     .diagnostic_code("550 5.1.1 Mailbox does not exist"),
+//!             )
+//!             .into_report(
+//!                 "Your message could not be delivered to jane@example.com.",
+//!                 Some(original_headers(original)),
+//!             ),
+//!     )
+//!     .write_to_string()
+//!     .unwrap();
+//! ```
+
+pub mod delivery_status;
+pub mod disposition;
+
+use std::borrow::Cow;
+
+pub use self::{
+    delivery_status::{Action, DeliveryStatus, RecipientStatus},
+    disposition::{ActionMode, Disposition, DispositionNotification, DispositionType, SendingMode},
+};
+use crate::{
+    headers::{Header, HeaderType, content_type::ContentType},
+    mime::{BodyPart, MimePart},
+};
+
+/// Returns the header section of a message as a text/rfc822-headers part,
+/// to include in a report when returning the whole message is not needed.
+pub fn original_headers(message: &[u8]) -> MimePart<'static> {
+    let mut end = message.len();
+    for (pos, window) in message.windows(2).enumerate() {
+        if window == b"\n\n" || (window == b"\n\r" && message.get(pos + 2) == Some(&b'\n')) {
+            end = pos + 1;
+            break;
+        }
+    }
+
+    MimePart::new("text/rfc822-headers", BodyPart::Binary(message[..end].to_vec().into()))
+}
+
+/// Returns a whole message as a message/rfc822 part.
+pub fn original_message(message: &[u8]) -> MimePart<'_> {
+    MimePart::new("message/rfc822", BodyPart::Binary(message.into())).transfer_encoding(if message.is_ascii() {
+        "7bit"
+    } else {
+        "8bit"
+    })
+}
+
+// Builds the multipart/report: the human readable explanation, the
+// machine readable report and optionally the original message.
+pub(crate) fn report<'x>(
+    report_type: &'static str,
+    explanation: Cow<'x, str>,
+    report: MimePart<'x>,
+    original: Option<MimePart<'x>>,
+) -> MimePart<'x> {
+    let mut parts = vec![MimePart::new("text/plain", BodyPart::Text(explanation)), report];
+    parts.extend(original);
+    MimePart::new(
+        ContentType::new("multipart/report").attribute("report-type", report_type),
+        parts,
+    )
+}
+
+// Writes a message/* part made of header fields, which may not be encoded
+pub(crate) fn fields_part<'x>(content_type: &'static str, groups: &[Vec<(&str, HeaderType<'_>)>]) -> MimePart<'x> {
+    let mut body = Vec::new();
+    for (pos, fields) in groups.iter().enumerate() {
+        if pos > 0 {
+            body.extend_from_slice(b"\r\n");
+        }
+        for (name, value) in fields {
+            body.extend_from_slice(name.as_bytes());
+            body.extend_from_slice(b": ");
+            // Writing to a Vec can't fail
+            let _ = value.write_header(&mut body, name.len() + 2);
+        }
+    }
+
+    let encoding = if body.is_ascii() { "7bit" } else { "8bit" };
+    MimePart::new(content_type, BodyPart::Binary(body.into())).transfer_encoding(encoding)
+}
+
+// "rfc822; jane@example.com", or "utf-8; jöhn@example.com" (RFC 6533)
+pub(crate) fn address_field(address: &str) -> String {
+    format!("{}; {}", if address.is_ascii() { "rfc822" } else { "utf-8" }, address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{original_headers, original_message};
+
+    #[test]
+    fn original_parts() {
+        let message = b"From: john@example.com\r\nSubject: Hi\r\n\r\nHello!\r\n\r\nBye\r\n";
+
+        let mut part = Vec::new();
+        original_headers(message).write_part(&mut part).unwrap();
+        assert_eq!(
+            String::from_utf8(part).unwrap(),
+            concat!(
+                "Content-Type: text/rfc822-headers\r\n",
+                "Content-Transfer-Encoding: 7bit\r\n\r\n",
+                "From: john@example.com\r\n",
+                "Subject: Hi\r\n",
+            )
+        );
+
+        let mut part = Vec::new();
+        original_headers(b"Subject: Hi\n\nHello!\n")
+            .write_part(&mut part)
+            .unwrap();
+        assert!(String::from_utf8(part).unwrap().ends_with("\r\n\r\nSubject: Hi\r\n"));
+
+        let mut part = Vec::new();
+        original_message(message).write_part(&mut part).unwrap();
+        assert_eq!(
+            part,
+            [
+                b"Content-Type: message/rfc822\r\nContent-Transfer-Encoding: 7bit\r\n\r\n".as_ref(),
+                message.as_ref()
+            ]
+            .concat()
+        );
+    }
+}
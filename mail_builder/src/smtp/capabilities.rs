@@ -0,0 +1,117 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use super::Response;
+
+/// The extensions advertised in the reply to EHLO
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub pipelining: bool,
+    pub starttls: bool,
+    pub smtputf8: bool,
+    pub eight_bit_mime: bool,
+    pub dsn: bool,
+    /// The maximum message size in bytes, `Some(0)` if the server advertises
+    /// SIZE without a limit.
+    pub size: Option<usize>,
+    /// The SASL mechanisms, in uppercase
+    pub auth: Vec<String>,
+}
+
+impl Capabilities {
+    /// Parse the reply to EHLO. The first line, which holds the domain of
+    /// the server, is skipped.
+    pub fn parse(response: &Response) -> Self {
+        let mut capabilities = Capabilities::default();
+
+        for line in response.message.lines().skip(1) {
+            let mut words = line.split_ascii_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+
+            // Some servers advertise "AUTH=LOGIN PLAIN" for old clients
+            let (keyword, first_param) = keyword.split_once('=').unwrap_or((keyword, ""));
+
+            match keyword.to_ascii_uppercase().as_str() {
+                "PIPELINING" => capabilities.pipelining = true,
+                "STARTTLS" => capabilities.starttls = true,
+                "SMTPUTF8" => capabilities.smtputf8 = true,
+                "8BITMIME" => capabilities.eight_bit_mime = true,
+                "DSN" => capabilities.dsn = true,
+                "SIZE" => capabilities.size = Some(words.next().and_then(|size| size.parse().ok()).unwrap_or(0)),
+                "AUTH" => {
+                    for mechanism in std::iter::once(first_param).chain(words) {
+                        let mechanism = mechanism.to_ascii_uppercase();
+                        if !mechanism.is_empty() && !capabilities.auth.contains(&mechanism) {
+                            capabilities.auth.push(mechanism);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        capabilities
+    }
+
+    pub fn supports_auth(&self, mechanism: &str) -> bool {
+        self.auth.iter().any(|item| item.eq_ignore_ascii_case(mechanism))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Capabilities;
+    use crate::smtp::Response;
+
+    #[test]
+    fn parse_ehlo() {
+        let capabilities = Capabilities::parse(&Response {
+            code: 250,
+            message: concat!(
+                "mx.example.com Hello [127.0.0.1]\n",
+                "PIPELINING\n",
+                "SIZE 35882577\n",
+                "8BITMIME\n",
+                "AUTH LOGIN plain XOAUTH2\n",
+                "AUTH=LOGIN\n",
+                "ENHANCEDSTATUSCODES\n",
+                "dsn\n",
+                "SMTPUTF8"
+            )
+            .into(),
+        });
+
+        assert_eq!(
+            capabilities,
+            Capabilities {
+                pipelining: true,
+                starttls: false,
+                smtputf8: true,
+                eight_bit_mime: true,
+                dsn: true,
+                size: Some(35882577),
+                auth: vec!["LOGIN".into(), "PLAIN".into(), "XOAUTH2".into()],
+            }
+        );
+        assert!(capabilities.supports_auth("plain"));
+        assert!(!capabilities.supports_auth("CRAM-MD5"));
+
+        let capabilities = Capabilities::parse(&Response {
+            code: 250,
+            message: "localhost\nSTARTTLS\nSIZE".into(),
+        });
+        assert!(capabilities.starttls);
+        assert_eq!(capabilities.size, Some(0));
+        assert!(capabilities.auth.is_empty());
+    }
+}
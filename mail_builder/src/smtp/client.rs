@@ -0,0 +1,1202 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::{
+    borrow::Cow,
+    io::{self, Read, Write},
+    mem,
+    sync::Arc,
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    sync::mpsc,
+    time::timeout,
+};
+use tokio_rustls::{
+    TlsConnector,
+    client::TlsStream,
+    rustls::{ClientConfig, RootCertStore, crypto::ring, pki_types::ServerName},
+};
+
+use super::{Capabilities, Credentials, Envelope, Error, Response, Tls, envelope::check_address};
+use crate::{
+    MessageBuilder,
+    encoders::base64::base64_encode,
+    mime::{Stream as BodyStream, StreamEncoding},
+};
+
+// Replies longer than this are rejected
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+// The message is written to the connection in chunks of this size
+const CHUNK_SIZE: usize = 64 * 1024;
+// The number of chunks of a streamed part read ahead of the connection
+const STREAM_CHUNKS: usize = 4;
+
+/// Connects and authenticates to an SMTP server.
+#[derive(Debug, Clone)]
+pub struct SmtpClientBuilder {
+    hostname: String,
+    port: u16,
+    tls: Tls,
+    tls_config: Option<Arc<ClientConfig>>,
+    credentials: Option<Credentials>,
+    helo_host: String,
+    timeout: Duration,
+}
+
+impl SmtpClientBuilder {
+    /// Create a builder connecting to `hostname` with implicit TLS, which is
+    /// also the name the certificate of the server is checked against.
+    pub fn new(hostname: impl Into<String>, port: u16) -> Self {
+        SmtpClientBuilder {
+            hostname: hostname.into(),
+            port,
+            tls: Tls::default(),
+            tls_config: None,
+            credentials: None,
+            helo_host: "localhost".into(),
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Set how the connection is secured.
+    pub fn tls(mut self, tls: Tls) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Set the TLS configuration. By default, certificates are checked
+    /// against the Mozilla root certificates.
+    pub fn tls_config(mut self, config: Arc<ClientConfig>) -> Self {
+        self.tls_config = Some(config);
+        self
+    }
+
+    /// Authenticate after connecting.
+    pub fn credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Set the domain sent with EHLO, "localhost" by default.
+    pub fn helo_host(mut self, host: impl Into<String>) -> Self {
+        self.helo_host = host.into();
+        self
+    }
+
+    /// Set the timeout of the connection and of each reply, 60 seconds by
+    /// default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Connect to the server, greet it, negotiate TLS and authenticate.
+    pub async fn connect(&self) -> Result<SmtpClient, Error> {
+        let stream = timeout(self.timeout, TcpStream::connect((self.hostname.as_str(), self.port)))
+            .await
+            .map_err(|_| Error::Timeout)??;
+        let stream = if self.tls == Tls::Implicit {
+            Stream::Tls(Box::new(self.tls_handshake(stream).await?))
+        } else {
+            Stream::Plain(stream)
+        };
+
+        let mut client = SmtpClient {
+            stream,
+            buffer: Vec::new(),
+            timeout: self.timeout,
+            capabilities: Capabilities::default(),
+        };
+
+        let greeting = client.read_response().await?;
+        if greeting.code != 220 {
+            return Err(Error::UnexpectedResponse(greeting));
+        }
+        client.ehlo(&self.helo_host).await?;
+
+        if self.tls == Tls::StartTls {
+            if !client.capabilities.starttls {
+                return Err(Error::StartTlsUnavailable);
+            }
+            let response = client.command("STARTTLS\r\n").await?;
+            if response.code != 220 {
+                return Err(Error::UnexpectedResponse(response));
+            }
+
+            client.stream = match client.stream {
+                Stream::Plain(stream) => Stream::Tls(Box::new(self.tls_handshake(stream).await?)),
+                stream => stream,
+            };
+            // Anything received before the handshake can't be trusted
+            client.buffer.clear();
+            client.ehlo(&self.helo_host).await?;
+        }
+
+        if let Some(credentials) = &self.credentials {
+            client.authenticate(credentials).await?;
+        }
+
+        Ok(client)
+    }
+
+    async fn tls_handshake(&self, stream: TcpStream) -> Result<TlsStream<TcpStream>, Error> {
+        let config = match &self.tls_config {
+            Some(config) => config.clone(),
+            None => Arc::new(default_tls_config()?),
+        };
+        let server_name = ServerName::try_from(self.hostname.clone()).map_err(|err| Error::Tls(err.to_string()))?;
+
+        timeout(self.timeout, TlsConnector::from(config).connect(server_name, stream))
+            .await
+            .map_err(|_| Error::Timeout)?
+            .map_err(|err| Error::Tls(err.to_string()))
+    }
+}
+
+fn default_tls_config() -> Result<ClientConfig, Error> {
+    let roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+    Ok(ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|err| Error::Tls(err.to_string()))?
+        .with_root_certificates(roots)
+        .with_no_client_auth())
+}
+
+enum Stream {
+    Plain(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl Stream {
+    async fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Plain(stream) => stream.read(buf).await,
+            Stream::Tls(stream) => stream.read(buf).await,
+        }
+    }
+
+    async fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            Stream::Plain(stream) => {
+                stream.write_all(buf).await?;
+                stream.flush().await
+            }
+            Stream::Tls(stream) => {
+                stream.write_all(buf).await?;
+                stream.flush().await
+            }
+        }
+    }
+}
+
+/// The reply of the server to a recipient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecipientResult {
+    pub address: String,
+    pub response: Response,
+}
+
+impl RecipientResult {
+    pub fn is_accepted(&self) -> bool {
+        self.response.is_positive_completion()
+    }
+}
+
+/// The outcome of a sent message: the reply to each recipient, and the
+/// final reply of the server, once it accepted the message for the
+/// recipients that were not rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendResult {
+    pub recipients: Vec<RecipientResult>,
+    pub response: Response,
+}
+
+impl SendResult {
+    pub fn rejected(&self) -> impl Iterator<Item = &RecipientResult> {
+        self.recipients.iter().filter(|recipient| !recipient.is_accepted())
+    }
+}
+
+/// A connection to an SMTP server, created by `SmtpClientBuilder`. Several
+/// messages can be sent with the same connection.
+pub struct SmtpClient {
+    stream: Stream,
+    buffer: Vec<u8>,
+    timeout: Duration,
+    capabilities: Capabilities,
+}
+
+impl SmtpClient {
+    /// The extensions advertised by the server
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    /// Send EHLO and store the capabilities of the server, falling back
+    /// to HELO for servers that don't support ESMTP.
+    pub async fn ehlo(&mut self, host: &str) -> Result<(), Error> {
+        check_address(host)?;
+        let response = self.command(&format!("EHLO {}\r\n", host)).await?;
+        if response.code == 250 {
+            self.capabilities = Capabilities::parse(&response);
+            return Ok(());
+        } else if !matches!(response.code, 500 | 502) {
+            return Err(Error::UnexpectedResponse(response));
+        }
+
+        let response = self.command(&format!("HELO {}\r\n", host)).await?;
+        if response.code != 250 {
+            return Err(Error::UnexpectedResponse(response));
+        }
+        self.capabilities = Capabilities::default();
+        Ok(())
+    }
+
+    /// Authenticate with the first mechanism supported by both the server
+    /// and the credentials.
+    pub async fn authenticate(&mut self, credentials: &Credentials) -> Result<(), Error> {
+        let response = match credentials {
+            Credentials::Password {
+                username,
+                password,
+            } if self.capabilities.supports_auth("PLAIN") => {
+                let token = base64(format!("\0{}\0{}", username, password).as_bytes())?;
+                self.command(&format!("AUTH PLAIN {}\r\n", token)).await?
+            }
+            Credentials::Password {
+                username,
+                password,
+            } if self.capabilities.supports_auth("LOGIN") => {
+                let response = self.command("AUTH LOGIN\r\n").await?;
+                if response.code != 334 {
+                    return Err(Error::AuthenticationFailed(response));
+                }
+                let response = self.command(&format!("{}\r\n", base64(username.as_bytes())?)).await?;
+                if response.code != 334 {
+                    return Err(Error::AuthenticationFailed(response));
+                }
+                self.command(&format!("{}\r\n", base64(password.as_bytes())?)).await?
+            }
+            Credentials::XOAuth2 {
+                username,
+                access_token,
+            } if self.capabilities.supports_auth("XOAUTH2") => {
+                let token = base64(format!("user={}\x01auth=Bearer {}\x01\x01", username, access_token).as_bytes())?;
+                let response = self.command(&format!("AUTH XOAUTH2 {}\r\n", token)).await?;
+                if response.code == 334 {
+                    // The challenge holds the details of the error, the
+                    // server replies with the final status to an empty line
+                    self.command("\r\n").await?
+                } else {
+                    response
+                }
+            }
+            _ => return Err(Error::AuthenticationUnavailable),
+        };
+
+        if response.code == 235 {
+            Ok(())
+        } else {
+            Err(Error::AuthenticationFailed(response))
+        }
+    }
+
+    /// Send a message to the addresses of its To, Cc and Bcc headers. The
+    /// Bcc headers are removed from the sent message.
+    ///
+    /// The message is written to the connection as it is built. Streamed
+    /// parts are opened before the transaction starts, then read and
+    /// encoded while they are sent, so they are never held in memory. The
+    /// SIZE parameter is an estimate when streamed parts have no known
+    /// size. If a streamed part fails to be read while it is sent, the
+    /// connection is left in the middle of the message and can't be used
+    /// anymore.
+    pub async fn send_message(&mut self, mut message: MessageBuilder<'_>) -> Result<SendResult, Error> {
+        let envelope = Envelope::from_message(&message);
+        if envelope.mail_from.is_empty() {
+            return Err(Error::MissingSender);
+        }
+
+        message.headers.retain(|(name, _)| !name.eq_ignore_ascii_case("Bcc"));
+        let mut body = Body::default();
+        message.write_to_with(&mut body, &mut |body, stream, encoding| body.add_stream(stream, encoding))?;
+        self.send_body(&envelope, body).await
+    }
+
+    /// Send a raw message to the recipients of the envelope. The commands
+    /// are pipelined if the server supports it. An error is returned if
+    /// all the recipients are rejected, otherwise the message is sent to
+    /// the accepted ones and `SendResult` tells which were rejected.
+    pub async fn send(&mut self, envelope: &Envelope, message: &[u8]) -> Result<SendResult, Error> {
+        let body = Body {
+            size: message.len(),
+            is_8bit: !message.is_ascii(),
+            segments: vec![Segment::Data(Cow::Borrowed(message))],
+        };
+        self.send_body(envelope, body).await
+    }
+
+    async fn send_body(&mut self, envelope: &Envelope, body: Body<'_>) -> Result<SendResult, Error> {
+        let size = body.size;
+        if envelope.recipients.is_empty() {
+            return Err(Error::NoRecipients);
+        }
+
+        let is_utf8 = envelope.is_utf8();
+        if is_utf8 && !self.capabilities.smtputf8 {
+            return Err(Error::SmtpUtf8Unavailable);
+        }
+        if let Some(max_size) = self.capabilities.size
+            && max_size > 0
+            && size > max_size
+        {
+            return Err(Error::MessageTooLarge {
+                size,
+                max_size,
+            });
+        }
+
+        let mut params = Vec::new();
+        if self.capabilities.size.is_some() {
+            params.push(format!("SIZE={}", size));
+        }
+        if self.capabilities.eight_bit_mime && body.is_8bit {
+            params.push("BODY=8BITMIME".to_string());
+        }
+        if is_utf8 {
+            params.push("SMTPUTF8".to_string());
+        }
+
+        // All the commands are built before sending any, so that an
+        // invalid address doesn't leave a transaction open
+        let dsn = self.capabilities.dsn;
+        let mut mail_from = String::new();
+        envelope.write_mail_from(&mut mail_from, &params, dsn)?;
+        let mut rcpt_to = Vec::with_capacity(envelope.recipients.len());
+        for recipient in &envelope.recipients {
+            let mut command = String::new();
+            recipient.write_rcpt_to(&mut command, dsn)?;
+            rcpt_to.push(command);
+        }
+        let mut recipients = Vec::with_capacity(envelope.recipients.len());
+
+        if self.capabilities.pipelining {
+            let mut commands = mail_from;
+            for command in &rcpt_to {
+                commands.push_str(command);
+            }
+            commands.push_str("DATA\r\n");
+            self.write(commands.as_bytes()).await?;
+
+            let mail_from = self.read_response().await?;
+            for recipient in &envelope.recipients {
+                recipients.push(RecipientResult {
+                    address: recipient.address.clone(),
+                    response: self.read_response().await?,
+                });
+            }
+            let data = self.read_response().await?;
+
+            if !mail_from.is_positive_completion() {
+                self.abort(data.code == 354).await?;
+                return Err(Error::UnexpectedResponse(mail_from));
+            } else if !recipients.iter().any(RecipientResult::is_accepted) {
+                self.abort(data.code == 354).await?;
+                return Err(Error::RecipientsRejected(recipients));
+            } else if data.code != 354 {
+                self.abort(false).await?;
+                return Err(Error::UnexpectedResponse(data));
+            }
+        } else {
+            let mail_from = self.command(&mail_from).await?;
+            if !mail_from.is_positive_completion() {
+                self.abort(false).await?;
+                return Err(Error::UnexpectedResponse(mail_from));
+            }
+
+            for (recipient, command) in envelope.recipients.iter().zip(&rcpt_to) {
+                recipients.push(RecipientResult {
+                    address: recipient.address.clone(),
+                    response: self.command(command).await?,
+                });
+            }
+            if !recipients.iter().any(RecipientResult::is_accepted) {
+                self.abort(false).await?;
+                return Err(Error::RecipientsRejected(recipients));
+            }
+
+            let data = self.command("DATA\r\n").await?;
+            if data.code != 354 {
+                self.abort(false).await?;
+                return Err(Error::UnexpectedResponse(data));
+            }
+        }
+
+        self.write_body(body).await?;
+        let response = self.read_response().await?;
+        if response.is_positive_completion() {
+            Ok(SendResult {
+                recipients,
+                response,
+            })
+        } else {
+            Err(Error::UnexpectedResponse(response))
+        }
+    }
+
+    // Writes the body of the message after the 354 reply, dot-stuffed and
+    // followed by the end of data marker.
+    async fn write_body(&mut self, body: Body<'_>) -> Result<(), Error> {
+        let mut data = DotStuffer::new(Vec::with_capacity(CHUNK_SIZE + CHUNK_SIZE / 8));
+        for segment in body.segments {
+            match segment {
+                Segment::Data(bytes) => {
+                    for chunk in bytes.chunks(CHUNK_SIZE) {
+                        data.write_all(chunk)?;
+                        self.write(&data.inner).await?;
+                        data.inner.clear();
+                    }
+                }
+                Segment::Stream(input, encoding) => {
+                    // The part is read and encoded by a blocking task, which
+                    // waits while the chunks already read are being sent
+                    let (sender, mut receiver) = mpsc::channel(STREAM_CHUNKS);
+                    let task = tokio::task::spawn_blocking(move || {
+                        let mut output = ChunkSender {
+                            sender,
+                            buffer: Vec::with_capacity(CHUNK_SIZE),
+                        };
+                        encoding.encode(input, &mut output)?;
+                        output.flush()
+                    });
+                    while let Some(chunk) = receiver.recv().await {
+                        data.write_all(&chunk)?;
+                        self.write(&data.inner).await?;
+                        data.inner.clear();
+                    }
+                    task.await.map_err(io::Error::other)??;
+                }
+            }
+        }
+
+        let (_, data) = data.finish()?;
+        self.write(&data).await
+    }
+
+    /// Reset the current transaction.
+    pub async fn rset(&mut self) -> Result<(), Error> {
+        let response = self.command("RSET\r\n").await?;
+        if response.is_positive_completion() {
+            Ok(())
+        } else {
+            Err(Error::UnexpectedResponse(response))
+        }
+    }
+
+    /// Check that the connection is still usable.
+    pub async fn noop(&mut self) -> Result<(), Error> {
+        let response = self.command("NOOP\r\n").await?;
+        if response.is_positive_completion() {
+            Ok(())
+        } else {
+            Err(Error::UnexpectedResponse(response))
+        }
+    }
+
+    /// Close the connection.
+    pub async fn quit(mut self) -> Result<(), Error> {
+        let response = self.command("QUIT\r\n").await?;
+        if response.code == 221 {
+            Ok(())
+        } else {
+            Err(Error::UnexpectedResponse(response))
+        }
+    }
+
+    // Ends a failed transaction. With pipelining, the server may have
+    // accepted DATA even though no recipient was accepted, in which case
+    // an empty message is sent to get out of the data state.
+    async fn abort(&mut self, in_data: bool) -> Result<(), Error> {
+        if in_data {
+            self.write(b".\r\n").await?;
+            self.read_response().await?;
+        }
+        self.rset().await
+    }
+
+    async fn command(&mut self, command: &str) -> Result<Response, Error> {
+        self.write(command.as_bytes()).await?;
+        self.read_response().await
+    }
+
+    async fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        timeout(self.timeout, self.stream.write_all(bytes))
+            .await
+            .map_err(|_| Error::Timeout)?
+            .map_err(Error::Io)
+    }
+
+    async fn read_response(&mut self) -> Result<Response, Error> {
+        let mut buf = [0u8; 1024];
+
+        loop {
+            if let Some((response, len)) = parse_response(&self.buffer)? {
+                self.buffer.drain(..len);
+                return Ok(response);
+            } else if self.buffer.len() > MAX_RESPONSE_SIZE {
+                return Err(Error::InvalidResponse);
+            }
+
+            let len = timeout(self.timeout, self.stream.read(&mut buf))
+                .await
+                .map_err(|_| Error::Timeout)??;
+            if len == 0 {
+                return Err(Error::Io(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "connection closed by the server",
+                )));
+            }
+            self.buffer.extend_from_slice(&buf[..len]);
+        }
+    }
+}
+
+fn base64(value: &[u8]) -> Result<String, Error> {
+    Ok(String::from_utf8_lossy(&base64_encode(value)?).into_owned())
+}
+
+// Parses a reply, returning it with the number of bytes it used, or None
+// if the last line has not been received yet.
+fn parse_response(buffer: &[u8]) -> Result<Option<(Response, usize)>, Error> {
+    let mut pos = 0;
+    let mut message = String::new();
+
+    while let Some(end) = buffer[pos..].iter().position(|&ch| ch == b'\n') {
+        let line = &buffer[pos..pos + end];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let is_first = pos == 0;
+        pos += end + 1;
+
+        let code = line
+            .get(..3)
+            .filter(|code| code.iter().all(u8::is_ascii_digit))
+            .and_then(|code| std::str::from_utf8(code).ok()?.parse::<u16>().ok())
+            .ok_or(Error::InvalidResponse)?;
+        let is_last = match line.get(3) {
+            None | Some(b' ') => true,
+            Some(b'-') => false,
+            _ => return Err(Error::InvalidResponse),
+        };
+
+        if !is_first {
+            message.push('\n');
+        }
+        message.push_str(&String::from_utf8_lossy(line.get(4..).unwrap_or_default()));
+
+        if is_last {
+            return Ok(Some((
+                Response {
+                    code,
+                    message,
+                },
+                pos,
+            )));
+        }
+    }
+
+    Ok(None)
+}
+
+// A message to send. The parts held in memory are written to `segments`
+// when the message is built, while streamed parts are only opened, to be
+// read as the message is sent.
+#[derive(Default)]
+struct Body<'x> {
+    segments: Vec<Segment<'x>>,
+    // The estimated size of the message
+    size: usize,
+    is_8bit: bool,
+}
+
+enum Segment<'x> {
+    Data(Cow<'x, [u8]>),
+    Stream(Box<dyn Read + Send>, StreamEncoding),
+}
+
+impl Body<'_> {
+    fn add_stream(&mut self, stream: BodyStream, encoding: StreamEncoding) -> io::Result<()> {
+        self.size += encoding.encoded_size(stream.content_size());
+        // Raw parts may have an 8bit Content-Transfer-Encoding
+        self.is_8bit |= encoding == StreamEncoding::Raw;
+        self.segments.push(Segment::Stream(stream.open()?, encoding));
+        Ok(())
+    }
+}
+
+impl Write for Body<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.size += buf.len();
+        self.is_8bit |= !buf.is_ascii();
+        match self.segments.last_mut() {
+            Some(Segment::Data(data)) => data.to_mut().extend_from_slice(buf),
+            _ => self.segments.push(Segment::Data(buf.to_vec().into())),
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Sends what is written in chunks of `CHUNK_SIZE`, blocking while the
+// channel is full.
+struct ChunkSender {
+    sender: mpsc::Sender<Vec<u8>>,
+    buffer: Vec<u8>,
+}
+
+impl Write for ChunkSender {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            let chunk = mem::replace(&mut self.buffer, Vec::with_capacity(CHUNK_SIZE));
+            self.sender
+                .blocking_send(chunk)
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "the message is not being sent anymore"))?;
+        }
+        Ok(())
+    }
+}
+
+// Converts bare LFs to CRLF and escapes lines starting with a dot while
+// the message is written. `finish` appends the end of data marker.
+struct DotStuffer<W: Write> {
+    inner: W,
+    last_ch: u8,
+    size: usize,
+}
+
+impl<W: Write> DotStuffer<W> {
+    fn new(inner: W) -> Self {
+        DotStuffer {
+            inner,
+            last_ch: b'\n',
+            size: 0,
+        }
+    }
+
+    // Returns the size of the message before stuffing, and the writer.
+    fn finish(mut self) -> io::Result<(usize, W)> {
+        // A LF is always written after a CR
+        if self.size > 0 && self.last_ch != b'\n' {
+            self.inner.write_all(b"\r\n")?;
+        }
+        self.inner.write_all(b".\r\n")?;
+        Ok((self.size, self.inner))
+    }
+}
+
+impl<W: Write> Write for DotStuffer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut start = 0;
+        for (pos, &ch) in buf.iter().enumerate() {
+            let insert = if ch == b'.' && self.last_ch == b'\n' {
+                Some(b'.')
+            } else if ch == b'\n' && self.last_ch != b'\r' {
+                Some(b'\r')
+            } else {
+                None
+            };
+            if let Some(insert) = insert {
+                self.inner.write_all(&buf[start..pos])?;
+                self.inner.write_all(&[insert])?;
+                start = pos;
+            }
+            self.last_ch = ch;
+        }
+        self.inner.write_all(&buf[start..])?;
+        self.size += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Read, Write},
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::{Duration, Instant},
+    };
+
+    use super::{DotStuffer, SmtpClientBuilder, parse_response};
+    use crate::{
+        MessageBuilder,
+        decoders::base64::base64_decode,
+        mime::Stream,
+        smtp::{
+            Credentials, Envelope, Error, Notify, Recipient, Response, Ret, Tls,
+            stub::{self, ACCESS_TOKEN, PASSWORD, StubConfig, USERNAME},
+        },
+    };
+
+    #[test]
+    fn parse_responses() {
+        assert_eq!(parse_response(b"250-mx.example.com\r\n250-PIPE").unwrap(), None);
+        assert_eq!(
+            parse_response(b"250-mx.example.com\r\n250-PIPELINING\r\n250 SIZE 100\r\n221 Bye\r\n").unwrap(),
+            Some((
+                Response {
+                    code: 250,
+                    message: "mx.example.com\nPIPELINING\nSIZE 100".into()
+                },
+                50
+            ))
+        );
+        assert_eq!(
+            parse_response(b"354\n").unwrap(),
+            Some((
+                Response {
+                    code: 354,
+                    message: "".into()
+                },
+                4
+            ))
+        );
+        assert!(parse_response(b"25O OK\r\n").is_err());
+        assert!(parse_response(b"250+OK\r\n").is_err());
+
+        let response = parse_response(b"550 5.1.1 Mailbox does not exist\r\n")
+            .unwrap()
+            .unwrap()
+            .0;
+        assert_eq!(response.enhanced_status(), Some("5.1.1"));
+        assert!(response.is_permanent_failure());
+        assert_eq!(parse_response(b"250 OK\r\n").unwrap().unwrap().0.enhanced_status(), None);
+    }
+
+    fn dot_stuff(message: &[u8], chunk_size: usize) -> Vec<u8> {
+        let mut data = DotStuffer::new(Vec::new());
+        for chunk in message.chunks(chunk_size) {
+            data.write_all(chunk).unwrap();
+        }
+        let (size, data) = data.finish().unwrap();
+        assert_eq!(size, message.len());
+        data
+    }
+
+    #[test]
+    fn dot_stuffing() {
+        for chunk_size in [1, 2, 1024] {
+            assert_eq!(
+                dot_stuff(b".\r\nHi\r\n..\r\n.a.b", chunk_size),
+                b"..\r\nHi\r\n...\r\n..a.b\r\n.\r\n"
+            );
+            assert_eq!(dot_stuff(b"Hi\n.\nthere\n", chunk_size), b"Hi\r\n..\r\nthere\r\n.\r\n");
+            assert_eq!(dot_stuff(b"", chunk_size), b".\r\n");
+        }
+    }
+
+    #[tokio::test]
+    async fn send_pipelined() {
+        let (port, server) = stub::start(StubConfig {
+            extensions: vec!["PIPELINING", "SIZE 1000000", "8BITMIME", "AUTH PLAIN LOGIN", "DSN"],
+            rejected: vec!["jim@example.com"],
+            ..Default::default()
+        })
+        .await;
+
+        let mut client = SmtpClientBuilder::new("127.0.0.1", port)
+            .tls(Tls::None)
+            .credentials(Credentials::new(USERNAME, PASSWORD))
+            .connect()
+            .await
+            .unwrap();
+        assert!(client.capabilities().pipelining);
+
+        let result = client
+            .send_message(
+                MessageBuilder::new()
+                    .from(("John Doe", "john@example.com"))
+                    .to(vec![("Jane Doe", "jane@example.com"), ("Jim", "jim@example.com")])
+                    .bcc("archive@example.com")
+                    .subject("Hello")
+                    .text_body("Hi there,\n.\nBye\n"),
+            )
+            .await
+            .unwrap();
+        client.quit().await.unwrap();
+
+        assert_eq!(
+            result
+                .recipients
+                .iter()
+                .map(|recipient| (recipient.address.as_str(), recipient.response.code))
+                .collect::<Vec<_>>(),
+            vec![
+                ("jane@example.com", 250),
+                ("jim@example.com", 550),
+                ("archive@example.com", 250)
+            ]
+        );
+        assert_eq!(
+            result
+                .rejected()
+                .map(|recipient| recipient.address.as_str())
+                .collect::<Vec<_>>(),
+            vec!["jim@example.com"]
+        );
+        assert_eq!(result.response.code, 250);
+
+        let transcript = server.await.unwrap();
+        assert!(!transcript.secured);
+        let auth = transcript.command("AUTH PLAIN ").unwrap();
+        assert_eq!(auth, "AUTH PLAIN AGpvaG4Ac2VjcmV0");
+
+        let mail_from = transcript
+            .commands
+            .iter()
+            .position(|command| command.starts_with("MAIL FROM:"))
+            .unwrap();
+        assert!(transcript.commands[mail_from].starts_with("MAIL FROM:<john@example.com> SIZE="));
+        assert!(!transcript.commands[mail_from].contains("BODY=8BITMIME"));
+        assert_eq!(
+            &transcript.commands[mail_from + 1..mail_from + 5],
+            &[
+                "RCPT TO:<jane@example.com>",
+                "RCPT TO:<jim@example.com>",
+                "RCPT TO:<archive@example.com>",
+                "DATA"
+            ]
+        );
+        assert!(transcript.pipelined[mail_from]);
+
+        let message = String::from_utf8(transcript.messages[0].clone()).unwrap();
+        assert!(message.contains("To: \"Jane Doe\" <jane@example.com>, \"Jim\" <jim@example.com>\r\n"));
+        assert!(!message.contains("archive@example.com"));
+        assert!(message.contains("\r\nHi there,\r\n..\r\nBye\r\n"));
+    }
+
+    // Generates `size` bytes, pausing halfway until the server received
+    // part of the message, which fails if the message is buffered before
+    // being sent.
+    struct SlowReader {
+        size: usize,
+        read: usize,
+        received: Arc<AtomicUsize>,
+    }
+
+    impl Read for SlowReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.read >= self.size / 2 {
+                let start = Instant::now();
+                while self.received.load(Ordering::Relaxed) < self.size / 4 {
+                    if start.elapsed() > Duration::from_secs(10) {
+                        return Err(io::Error::other("the message is not sent while it is read"));
+                    }
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+            }
+            let len = buf.len().min(self.size - self.read);
+            buf[..len].fill(b'a');
+            self.read += len;
+            Ok(len)
+        }
+    }
+
+    #[tokio::test]
+    async fn send_streamed_message() {
+        let received = Arc::new(AtomicUsize::new(0));
+        let (port, server) = stub::start(StubConfig {
+            extensions: vec!["SIZE 100000000", "8BITMIME"],
+            received: received.clone(),
+            ..Default::default()
+        })
+        .await;
+        let mut client = SmtpClientBuilder::new("127.0.0.1", port)
+            .tls(Tls::None)
+            .connect()
+            .await
+            .unwrap();
+
+        let size = 4 * 1024 * 1024;
+        let reader = SlowReader {
+            size,
+            read: 0,
+            received,
+        };
+        let result = client
+            .send_message(
+                MessageBuilder::new()
+                    .from("john@example.com")
+                    .to("jane@example.com")
+                    .text_body("See the attachment.")
+                    .attachment(
+                        "application/octet-stream",
+                        "data.bin",
+                        Stream::from_reader(reader).size(size as u64),
+                    ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.response.code, 250);
+        client.quit().await.unwrap();
+
+        let transcript = server.await.unwrap();
+        let mail_from = transcript.command("MAIL FROM:").unwrap();
+        let estimated_size: usize = mail_from.split_once("SIZE=").unwrap().1.parse().unwrap();
+        assert!(estimated_size > size * 4 / 3);
+        assert!(!mail_from.contains("BODY=8BITMIME"));
+
+        let message = String::from_utf8(transcript.messages[0].clone()).unwrap();
+        let (_, attachment) = message.split_once("filename=\"data.bin\"").unwrap();
+        let (_, encoded) = attachment.split_once("\r\n\r\n").unwrap();
+        let encoded: String = encoded.lines().take_while(|line| !line.starts_with("--")).collect();
+        assert_eq!(base64_decode(encoded.as_bytes()).unwrap(), vec![b'a'; size]);
+    }
+
+    #[tokio::test]
+    async fn send_starttls() {
+        let (server_config, client_config) = stub::tls_configs();
+        let (port, server) = stub::start(StubConfig {
+            extensions: vec!["AUTH LOGIN", "DSN", "SMTPUTF8", "8BITMIME"],
+            tls: Some(server_config),
+            ..Default::default()
+        })
+        .await;
+
+        let mut client = SmtpClientBuilder::new("localhost", port)
+            .tls(Tls::StartTls)
+            .tls_config(client_config)
+            .helo_host("client.example.com")
+            .credentials(Credentials::new(USERNAME, PASSWORD))
+            .connect()
+            .await
+            .unwrap();
+        assert!(!client.capabilities().starttls);
+        assert!(client.capabilities().dsn);
+
+        let envelope = Envelope::new("john@example.com")
+            .ret(Ret::Headers)
+            .envelope_id("id+1")
+            .recipient(
+                Recipient::new("jöhn@example.com")
+                    .notify(Notify::Failure)
+                    .notify(Notify::Delay),
+            )
+            .recipient(Recipient::new("jane@example.com").orcpt("jane@example.org"));
+        let result = client
+            .send(&envelope, "Subject: Olá\r\n\r\nOlá!\r\n".as_bytes())
+            .await
+            .unwrap();
+        assert!(result.rejected().next().is_none());
+
+        client
+            .send(
+                &Envelope::new("").recipient("postmaster@example.com"),
+                b"Subject: Bounce\r\n\r\nBye",
+            )
+            .await
+            .unwrap();
+        client.quit().await.unwrap();
+
+        let transcript = server.await.unwrap();
+        assert!(transcript.secured);
+        assert_eq!(
+            transcript.commands[..4],
+            [
+                "EHLO client.example.com",
+                "STARTTLS",
+                "EHLO client.example.com",
+                "AUTH LOGIN"
+            ]
+        );
+        assert_eq!(
+            transcript.commands[4..],
+            [
+                "MAIL FROM:<john@example.com> BODY=8BITMIME SMTPUTF8 RET=HDRS ENVID=id+2B1",
+                "RCPT TO:<jöhn@example.com> NOTIFY=FAILURE,DELAY",
+                "RCPT TO:<jane@example.com> ORCPT=rfc822;jane@example.org",
+                "DATA",
+                "MAIL FROM:<>",
+                "RCPT TO:<postmaster@example.com>",
+                "DATA",
+                "QUIT"
+            ]
+        );
+        assert!(transcript.pipelined.iter().all(|pipelined| !pipelined));
+        assert_eq!(
+            transcript.messages,
+            vec![
+                "Subject: Olá\r\n\r\nOlá!\r\n".as_bytes().to_vec(),
+                b"Subject: Bounce\r\n\r\nBye\r\n".to_vec()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn implicit_tls_xoauth2() {
+        for (access_token, success) in [(ACCESS_TOKEN, true), ("expired", false)] {
+            let (server_config, client_config) = stub::tls_configs();
+            let (port, server) = stub::start(StubConfig {
+                extensions: vec!["AUTH PLAIN XOAUTH2"],
+                tls: Some(server_config),
+                implicit_tls: true,
+                ..Default::default()
+            })
+            .await;
+
+            let result = SmtpClientBuilder::new("localhost", port)
+                .tls_config(client_config)
+                .credentials(Credentials::xoauth2(USERNAME, access_token))
+                .connect()
+                .await;
+
+            if success {
+                result.unwrap().quit().await.unwrap();
+            } else {
+                match result {
+                    Err(Error::AuthenticationFailed(response)) => assert_eq!(response.code, 535),
+                    _ => panic!("Expected an authentication failure"),
+                }
+            }
+
+            let transcript = server.await.unwrap();
+            assert!(transcript.secured);
+            assert!(transcript.command("AUTH XOAUTH2 ").is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn send_errors() {
+        let (port, server) = stub::start(StubConfig {
+            extensions: vec!["PIPELINING", "SIZE 100"],
+            rejected: vec!["jim@example.com"],
+            ..Default::default()
+        })
+        .await;
+        let mut client = SmtpClientBuilder::new("127.0.0.1", port)
+            .tls(Tls::None)
+            .connect()
+            .await
+            .unwrap();
+
+        let envelope = Envelope::new("john@example.com").recipient("jim@example.com");
+        match client.send(&envelope, b"Subject: Hi\r\n\r\nHi\r\n").await {
+            Err(Error::RecipientsRejected(recipients)) => {
+                assert_eq!(recipients.len(), 1);
+                assert_eq!(recipients[0].response.enhanced_status(), Some("5.1.1"));
+            }
+            result => panic!("Unexpected result {:?}", result),
+        }
+        assert!(matches!(
+            client.send(&envelope, &[b'a'; 200]).await,
+            Err(Error::MessageTooLarge {
+                size: 200,
+                max_size: 100
+            })
+        ));
+        assert!(matches!(
+            client
+                .send(&Envelope::new("jöhn@example.com").recipient("jane@example.com"), b"Hi")
+                .await,
+            Err(Error::SmtpUtf8Unavailable)
+        ));
+        assert!(matches!(
+            client.send(&Envelope::new("john@example.com"), b"Hi").await,
+            Err(Error::NoRecipients)
+        ));
+        assert!(matches!(
+            client.send_message(MessageBuilder::new().to("jane@example.com")).await,
+            Err(Error::MissingSender)
+        ));
+        assert!(matches!(
+            client
+                .send(
+                    &Envelope::new("john@example.com")
+                        .recipient("jane@example.com")
+                        .recipient("jim@example.com>\r\nDATA"),
+                    b"Hi"
+                )
+                .await,
+            Err(Error::InvalidAddress(address)) if address == "jim@example.com>\r\nDATA"
+        ));
+        assert!(matches!(
+            client.ehlo("example.com\r\nRSET").await,
+            Err(Error::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            client.authenticate(&Credentials::new(USERNAME, PASSWORD)).await,
+            Err(Error::AuthenticationUnavailable)
+        ));
+        client.noop().await.unwrap();
+        client.quit().await.unwrap();
+
+        let transcript = server.await.unwrap();
+        assert_eq!(
+            transcript.commands[1..],
+            [
+                "MAIL FROM:<john@example.com> SIZE=19",
+                "RCPT TO:<jim@example.com>",
+                "DATA",
+                "RSET",
+                "NOOP",
+                "QUIT"
+            ]
+        );
+        assert!(transcript.messages.is_empty());
+    }
+
+    #[tokio::test]
+    async fn connect_errors() {
+        let (port, _) = stub::start(StubConfig::default()).await;
+        assert!(matches!(
+            SmtpClientBuilder::new("127.0.0.1", port)
+                .tls(Tls::StartTls)
+                .connect()
+                .await,
+            Err(Error::StartTlsUnavailable)
+        ));
+
+        let (port, server) = stub::start(StubConfig {
+            no_esmtp: true,
+            ..Default::default()
+        })
+        .await;
+        let client = SmtpClientBuilder::new("127.0.0.1", port)
+            .tls(Tls::None)
+            .connect()
+            .await
+            .unwrap();
+        assert!(!client.capabilities().pipelining);
+        client.quit().await.unwrap();
+        assert_eq!(server.await.unwrap().commands, ["EHLO localhost", "HELO localhost", "QUIT"]);
+    }
+}
@@ -0,0 +1,372 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+use std::fmt::Write;
+
+use super::Error;
+use crate::{
+    MessageBuilder,
+    headers::{HeaderType, address::Address},
+};
+
+/// When the server should send a delivery status notification for a
+/// recipient (_RFC 3461_)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notify {
+    /// Never, which excludes the other values
+    Never,
+    Success,
+    Failure,
+    Delay,
+}
+
+impl Notify {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Notify::Never => "NEVER",
+            Notify::Success => "SUCCESS",
+            Notify::Failure => "FAILURE",
+            Notify::Delay => "DELAY",
+        }
+    }
+}
+
+/// What delivery status notifications include of the message (_RFC 3461_)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ret {
+    Full,
+    Headers,
+}
+
+impl Ret {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Ret::Full => "FULL",
+            Ret::Headers => "HDRS",
+        }
+    }
+}
+
+/// An envelope recipient, with its delivery status notification parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipient {
+    pub address: String,
+    pub notify: Vec<Notify>,
+    /// The original recipient, if the message is forwarded
+    pub orcpt: Option<String>,
+}
+
+impl Recipient {
+    pub fn new(address: impl Into<String>) -> Self {
+        Recipient {
+            address: address.into(),
+            notify: Vec::new(),
+            orcpt: None,
+        }
+    }
+
+    /// Add a NOTIFY condition.
+    pub fn notify(mut self, notify: Notify) -> Self {
+        if !self.notify.contains(&notify) {
+            self.notify.push(notify);
+        }
+        self
+    }
+
+    /// Set the ORCPT parameter.
+    pub fn orcpt(mut self, address: impl Into<String>) -> Self {
+        self.orcpt = Some(address.into());
+        self
+    }
+
+    // The RCPT TO parameters, DSN ones only if the server supports them
+    pub(crate) fn write_rcpt_to(&self, command: &mut String, dsn: bool) -> Result<(), Error> {
+        check_address(&self.address)?;
+        let _ = write!(command, "RCPT TO:<{}>", self.address);
+        if dsn {
+            if !self.notify.is_empty() {
+                command.push_str(" NOTIFY=");
+                if self.notify.contains(&Notify::Never) {
+                    command.push_str(Notify::Never.as_str());
+                } else {
+                    for (pos, notify) in self.notify.iter().enumerate() {
+                        if pos > 0 {
+                            command.push(',');
+                        }
+                        command.push_str(notify.as_str());
+                    }
+                }
+            }
+            if let Some(orcpt) = &self.orcpt {
+                command.push_str(" ORCPT=rfc822;");
+                write_xtext(command, orcpt);
+            }
+        }
+        command.push_str("\r\n");
+        Ok(())
+    }
+}
+
+impl From<&str> for Recipient {
+    fn from(address: &str) -> Self {
+        Recipient::new(address)
+    }
+}
+
+impl From<String> for Recipient {
+    fn from(address: String) -> Self {
+        Recipient::new(address)
+    }
+}
+
+/// The sender and recipients of a message, which may differ from the
+/// addresses in its headers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Envelope {
+    /// The reverse path, where bounces are sent. Empty for the null
+    /// reverse path used by bounces themselves.
+    pub mail_from: String,
+    pub recipients: Vec<Recipient>,
+    pub ret: Option<Ret>,
+    /// The ENVID parameter, returned in delivery status notifications
+    pub envelope_id: Option<String>,
+}
+
+impl Envelope {
+    pub fn new(mail_from: impl Into<String>) -> Self {
+        Envelope {
+            mail_from: mail_from.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Add a recipient.
+    pub fn recipient(mut self, recipient: impl Into<Recipient>) -> Self {
+        self.recipients.push(recipient.into());
+        self
+    }
+
+    /// Set the RET parameter.
+    pub fn ret(mut self, ret: Ret) -> Self {
+        self.ret = Some(ret);
+        self
+    }
+
+    /// Set the ENVID parameter.
+    pub fn envelope_id(mut self, envelope_id: impl Into<String>) -> Self {
+        self.envelope_id = Some(envelope_id.into());
+        self
+    }
+
+    /// Build the envelope of a message: the sender is the first address of
+    /// the Sender or From header, and the recipients are the addresses of
+    /// the To, Cc and Bcc headers, without duplicates.
+    pub fn from_message(message: &MessageBuilder<'_>) -> Self {
+        let mut envelope = Envelope::default();
+
+        for name in ["Sender", "From"] {
+            if let Some(address) = header_addresses(message, name).into_iter().next() {
+                envelope.mail_from = address;
+                break;
+            }
+        }
+
+        for name in ["To", "Cc", "Bcc"] {
+            for address in header_addresses(message, name) {
+                if !envelope
+                    .recipients
+                    .iter()
+                    .any(|recipient| recipient.address.eq_ignore_ascii_case(&address))
+                {
+                    envelope.recipients.push(Recipient::new(address));
+                }
+            }
+        }
+
+        envelope
+    }
+
+    /// Whether the envelope needs the SMTPUTF8 extension
+    pub fn is_utf8(&self) -> bool {
+        !self.mail_from.is_ascii() || self.recipients.iter().any(|recipient| !recipient.address.is_ascii())
+    }
+
+    pub(crate) fn write_mail_from(&self, command: &mut String, params: &[String], dsn: bool) -> Result<(), Error> {
+        check_address(&self.mail_from)?;
+        let _ = write!(command, "MAIL FROM:<{}>", self.mail_from);
+        for param in params {
+            command.push(' ');
+            command.push_str(param);
+        }
+        if dsn {
+            if let Some(ret) = self.ret {
+                command.push_str(" RET=");
+                command.push_str(ret.as_str());
+            }
+            if let Some(envelope_id) = &self.envelope_id {
+                command.push_str(" ENVID=");
+                write_xtext(command, envelope_id);
+            }
+        }
+        command.push_str("\r\n");
+        Ok(())
+    }
+}
+
+fn header_addresses(message: &MessageBuilder<'_>, name: &str) -> Vec<String> {
+    let mut addresses = Vec::new();
+    for (header_name, value) in &message.headers {
+        if header_name.eq_ignore_ascii_case(name)
+            && let HeaderType::Address(address) = value
+        {
+            collect_addresses(address, &mut addresses);
+        }
+    }
+    addresses
+}
+
+fn collect_addresses(address: &Address<'_>, addresses: &mut Vec<String>) {
+    match address {
+        Address::Address(address) => addresses.push(address.email.to_string()),
+        Address::Group(group) => {
+            for address in &group.addresses {
+                collect_addresses(address, addresses);
+            }
+        }
+        Address::List(list) => {
+            for address in list {
+                collect_addresses(address, addresses);
+            }
+        }
+    }
+}
+
+// Rejects addresses, and EHLO domains, that could end the command early or
+// add parameters to it.
+pub(crate) fn check_address(address: &str) -> Result<(), Error> {
+    if address
+        .bytes()
+        .any(|ch| ch.is_ascii_control() || matches!(ch, b'<' | b'>' | b' '))
+    {
+        Err(Error::InvalidAddress(address.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+// xtext (RFC 3461): "+", "=" and characters outside of "!" to "~" are
+// written as "+" followed by their hexadecimal value.
+fn write_xtext(output: &mut String, value: &str) {
+    for &ch in value.as_bytes() {
+        if (b'!'..=b'~').contains(&ch) && ch != b'+' && ch != b'=' {
+            output.push(char::from(ch));
+        } else {
+            let _ = write!(output, "+{:02X}", ch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Envelope, Notify, Recipient, Ret};
+    use crate::{MessageBuilder, headers::address::Address, smtp::Error};
+
+    #[test]
+    fn envelope_from_message() {
+        let message = MessageBuilder::new()
+            .from(("John Doe", "john@example.com"))
+            .to(vec![("Jane Doe", "jane@example.com"), ("James", "james@example.com")])
+            .cc(Address::new_group(
+                "Team".into(),
+                vec![
+                    Address::new_address(None::<&str>, "JANE@example.com"),
+                    Address::new_address(None::<&str>, "jim@example.com"),
+                ],
+            ))
+            .bcc("archive@example.com");
+
+        assert_eq!(
+            Envelope::from_message(&message),
+            Envelope::new("john@example.com")
+                .recipient("jane@example.com")
+                .recipient("james@example.com")
+                .recipient("jim@example.com")
+                .recipient("archive@example.com")
+        );
+
+        let message = message.sender("list@example.com");
+        assert_eq!(Envelope::from_message(&message).mail_from, "list@example.com");
+        assert_eq!(Envelope::from_message(&MessageBuilder::new()), Envelope::default());
+    }
+
+    #[test]
+    fn dsn_parameters() {
+        let envelope = Envelope::new("john@example.com")
+            .ret(Ret::Headers)
+            .envelope_id("QQ314159 +x=y")
+            .recipient(
+                Recipient::new("jane@example.com")
+                    .notify(Notify::Failure)
+                    .notify(Notify::Delay)
+                    .orcpt("jane+lists@example.com"),
+            )
+            .recipient(
+                Recipient::new("jim@example.com")
+                    .notify(Notify::Never)
+                    .notify(Notify::Success),
+            );
+
+        let mut command = String::new();
+        envelope
+            .write_mail_from(&mut command, &["SIZE=100".to_string()], true)
+            .unwrap();
+        envelope.recipients[0].write_rcpt_to(&mut command, true).unwrap();
+        envelope.recipients[1].write_rcpt_to(&mut command, true).unwrap();
+        envelope.recipients[0].write_rcpt_to(&mut command, false).unwrap();
+        assert_eq!(
+            command,
+            concat!(
+                "MAIL FROM:<john@example.com> SIZE=100 RET=HDRS ENVID=QQ314159+20+2Bx+3Dy\r\n",
+                "RCPT TO:<jane@example.com> NOTIFY=FAILURE,DELAY ORCPT=rfc822;jane+2Blists@example.com\r\n",
+                "RCPT TO:<jim@example.com> NOTIFY=NEVER\r\n",
+                "RCPT TO:<jane@example.com>\r\n",
+            )
+        );
+        assert!(!envelope.is_utf8());
+        assert!(Envelope::new("jöhn@example.com").is_utf8());
+    }
+
+    #[test]
+    fn reject_invalid_addresses() {
+        for address in [
+            "john@example.com>\r\nRCPT TO:<jim@example.com",
+            "john@example.com\nDATA",
+            "john\0@example.com",
+            "<john@example.com>",
+            "john@example.com> SIZE=1",
+        ] {
+            let mut command = String::new();
+            assert!(matches!(
+                Envelope::new(address).write_mail_from(&mut command, &[], true),
+                Err(Error::InvalidAddress(invalid)) if invalid == address
+            ));
+            assert!(matches!(
+                Recipient::new(address).write_rcpt_to(&mut command, true),
+                Err(Error::InvalidAddress(invalid)) if invalid == address
+            ));
+            assert!(command.is_empty());
+        }
+
+        let mut command = String::new();
+        Envelope::new("").write_mail_from(&mut command, &[], false).unwrap();
+        assert_eq!(command, "MAIL FROM:<>\r\n");
+    }
+}
@@ -0,0 +1,238 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+//! Async SMTP client (_RFC 5321_) with implicit TLS and STARTTLS,
+//! AUTH PLAIN, LOGIN and XOAUTH2, and the PIPELINING, SIZE, 8BITMIME,
+//! SMTPUTF8 and DSN extensions.
+//!
+//! ```no_run
+//! # async fn send() -> Result<(), mail_builder::smtp::Error> {
+//! use mail_builder::{
+//!     MessageBuilder,
+//!     smtp::{Credentials, SmtpClientBuilder, Tls},
+//! };
+//!
+//! let message = MessageBuilder::new()
+//!     .from(("John Doe", "john@example.com"))
+//!     .to(vec![("Jane Doe", "jane@example.com"), ("James Smith", "james@test.com")])
+//!     .subject("Hi!")
+//!     .text_body("Hello, world!");
+//!
+//! let result = SmtpClientBuilder::new("smtp.example.com", 587)
+//!     .tls(Tls::StartTls)
+//!     .credentials(Credentials::new("john", "p4ssw0rd"))
+//!     .connect()
+//!     .await?
+//!     .send_message(message)
+//!     .await?;
+//!
+//! for recipient in result.recipients {
+//!     println!("{}: {}", recipient.address, recipient.response);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod capabilities;
+pub mod client;
+pub mod envelope;
+#[cfg(test)]
+mod stub;
+
+use std::{fmt, io};
+
+pub use self::{
+    capabilities::Capabilities,
+    client::{RecipientResult, SendResult, SmtpClient, SmtpClientBuilder},
+    envelope::{Envelope, Notify, Recipient, Ret},
+};
+
+/// How the connection to the server is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tls {
+    /// TLS is negotiated as soon as the connection is established,
+    /// usually on port 465.
+    #[default]
+    Implicit,
+    /// The connection is upgraded with STARTTLS, which the server must
+    /// advertise. Usually on port 587.
+    StartTls,
+    /// Plain text connection, only suitable for local servers.
+    None,
+}
+
+/// Authentication credentials
+#[derive(Clone, PartialEq, Eq)]
+pub enum Credentials {
+    /// Authenticates with AUTH PLAIN, or AUTH LOGIN if the server does not
+    /// support PLAIN.
+    Password { username: String, password: String },
+    /// Authenticates with AUTH XOAUTH2 and an OAuth 2.0 access token.
+    XOAuth2 { username: String, access_token: String },
+}
+
+impl Credentials {
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Credentials::Password {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    pub fn xoauth2(username: impl Into<String>, access_token: impl Into<String>) -> Self {
+        Credentials::XOAuth2 {
+            username: username.into(),
+            access_token: access_token.into(),
+        }
+    }
+}
+
+// Keeps passwords and tokens out of the logs
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Credentials::Password {
+                username, ..
+            } => f
+                .debug_struct("Password")
+                .field("username", username)
+                .finish_non_exhaustive(),
+            Credentials::XOAuth2 {
+                username, ..
+            } => f
+                .debug_struct("XOAuth2")
+                .field("username", username)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+/// A reply of the server. The lines of multiline replies are joined with
+/// "\n".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub code: u16,
+    pub message: String,
+}
+
+impl Response {
+    /// 2yz: the command was accepted
+    pub fn is_positive_completion(&self) -> bool {
+        (200..300).contains(&self.code)
+    }
+
+    /// 3yz: the server waits for more data
+    pub fn is_positive_intermediate(&self) -> bool {
+        (300..400).contains(&self.code)
+    }
+
+    /// 4yz: the command failed, and may succeed if retried later
+    pub fn is_transient_failure(&self) -> bool {
+        (400..500).contains(&self.code)
+    }
+
+    /// 5yz: the command failed, and should not be retried
+    pub fn is_permanent_failure(&self) -> bool {
+        self.code >= 500
+    }
+
+    /// The enhanced status code (_RFC 3463_) at the beginning of the
+    /// message, such as "5.1.1".
+    pub fn enhanced_status(&self) -> Option<&str> {
+        let status = self.message.split_ascii_whitespace().next()?;
+        let mut parts = status.split('.');
+        (matches!(parts.next(), Some("2" | "4" | "5"))
+            && parts.next().is_some_and(|part| is_status_number(part, 3))
+            && parts.next().is_some_and(|part| is_status_number(part, 3))
+            && parts.next().is_none())
+        .then_some(status)
+    }
+}
+
+fn is_status_number(value: &str, max_len: usize) -> bool {
+    !value.is_empty() && value.len() <= max_len && value.bytes().all(|ch| ch.is_ascii_digit())
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.code, self.message)
+    }
+}
+
+/// SMTP client errors
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// The TLS handshake failed, or the server name is invalid
+    Tls(String),
+    /// The server did not reply in time
+    Timeout,
+    /// The server sent a malformed reply
+    InvalidResponse,
+    /// The server rejected a command
+    UnexpectedResponse(Response),
+    /// TLS was required but the server does not advertise STARTTLS
+    StartTlsUnavailable,
+    /// The server supports none of the mechanisms usable with the credentials
+    AuthenticationUnavailable,
+    AuthenticationFailed(Response),
+    /// The envelope has non-ASCII addresses but the server does not
+    /// advertise SMTPUTF8
+    SmtpUtf8Unavailable,
+    /// The message is larger than the SIZE advertised by the server
+    MessageTooLarge {
+        size: usize,
+        max_size: usize,
+    },
+    /// The envelope has no sender, and the message has no Sender or From header
+    MissingSender,
+    /// The envelope has no recipients
+    NoRecipients,
+    /// An address or the EHLO domain contains characters that would
+    /// break the command it is sent in, such as CR or LF
+    InvalidAddress(String),
+    /// The server rejected all the recipients, the message was not sent
+    RecipientsRejected(Vec<RecipientResult>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Tls(err) => write!(f, "TLS error: {}", err),
+            Error::Timeout => write!(f, "Connection timed out"),
+            Error::InvalidResponse => write!(f, "Invalid response from the server"),
+            Error::UnexpectedResponse(response) => write!(f, "Unexpected response: {}", response),
+            Error::StartTlsUnavailable => write!(f, "The server does not support STARTTLS"),
+            Error::AuthenticationUnavailable => write!(f, "No supported authentication mechanism"),
+            Error::AuthenticationFailed(response) => write!(f, "Authentication failed: {}", response),
+            Error::SmtpUtf8Unavailable => write!(f, "The server does not support SMTPUTF8"),
+            Error::MessageTooLarge {
+                size,
+                max_size,
+            } => {
+                write!(f, "The message is too large ({} bytes, maximum {} bytes)", size, max_size)
+            }
+            Error::MissingSender => write!(f, "No sender address"),
+            Error::NoRecipients => write!(f, "No recipient addresses"),
+            Error::InvalidAddress(address) => write!(f, "Invalid address {:?}", address),
+            Error::RecipientsRejected(_) => write!(f, "All the recipients were rejected"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,263 @@
+/*
+ * Copyright Stalwart Labs Ltd. See the COPYING
+ * file at the top-level directory of this distribution.
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+//! A scripted SMTP server accepting a single connection, used to test the
+//! client without a real server.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpListener,
+    task::JoinHandle,
+};
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        ClientConfig, RootCertStore, ServerConfig,
+        crypto::ring,
+        pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer},
+    },
+};
+
+use crate::{decoders::base64::base64_decode, encoders::base64::base64_encode};
+
+pub const USERNAME: &str = "john";
+pub const PASSWORD: &str = "secret";
+pub const ACCESS_TOKEN: &str = "ya29.token";
+
+#[derive(Default)]
+pub struct StubConfig {
+    /// The extensions advertised after EHLO. STARTTLS is added when `tls`
+    /// is set and the connection is not secured yet.
+    pub extensions: Vec<&'static str>,
+    pub tls: Option<Arc<ServerConfig>>,
+    pub implicit_tls: bool,
+    /// Recipients rejected with a 550 reply
+    pub rejected: Vec<&'static str>,
+    /// Reply to EHLO with 502, as a server only supporting HELO
+    pub no_esmtp: bool,
+    /// Incremented with the size of each line received after DATA, to
+    /// follow the progress of a message while it is sent
+    pub received: Arc<AtomicUsize>,
+}
+
+/// What the server received
+#[derive(Debug, Default)]
+pub struct Transcript {
+    pub commands: Vec<String>,
+    /// The contents of each DATA command, without the end of data marker
+    pub messages: Vec<Vec<u8>>,
+    /// Whether more commands were already received when each command was
+    /// read, i.e. the client pipelined them
+    pub pipelined: Vec<bool>,
+    pub secured: bool,
+}
+
+impl Transcript {
+    pub fn command(&self, prefix: &str) -> Option<&str> {
+        self.commands
+            .iter()
+            .find(|command| command.starts_with(prefix))
+            .map(String::as_str)
+    }
+}
+
+/// Start a server on a random local port, returning the port and a task
+/// resolving to the transcript once the client disconnects.
+pub async fn start(config: StubConfig) -> (u16, JoinHandle<Transcript>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let handle = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut transcript = Transcript::default();
+        let acceptor = config.tls.clone().map(TlsAcceptor::from);
+
+        if config.implicit_tls {
+            let stream = acceptor.unwrap().accept(stream).await.unwrap();
+            transcript.secured = true;
+            session(stream, &config, &mut transcript, true).await;
+        } else if let Some(stream) = session(stream, &config, &mut transcript, false).await {
+            let stream = acceptor.unwrap().accept(stream).await.unwrap();
+            transcript.secured = true;
+            session(stream, &config, &mut transcript, true).await;
+        }
+
+        transcript
+    });
+
+    (port, handle)
+}
+
+/// A self-signed certificate for "localhost", returned as the
+/// configuration of the server and a client configuration trusting it.
+pub fn tls_configs() -> (Arc<ServerConfig>, Arc<ClientConfig>) {
+    let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let certificate = certified.cert.der().clone();
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+
+    let server = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(vec![certificate.clone()], key)
+        .unwrap();
+
+    let mut roots = RootCertStore::empty();
+    roots.add(certificate).unwrap();
+    let client = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    (Arc::new(server), Arc::new(client))
+}
+
+// Runs the session until QUIT or the client disconnects. Returns the
+// stream when the client sends STARTTLS, for the caller to secure it.
+async fn session<T: AsyncRead + AsyncWrite + Unpin>(
+    stream: T,
+    config: &StubConfig,
+    transcript: &mut Transcript,
+    secured: bool,
+) -> Option<T> {
+    let mut stream = BufReader::new(stream);
+    let mut accepted_recipients = 0;
+
+    if !secured || config.implicit_tls {
+        reply(&mut stream, "220 stub.example.com ESMTP ready\r\n").await;
+    }
+
+    loop {
+        let command = read_line(&mut stream).await?;
+        transcript.pipelined.push(!stream.buffer().is_empty());
+        transcript.commands.push(command.clone());
+        let upper_command = command.to_ascii_uppercase();
+
+        if upper_command.starts_with("EHLO ") {
+            if config.no_esmtp {
+                reply(&mut stream, "502 5.5.1 Command not implemented\r\n").await;
+                continue;
+            }
+            let mut response = String::from("250-stub.example.com\r\n");
+            let mut extensions = config.extensions.clone();
+            if config.tls.is_some() && !secured {
+                extensions.push("STARTTLS");
+            }
+            for (pos, extension) in extensions.iter().enumerate() {
+                let separator = if pos + 1 == extensions.len() { ' ' } else { '-' };
+                response.push_str(&format!("250{}{}\r\n", separator, extension));
+            }
+            if extensions.is_empty() {
+                response = "250 stub.example.com\r\n".into();
+            }
+            reply(&mut stream, &response).await;
+        } else if upper_command.starts_with("HELO ") {
+            reply(&mut stream, "250 stub.example.com\r\n").await;
+        } else if upper_command == "STARTTLS" {
+            reply(&mut stream, "220 2.0.0 Ready to start TLS\r\n").await;
+            return Some(stream.into_inner());
+        } else if let Some(token) = upper_command.strip_prefix("AUTH PLAIN ") {
+            let token = &command[command.len() - token.len()..];
+            let expected = format!("\0{}\0{}", USERNAME, PASSWORD);
+            auth_reply(&mut stream, base64_decode(token.as_bytes()) == Some(expected.into_bytes())).await;
+        } else if upper_command == "AUTH LOGIN" {
+            reply(&mut stream, "334 VXNlcm5hbWU6\r\n").await;
+            let username = read_line(&mut stream).await?;
+            reply(&mut stream, "334 UGFzc3dvcmQ6\r\n").await;
+            let password = read_line(&mut stream).await?;
+            auth_reply(
+                &mut stream,
+                base64_decode(username.as_bytes()) == Some(USERNAME.into())
+                    && base64_decode(password.as_bytes()) == Some(PASSWORD.into()),
+            )
+            .await;
+        } else if let Some(token) = upper_command.strip_prefix("AUTH XOAUTH2 ") {
+            let token = &command[command.len() - token.len()..];
+            let expected = format!("user={}\x01auth=Bearer {}\x01\x01", USERNAME, ACCESS_TOKEN);
+            if base64_decode(token.as_bytes()) == Some(expected.into_bytes()) {
+                auth_reply(&mut stream, true).await;
+            } else {
+                let details = base64_encode(br#"{"status":"401","schemes":"bearer"}"#).unwrap();
+                reply(&mut stream, &format!("334 {}\r\n", String::from_utf8(details).unwrap())).await;
+                read_line(&mut stream).await?;
+                auth_reply(&mut stream, false).await;
+            }
+        } else if upper_command.starts_with("MAIL FROM:") {
+            accepted_recipients = 0;
+            reply(&mut stream, "250 2.1.0 Sender OK\r\n").await;
+        } else if upper_command.starts_with("RCPT TO:") {
+            let address = command
+                .split_once('<')
+                .and_then(|(_, address)| address.split_once('>'))
+                .map(|(address, _)| address)
+                .unwrap_or_default();
+            if config.rejected.contains(&address) {
+                reply(&mut stream, "550 5.1.1 Mailbox does not exist\r\n").await;
+            } else {
+                accepted_recipients += 1;
+                reply(&mut stream, "250 2.1.5 Recipient OK\r\n").await;
+            }
+        } else if upper_command == "DATA" {
+            if accepted_recipients == 0 {
+                reply(&mut stream, "554 5.5.1 No valid recipients\r\n").await;
+                continue;
+            }
+            reply(&mut stream, "354 Start mail input; end with <CRLF>.<CRLF>\r\n").await;
+
+            let mut message = Vec::new();
+            loop {
+                let mut line = Vec::new();
+                if stream.read_until(b'\n', &mut line).await.ok()? == 0 {
+                    return None;
+                } else if line == b".\r\n" {
+                    break;
+                }
+                config.received.fetch_add(line.len(), Ordering::Relaxed);
+                message.extend_from_slice(&line);
+            }
+            transcript.messages.push(message);
+            reply(&mut stream, "250 2.0.0 Message queued\r\n").await;
+        } else if upper_command == "RSET" || upper_command == "NOOP" {
+            reply(&mut stream, "250 2.0.0 OK\r\n").await;
+        } else if upper_command == "QUIT" {
+            reply(&mut stream, "221 2.0.0 Bye\r\n").await;
+            return None;
+        } else {
+            reply(&mut stream, "500 5.5.2 Command not recognized\r\n").await;
+        }
+    }
+}
+
+async fn read_line<T: AsyncRead + AsyncWrite + Unpin>(stream: &mut BufReader<T>) -> Option<String> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await.ok()? == 0 {
+        return None;
+    }
+    Some(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+async fn reply<T: AsyncRead + AsyncWrite + Unpin>(stream: &mut BufReader<T>, response: &str) {
+    let _ = stream.get_mut().write_all(response.as_bytes()).await;
+}
+
+async fn auth_reply<T: AsyncRead + AsyncWrite + Unpin>(stream: &mut BufReader<T>, success: bool) {
+    if success {
+        reply(stream, "235 2.7.0 Authentication successful\r\n").await;
+    } else {
+        reply(stream, "535 5.7.8 Authentication credentials invalid\r\n").await;
+    }
+}
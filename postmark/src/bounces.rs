@@ -0,0 +1,166 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ApiError, Client, SendRequestInput,
+    client::url_with_query,
+    pagination::{Page, PageStream, paginate},
+};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Bounce {
+    #[serde(rename = "ID")]
+    pub id: i64,
+    /// The type of bounce, e.g. `HardBounce`
+    pub r#type: String,
+    pub type_code: i64,
+    pub name: Option<String>,
+    pub tag: Option<String>,
+    #[serde(rename = "MessageID")]
+    pub message_id: Option<String>,
+    #[serde(rename = "ServerID")]
+    pub server_id: Option<i64>,
+    pub message_stream: Option<String>,
+    pub description: Option<String>,
+    pub details: Option<String>,
+    pub email: String,
+    pub from: Option<String>,
+    pub bounced_at: String,
+    /// Whether the raw source of the bounce can be retrieved with `get_bounce_dump`
+    #[serde(default)]
+    pub dump_available: bool,
+    /// Whether the address has been deactivated: Postmark no longer sends emails to this address
+    #[serde(default)]
+    pub inactive: bool,
+    /// Whether the address can be reactivated with `activate_bounce`
+    #[serde(default)]
+    pub can_activate: bool,
+    pub subject: Option<String>,
+    pub content: Option<String>,
+}
+
+/// Filters for `list_bounces`. Dates use the `2024-01-31T12:00:00` format, in Eastern Time.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ListBouncesOptions {
+    /// Number of bounces to return. Max 500.
+    pub count: i64,
+    /// Number of bounces to skip. `count + offset` can't exceed 10,000.
+    pub offset: i64,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inactive: Option<bool>,
+    /// Filter the bounces by email address
+    #[serde(rename = "emailFilter", skip_serializing_if = "Option::is_none")]
+    pub email_filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(rename = "messageID", skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(rename = "fromdate", skip_serializing_if = "Option::is_none")]
+    pub from_date: Option<String>,
+    #[serde(rename = "todate", skip_serializing_if = "Option::is_none")]
+    pub to_date: Option<String>,
+    #[serde(rename = "messagestream", skip_serializing_if = "Option::is_none")]
+    pub message_stream: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListBouncesResponse {
+    pub total_count: i64,
+    pub bounces: Vec<Bounce>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BounceDump {
+    /// The raw source of the bounce. Empty if the dump is no longer available.
+    pub body: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ActivateBounceResponse {
+    pub message: String,
+    pub bounce: Bounce,
+}
+
+impl Client {
+    pub async fn list_bounces(
+        &self,
+        server_token: String,
+        options: ListBouncesOptions,
+    ) -> Result<ListBouncesResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::GET,
+                url: url_with_query("/bounces", &options)?,
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Stream all the bounces matching `options`, fetching them page by page.
+    /// `options.count` and `options.offset` are ignored.
+    /// Postmark only lists the first `MAX_RECORDS` (10,000) bounces: narrow down `options`, e.g. with
+    /// `from_date` and `to_date`, to get the others.
+    pub fn bounces_stream(&self, server_token: String, options: ListBouncesOptions) -> PageStream<'_, Bounce> {
+        return paginate(move |offset, count| {
+            let server_token = server_token.clone();
+            let options = ListBouncesOptions {
+                count,
+                offset,
+                ..options.clone()
+            };
+            async move {
+                let res = self.list_bounces(server_token, options).await?;
+                return Ok(Page {
+                    items: res.bounces,
+                    total_count: res.total_count,
+                });
+            }
+        });
+    }
+
+    pub async fn get_bounce(&self, server_token: String, bounce_id: i64) -> Result<Bounce, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::GET,
+                url: format!("/bounces/{bounce_id}"),
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Get the raw source of a bounce. Dumps are kept for 30 days.
+    pub async fn get_bounce_dump(&self, server_token: String, bounce_id: i64) -> Result<BounceDump, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::GET,
+                url: format!("/bounces/{bounce_id}/dump"),
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Reactivate the address of a bounce, so emails can be sent to it again.
+    pub async fn activate_bounce(
+        &self,
+        server_token: String,
+        bounce_id: i64,
+    ) -> Result<ActivateBounceResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::PUT,
+                url: format!("/bounces/{bounce_id}/activate"),
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+}
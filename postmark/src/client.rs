@@ -1,37 +1,90 @@
+use std::{sync::Arc, time::Duration};
+
 use reqwest::{
     Method,
     header::{self, HeaderMap, HeaderValue},
 };
-use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{ApiError, ErrorCode, HttpRequest, HttpTransport, ReqwestTransport};
 
 pub struct Client {
-    pub http_client: reqwest::Client,
+    pub transport: Arc<dyn HttpTransport>,
     pub api_base_url: &'static str,
     pub account_api_token: Option<String>,
+    pub retry_policy: RetryPolicy,
+}
+
+/// How requests rejected with `429 Too Many Requests` or a `5xx` status are retried. The delay between
+/// two attempts doubles after each retry, unless the API specifies it with a `Retry-After` header.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Set to `0` to disable retries
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        return RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        };
+    }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "PascalCase")]
-pub struct ApiError {
-    pub error_code: i64,
-    pub message: String,
+impl RetryPolicy {
+    fn backoff(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        let backoff = retry_after.unwrap_or_else(|| self.initial_backoff.saturating_mul(2u32.saturating_pow(retry)));
+        return backoff.min(self.max_backoff);
+    }
 }
 
 pub struct SendRequestInput<B: Serialize> {
     pub method: Method,
     pub url: String,
-    pub body: B,
+    /// The JSON body of the request. Requests without a body, such as `GET` requests, use `None`.
+    pub body: Option<B>,
     pub server_token: Option<String>,
 }
 
 impl Client {
     pub fn new(account_api_token: Option<String>) -> Client {
-        let http_client = reqwest::Client::new();
-
         return Client {
-            http_client,
+            transport: Arc::new(ReqwestTransport::default()),
             api_base_url: "https://api.postmarkapp.com",
             account_api_token,
+            retry_policy: RetryPolicy::default(),
+        };
+    }
+
+    /// Use `transport` to send the requests, e.g. a local stand-in of the API in tests, or a
+    /// `ReqwestTransport` wrapping a custom `reqwest::Client`:
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use postmark::{Client, ReqwestTransport};
+    ///
+    /// let http_client = reqwest::Client::builder()
+    ///     .timeout(Duration::from_secs(30))
+    ///     .build()
+    ///     .unwrap();
+    /// let client = Client::new(None).with_transport(ReqwestTransport::new(http_client));
+    /// ```
+    pub fn with_transport(self, transport: impl HttpTransport + 'static) -> Client {
+        return Client {
+            transport: Arc::new(transport),
+            ..self
+        };
+    }
+
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> Client {
+        return Client {
+            retry_policy,
+            ..self
         };
     }
 
@@ -54,32 +107,140 @@ impl Client {
             );
         }
 
-        let url = format!("{}{}", &self.api_base_url, input.url);
-        let res = self
-            .http_client
-            .request(input.method, url)
-            .headers(headers)
-            .json(&input.body)
-            .send()
-            .await
-            .map_err(|err| ApiError {
-                error_code: 0,
+        let body = match &input.body {
+            Some(body) => Some(serde_json::to_vec(body).map_err(|err| ApiError {
+                error_code: ErrorCode::RequestFailed,
+                message: format!("postmark: error encoding request: {err}"),
+            })?),
+            None => None,
+        };
+        let req = HttpRequest {
+            method: input.method,
+            url: format!("{}{}", &self.api_base_url, input.url),
+            headers,
+            body,
+        };
+
+        let mut retry = 0;
+        let res = loop {
+            let res = self.transport.send(req.clone()).await.map_err(|err| ApiError {
+                error_code: ErrorCode::RequestFailed,
                 message: format!("postmark: error sending request: {err}"),
             })?;
 
-        if res.status().as_u16() > 399 {
-            let err: ApiError = res.json().await.map_err(|err| ApiError {
-                error_code: 0,
-                message: format!("postmark: error parsing error response: {err}"),
+            let retryable = res.status == 429 || res.status >= 500;
+            if !retryable || retry >= self.retry_policy.max_retries {
+                break res;
+            }
+
+            let retry_after = res
+                .headers
+                .get(header::RETRY_AFTER)
+                .and_then(|retry_after| retry_after.to_str().ok())
+                .and_then(|retry_after| retry_after.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            tokio::time::sleep(self.retry_policy.backoff(retry, retry_after)).await;
+            retry += 1;
+        };
+
+        if res.status > 399 {
+            let err: ApiError = serde_json::from_slice(&res.body).map_err(|err| ApiError {
+                error_code: ErrorCode::InvalidResponse,
+                message: format!("postmark: error parsing error response (HTTP status: {}): {err}", res.status),
             })?;
             return Err(err);
         }
 
-        let res: R = res.json().await.map_err(|err| ApiError {
-            error_code: 0,
+        let res: R = serde_json::from_slice(&res.body).map_err(|err| ApiError {
+            error_code: ErrorCode::InvalidResponse,
             message: format!("postmark: error parsing response: {err}"),
         })?;
 
         return Ok(res);
     }
 }
+
+/// Append the URL-encoded `query` to `path`
+pub(crate) fn url_with_query<Q: Serialize>(path: &str, query: &Q) -> Result<String, ApiError> {
+    let query = serde_urlencoded::to_string(query).map_err(|err| ApiError {
+        error_code: ErrorCode::RequestFailed,
+        message: format!("postmark: error encoding query: {err}"),
+    })?;
+    if query.is_empty() {
+        return Ok(path.to_string());
+    }
+    return Ok(format!("{path}?{query}"));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, sync::Mutex};
+
+    use super::*;
+    use crate::{BoxFuture, Email, HttpResponse, SendEmailResponse};
+
+    /// Answers requests with canned responses, and records the requests
+    #[derive(Clone, Default)]
+    struct StandIn {
+        responses: Arc<Mutex<VecDeque<HttpResponse>>>,
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+    }
+
+    impl HttpTransport for StandIn {
+        fn send(
+            &self,
+            request: HttpRequest,
+        ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+            self.requests.lock().unwrap().push(request);
+            let response = self.responses.lock().unwrap().pop_front();
+            return Box::pin(async move { response.ok_or_else(|| "no more responses".into()) });
+        }
+    }
+
+    fn response(status: u16, body: &str) -> HttpResponse {
+        return HttpResponse {
+            status,
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+        };
+    }
+
+    #[tokio::test]
+    async fn retry_and_error_codes() {
+        let transport = StandIn::default();
+        transport.responses.lock().unwrap().extend([
+            response(429, r#"{"ErrorCode":429,"Message":"Rate limit exceeded"}"#),
+            response(503, "Service Unavailable"),
+            response(200, r#"{"To":"a@example.com","MessageID":"1","ErrorCode":0,"Message":"OK"}"#),
+            response(422, r#"{"ErrorCode":406,"Message":"Inactive recipient"}"#),
+        ]);
+        let client = Client::new(None)
+            .with_transport(transport.clone())
+            .with_retry_policy(RetryPolicy {
+                max_retries: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(5),
+            });
+
+        let res: SendEmailResponse = client.send_email("token".to_string(), Email::default()).await.unwrap();
+        assert_eq!(res.error_code, ErrorCode::Success);
+        assert_eq!(transport.requests.lock().unwrap().len(), 3);
+
+        let err = client
+            .send_email("token".to_string(), Email::default())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_code, ErrorCode::InactiveRecipient);
+        assert_eq!(transport.requests.lock().unwrap().len(), 4);
+
+        let err = client
+            .send_email("token".to_string(), Email::default())
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_code, ErrorCode::RequestFailed);
+
+        let request = &transport.requests.lock().unwrap()[0];
+        assert_eq!(request.url, "https://api.postmarkapp.com/email");
+        assert_eq!(request.headers["X-Postmark-Server-Token"], "token");
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
-use crate::{ApiError, Client, SendRequestInput};
+use crate::{ApiError, Client, ErrorCode, SendRequestInput, TemplateIdentifier};
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -137,6 +137,77 @@ impl Default for TrackLink {
     }
 }
 
+/// An email whose subject and body are rendered from a template.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TemplateEmail {
+    /// The template to use, identified by its ID or its alias.
+    #[serde(flatten)]
+    pub template: TemplateIdentifier,
+
+    /// The model used to render the template.
+    pub template_model: serde_json::Value,
+
+    /// By default, if the specified template contains an HTML body, we will apply the style blocks as inline attributes
+    /// to the rendered HTML content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inline_css: Option<bool>,
+
+    /// From: The sender email address. Must have a registered and confirmed Sender Signature.
+    pub from: String,
+
+    /// To: Recipient email address. Multiple addresses are comma separated. Max 50.
+    pub to: String,
+
+    /// Cc recipient email address. Multiple addresses are comma separated. Max 50.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cc: Option<String>,
+
+    /// Bcc recipient email address. Multiple addresses are comma separated. Max 50.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bcc: Option<String>,
+
+    /// Email tag that allows you to categorize outgoing emails and get detailed statistics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// Reply To override email address. Defaults to the Reply To set in the sender signature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+
+    /// List of custom headers to include.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<Header>>,
+
+    /// Activate open tracking for this email.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_opens: Option<bool>,
+
+    /// Activate link tracking for links in the HTML or Text bodies of this email.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_links: Option<TrackLink>,
+
+    /// List of attachments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<Attachment>>,
+
+    /// Custom metadata key/value pairs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+
+    /// Set message stream ID that's used for sending. If not provided, message will default to the "outbound" transactional stream.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_stream: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct SendEmailBatchWithTemplatesRequest {
+    messages: Vec<TemplateEmail>,
+}
+
+/// The result of sending an email. When sending a batch of emails, each email has its own result and
+/// `error_code` is not `ErrorCode::Success` if the email has not been sent.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct SendEmailResponse {
@@ -144,7 +215,7 @@ pub struct SendEmailResponse {
     pub submitted_at: Option<String>,
     #[serde(rename = "MessageID")]
     pub message_id: Option<String>,
-    pub error_code: i64,
+    pub error_code: ErrorCode,
     pub message: String,
 }
 
@@ -154,7 +225,59 @@ impl Client {
             .send_request(SendRequestInput {
                 method: Method::POST,
                 url: "/email".to_string(),
-                body: email,
+                body: Some(email),
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    pub async fn send_email_with_template(
+        &self,
+        server_token: String,
+        email: TemplateEmail,
+    ) -> Result<SendEmailResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::POST,
+                url: "/email/withTemplate".to_string(),
+                body: Some(email),
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Send up to 500 emails in a single request. The results are in the same order as the emails, and
+    /// an email that can't be sent doesn't prevent the others from being sent: check the `error_code`
+    /// of each result.
+    pub async fn send_email_batch(
+        &self,
+        server_token: String,
+        emails: Vec<Email>,
+    ) -> Result<Vec<SendEmailResponse>, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::POST,
+                url: "/email/batch".to_string(),
+                body: Some(emails),
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Send up to 500 templated emails in a single request. Like with `send_email_batch`, each email
+    /// has its own result.
+    pub async fn send_email_batch_with_templates(
+        &self,
+        server_token: String,
+        emails: Vec<TemplateEmail>,
+    ) -> Result<Vec<SendEmailResponse>, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::POST,
+                url: "/email/batchWithTemplates".to_string(),
+                body: Some(SendEmailBatchWithTemplatesRequest {
+                    messages: emails,
+                }),
                 server_token: Some(server_token),
             })
             .await;
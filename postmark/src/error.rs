@@ -0,0 +1,113 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// An error returned by the Postmark API, or raised by the client if the API could not be reached.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ApiError {
+    pub error_code: ErrorCode,
+    pub message: String,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{} (error code: {})", self.message, i64::from(self.error_code));
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// The error codes documented by Postmark: https://postmarkapp.com/developer/api/overview#error-codes
+///
+/// `RequestFailed` and `InvalidResponse` are never returned by Postmark: they are used when the API
+/// can't be reached or returns an unexpected response.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "i64", into = "i64")]
+pub enum ErrorCode {
+    /// No error. Used by the per-message results of batch sends.
+    #[default]
+    Success,
+    /// The request could not be sent, or the response could not be received
+    RequestFailed,
+    /// The response of the API could not be parsed
+    InvalidResponse,
+    BadOrMissingApiToken,
+    Maintenance,
+    InvalidEmailRequest,
+    SenderSignatureNotFound,
+    SenderSignatureNotConfirmed,
+    InvalidJson,
+    IncompatibleJson,
+    NotAllowedToSend,
+    InactiveRecipient,
+    BounceNotFound,
+    BounceQueryException,
+    JsonRequired,
+    TooManyBatchMessages,
+    ForbiddenAttachmentType,
+    AccountIsPending,
+    AccountMayNotSend,
+    RateLimitExceeded,
+    TemplateNotFound,
+    /// An error code without a dedicated variant
+    Other(i64),
+}
+
+impl From<i64> for ErrorCode {
+    fn from(code: i64) -> Self {
+        return match code {
+            0 => ErrorCode::Success,
+            -1 => ErrorCode::RequestFailed,
+            -2 => ErrorCode::InvalidResponse,
+            10 => ErrorCode::BadOrMissingApiToken,
+            100 => ErrorCode::Maintenance,
+            300 => ErrorCode::InvalidEmailRequest,
+            400 => ErrorCode::SenderSignatureNotFound,
+            401 => ErrorCode::SenderSignatureNotConfirmed,
+            402 => ErrorCode::InvalidJson,
+            403 => ErrorCode::IncompatibleJson,
+            405 => ErrorCode::NotAllowedToSend,
+            406 => ErrorCode::InactiveRecipient,
+            407 => ErrorCode::BounceNotFound,
+            408 => ErrorCode::BounceQueryException,
+            409 => ErrorCode::JsonRequired,
+            410 => ErrorCode::TooManyBatchMessages,
+            411 => ErrorCode::ForbiddenAttachmentType,
+            412 => ErrorCode::AccountIsPending,
+            413 => ErrorCode::AccountMayNotSend,
+            429 => ErrorCode::RateLimitExceeded,
+            1101 => ErrorCode::TemplateNotFound,
+            code => ErrorCode::Other(code),
+        };
+    }
+}
+
+impl From<ErrorCode> for i64 {
+    fn from(code: ErrorCode) -> Self {
+        return match code {
+            ErrorCode::Success => 0,
+            ErrorCode::RequestFailed => -1,
+            ErrorCode::InvalidResponse => -2,
+            ErrorCode::BadOrMissingApiToken => 10,
+            ErrorCode::Maintenance => 100,
+            ErrorCode::InvalidEmailRequest => 300,
+            ErrorCode::SenderSignatureNotFound => 400,
+            ErrorCode::SenderSignatureNotConfirmed => 401,
+            ErrorCode::InvalidJson => 402,
+            ErrorCode::IncompatibleJson => 403,
+            ErrorCode::NotAllowedToSend => 405,
+            ErrorCode::InactiveRecipient => 406,
+            ErrorCode::BounceNotFound => 407,
+            ErrorCode::BounceQueryException => 408,
+            ErrorCode::JsonRequired => 409,
+            ErrorCode::TooManyBatchMessages => 410,
+            ErrorCode::ForbiddenAttachmentType => 411,
+            ErrorCode::AccountIsPending => 412,
+            ErrorCode::AccountMayNotSend => 413,
+            ErrorCode::RateLimitExceeded => 429,
+            ErrorCode::TemplateNotFound => 1101,
+            ErrorCode::Other(code) => code,
+        };
+    }
+}
@@ -0,0 +1,488 @@
+use std::{borrow::Cow, fmt, io::Read};
+
+use mail_builder::{
+    MessageBuilder,
+    headers::{
+        HeaderType,
+        address::{Address, EmailAddress},
+        content_type::ContentType,
+    },
+    html::html_to_text,
+    mime::{BodyPart, MimePart, Stream},
+};
+
+use crate::{Attachment, Body, Email, Header};
+
+/// The maximum number of recipients of each of the `To`, `Cc` and `Bcc` fields
+const MAX_RECIPIENTS: usize = 50;
+
+/// Why a `MessageBuilder` can't be converted to an `Email`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MessageConversionError {
+    /// The message has no `From` address, or more than one
+    InvalidFrom,
+    /// The message has no `To` address. Postmark requires at least one, even if the message has
+    /// `Cc` or `Bcc` recipients.
+    MissingRecipients,
+    /// The message has neither a text nor an HTML body
+    MissingBody,
+    /// The message has more than one text or HTML body
+    MultipleBodies(String),
+    /// The `To`, `Cc` or `Bcc` header has more than 50 recipients, the maximum accepted by
+    /// Postmark for each header
+    TooManyRecipients {
+        header: String,
+        count: usize,
+    },
+    /// The header can't be set with Postmark, e.g. `Sender`
+    UnsupportedHeader(String),
+    /// The MIME part can't be represented by Postmark, e.g. a `multipart/signed` part or a part
+    /// with a custom `Content-Transfer-Encoding`
+    UnsupportedPart(String),
+}
+
+impl fmt::Display for MessageConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            MessageConversionError::InvalidFrom => {
+                f.write_str("postmark: the message must have exactly one From address")
+            }
+            MessageConversionError::MissingRecipients => f.write_str("postmark: the message has no To address"),
+            MessageConversionError::MissingBody => f.write_str("postmark: the message has no text or HTML body"),
+            MessageConversionError::MultipleBodies(content_type) => {
+                write!(f, "postmark: the message has more than one {content_type} body")
+            }
+            MessageConversionError::TooManyRecipients {
+                header,
+                count,
+            } => write!(
+                f,
+                "postmark: {header} has {count} recipients but at most {MAX_RECIPIENTS} are supported"
+            ),
+            MessageConversionError::UnsupportedHeader(header) => write!(f, "postmark: unsupported header: {header}"),
+            MessageConversionError::UnsupportedPart(reason) => write!(f, "postmark: unsupported MIME part: {reason}"),
+        };
+    }
+}
+
+impl std::error::Error for MessageConversionError {}
+
+/// Convert a message built with `mail_builder` to a Postmark `Email`.
+///
+/// Group names of addresses are dropped, and the `Date`, `MIME-Version` and `Content-*` headers of
+/// the message are ignored because Postmark sets them.
+impl<'x> TryFrom<MessageBuilder<'x>> for Email {
+    type Error = MessageConversionError;
+
+    fn try_from(message: MessageBuilder<'x>) -> Result<Self, Self::Error> {
+        let mut email = Email::default();
+        let mut from = Vec::new();
+        let mut reply_to = Vec::new();
+        let mut headers = Vec::new();
+
+        for (name, value) in message.headers {
+            match (name.to_ascii_lowercase().as_str(), value) {
+                ("from", HeaderType::Address(address)) => flatten_addresses(address, &mut from),
+                ("to", HeaderType::Address(address)) => email.to = recipients("To", address)?,
+                ("cc", HeaderType::Address(address)) => email.cc = Some(recipients("Cc", address)?),
+                ("bcc", HeaderType::Address(address)) => email.bcc = Some(recipients("Bcc", address)?),
+                ("reply-to", HeaderType::Address(address)) => flatten_addresses(address, &mut reply_to),
+                ("subject", value) => email.subject = Some(header_value(&value)),
+                ("date" | "mime-version", _) => {}
+                ("sender" | "from" | "to" | "cc" | "bcc" | "reply-to", _) => {
+                    return Err(MessageConversionError::UnsupportedHeader(name.into_owned()));
+                }
+                (lowercase_name, _) if lowercase_name.starts_with("content-") => {}
+                (_, value) => headers.push(Header {
+                    name: name.into_owned(),
+                    value: header_value(&value),
+                }),
+            }
+        }
+
+        let [from] = from.as_slice() else {
+            return Err(MessageConversionError::InvalidFrom);
+        };
+        email.from = format_address(from);
+        if email.to.is_empty() {
+            return Err(MessageConversionError::MissingRecipients);
+        }
+        if !reply_to.is_empty() {
+            email.reply_to = Some(format_addresses(&reply_to));
+        }
+        if !headers.is_empty() {
+            email.headers = Some(headers);
+        }
+
+        let mut parts = Parts::default();
+        match message.body {
+            Some(body) => parts.add(body)?,
+            // Postmark only sends text and HTML alternatives
+            None if message.calendar_body.is_some() => {
+                return Err(MessageConversionError::UnsupportedPart("text/calendar alternative".to_string()));
+            }
+            None => {
+                if let Some(text) = message.text_body {
+                    parts.add(text)?;
+                }
+                if let Some(html) = message.html_body {
+                    parts.add(html)?;
+                }
+                for attachment in message.attachments.unwrap_or_default() {
+                    parts.add_attachment(attachment)?;
+                }
+            }
+        }
+
+        email.body = match (parts.html, parts.text) {
+            (Some(html), Some(text)) => Body::html_and_text(html, text),
+            (Some(html), None) if message.generate_text_body => {
+                let text = html_to_text(&html);
+                Body::html_and_text(html, text)
+            }
+            (Some(html), None) => Body::html(html),
+            (None, Some(text)) => Body::text(text),
+            (None, None) => return Err(MessageConversionError::MissingBody),
+        };
+        if !parts.attachments.is_empty() {
+            email.attachments = Some(parts.attachments);
+        }
+
+        return Ok(email);
+    }
+}
+
+/// The bodies and attachments found while walking the MIME tree of a message
+#[derive(Default)]
+struct Parts {
+    text: Option<String>,
+    html: Option<String>,
+    attachments: Vec<Attachment>,
+}
+
+impl Parts {
+    fn add(&mut self, part: MimePart<'_>) -> Result<(), MessageConversionError> {
+        let content_type = part_content_type(&part).map(|content_type| content_type.c_type.to_ascii_lowercase());
+
+        if let BodyPart::Multipart(children) = part.contents {
+            return match content_type.as_deref() {
+                Some("multipart/signed" | "multipart/encrypted") | None => {
+                    Err(MessageConversionError::UnsupportedPart(
+                        content_type.unwrap_or_else(|| "raw multipart".to_string()),
+                    ))
+                }
+                _ => children.into_iter().try_for_each(|child| self.add(child)),
+            };
+        }
+
+        let is_attachment = part_disposition(&part).is_some_and(|disposition| disposition.is_attachment());
+        let body = match content_type.as_deref() {
+            Some("text/plain") if !is_attachment => &mut self.text,
+            Some("text/html") if !is_attachment => &mut self.html,
+            _ => return self.add_attachment(part),
+        };
+        if body.is_some() {
+            return Err(MessageConversionError::MultipleBodies(content_type.unwrap_or_default()));
+        }
+        check_transfer_encoding(&part)?;
+
+        *body = Some(match part.contents {
+            BodyPart::Text(text) => text.into_owned(),
+            BodyPart::Binary(binary) => String::from_utf8(binary.into_owned())
+                .map_err(|_| MessageConversionError::UnsupportedPart("body is not valid UTF-8".to_string()))?,
+            BodyPart::Stream(stream) => String::from_utf8(read_stream(&stream)?)
+                .map_err(|_| MessageConversionError::UnsupportedPart("body is not valid UTF-8".to_string()))?,
+            BodyPart::Multipart(_) => unreachable!(),
+        });
+        return Ok(());
+    }
+
+    fn add_attachment(&mut self, part: MimePart<'_>) -> Result<(), MessageConversionError> {
+        check_transfer_encoding(&part)?;
+        let Some(content_type) = part_content_type(&part) else {
+            return Err(MessageConversionError::UnsupportedPart(
+                "raw parts can't be attached".to_string(),
+            ));
+        };
+
+        let content_id = part.headers.iter().find_map(|(name, value)| match value {
+            HeaderType::MessageId(id) if name.eq_ignore_ascii_case("Content-ID") => id.id.first().cloned(),
+            _ => None,
+        });
+        // the cid: prefix is used in HTML bodies, not in the Content-ID header
+        let content_id = content_id.map(|id| id.strip_prefix("cid:").unwrap_or(&id).to_string());
+
+        let name = part_disposition(&part)
+            .and_then(|disposition| attribute(disposition, "filename"))
+            .or_else(|| attribute(content_type, "name"))
+            .map(|name| name.to_string())
+            .or_else(|| content_id.clone())
+            .ok_or_else(|| MessageConversionError::UnsupportedPart("attachment without a name".to_string()))?;
+
+        let content = match &part.contents {
+            BodyPart::Text(text) => base64::encode(text.as_bytes()),
+            BodyPart::Binary(binary) => base64::encode(binary.as_ref()),
+            // Postmark takes attachments inline in the JSON request
+            BodyPart::Stream(stream) => base64::encode(&read_stream(stream)?),
+            BodyPart::Multipart(_) => {
+                return Err(MessageConversionError::UnsupportedPart(
+                    "multipart attachments are not supported".to_string(),
+                ));
+            }
+        };
+
+        self.attachments.push(Attachment {
+            name,
+            content,
+            content_type: content_type.c_type.to_string(),
+            content_id: content_id.map(|id| format!("cid:{id}")),
+        });
+        return Ok(());
+    }
+}
+
+fn part_content_type<'a>(part: &'a MimePart<'_>) -> Option<&'a ContentType<'a>> {
+    return part.headers.iter().find_map(|(name, value)| match value {
+        HeaderType::ContentType(content_type) if name.eq_ignore_ascii_case("Content-Type") => Some(content_type),
+        _ => None,
+    });
+}
+
+fn part_disposition<'a>(part: &'a MimePart<'_>) -> Option<&'a ContentType<'a>> {
+    return part.headers.iter().find_map(|(name, value)| match value {
+        HeaderType::ContentType(disposition) if name.eq_ignore_ascii_case("Content-Disposition") => Some(disposition),
+        _ => None,
+    });
+}
+
+fn attribute<'a>(content_type: &'a ContentType<'_>, name: &str) -> Option<&'a str> {
+    return content_type
+        .attributes
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_ref());
+}
+
+/// Postmark encodes the parts itself: parts with a custom transfer encoding can't be converted
+fn check_transfer_encoding(part: &MimePart<'_>) -> Result<(), MessageConversionError> {
+    if part
+        .headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("Content-Transfer-Encoding"))
+    {
+        return Err(MessageConversionError::UnsupportedPart(
+            "custom Content-Transfer-Encoding".to_string(),
+        ));
+    }
+    return Ok(());
+}
+
+fn read_stream(stream: &Stream) -> Result<Vec<u8>, MessageConversionError> {
+    let mut contents = Vec::new();
+    stream
+        .open()
+        .and_then(|mut reader| reader.read_to_end(&mut contents))
+        .map_err(|err| MessageConversionError::UnsupportedPart(format!("can't read streamed contents: {err}")))?;
+    return Ok(contents);
+}
+
+fn flatten_addresses<'x>(address: Address<'x>, addresses: &mut Vec<EmailAddress<'x>>) {
+    match address {
+        Address::Address(address) => addresses.push(address),
+        Address::Group(group) => group
+            .addresses
+            .into_iter()
+            .for_each(|address| flatten_addresses(address, addresses)),
+        Address::List(list) => list
+            .into_iter()
+            .for_each(|address| flatten_addresses(address, addresses)),
+    }
+}
+
+fn recipients(header: &str, address: Address<'_>) -> Result<String, MessageConversionError> {
+    let mut addresses = Vec::new();
+    flatten_addresses(address, &mut addresses);
+    if addresses.len() > MAX_RECIPIENTS {
+        return Err(MessageConversionError::TooManyRecipients {
+            header: header.to_string(),
+            count: addresses.len(),
+        });
+    }
+    return Ok(format_addresses(&addresses));
+}
+
+fn format_addresses(addresses: &[EmailAddress<'_>]) -> String {
+    return addresses.iter().map(format_address).collect::<Vec<_>>().join(", ");
+}
+
+/// Format an address as `"Display Name" <email>`, quoting the name only if needed
+fn format_address(address: &EmailAddress<'_>) -> String {
+    let Some(name) = address.name.as_deref().filter(|name| !name.is_empty()) else {
+        return address.email.to_string();
+    };
+
+    let needs_quotes = name.chars().any(|c| "()<>[]:;@\\,.\"".contains(c));
+    let name: Cow<str> = if needs_quotes {
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\"")).into()
+    } else {
+        name.into()
+    };
+    return format!("{name} <{}>", address.email);
+}
+
+/// Render the value of a header without any encoding or folding: Postmark encodes the headers itself.
+fn header_value(value: &HeaderType<'_>) -> String {
+    return match value {
+        HeaderType::Address(address) => {
+            let mut addresses = Vec::new();
+            flatten_addresses(address.clone(), &mut addresses);
+            format_addresses(&addresses)
+        }
+        HeaderType::Date(date) => date.to_rfc822(),
+        HeaderType::MessageId(message_id) => message_id
+            .id
+            .iter()
+            .map(|id| format!("<{id}>"))
+            .collect::<Vec<_>>()
+            .join(" "),
+        HeaderType::Raw(raw) => raw.raw.to_string(),
+        HeaderType::Text(text) => text.text.to_string(),
+        HeaderType::URL(url) => url
+            .url
+            .iter()
+            .map(|url| format!("<{url}>"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        HeaderType::ContentType(content_type) => {
+            let mut value = content_type.c_type.to_string();
+            for (key, attribute) in &content_type.attributes {
+                value.push_str(&format!("; {key}=\"{attribute}\""));
+            }
+            value
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use mail_builder::{
+        headers::{raw::Raw, url::URL},
+        icalendar::{Attendee, Calendar, DateTime, Event, Method, Organizer},
+    };
+
+    use super::*;
+
+    #[test]
+    fn convert_message_builder() {
+        let message = MessageBuilder::new()
+            .from(("Doe, John", "john@doe.com"))
+            .to(vec![("Jane Doe", "jane@doe.com"), ("", "bob@doe.com")])
+            .bcc(("Team", vec![("Alice", "alice@doe.com")]))
+            .reply_to("support@doe.com")
+            .subject("Hello, world!")
+            .in_reply_to("previous@doe.com")
+            .header("List-Unsubscribe", URL::new("https://doe.com/unsubscribe"))
+            .header("X-Campaign", Raw::new("spring"))
+            .text_body("Hello!")
+            .html_body("<p>Hello! <img src=\"cid:logo\"></p>")
+            .inline("image/png", "cid:logo", [1, 2, 3].as_ref())
+            .attachment("text/plain", "notes.txt", "notes");
+
+        let email = Email::try_from(message).unwrap();
+        assert_eq!(email.from, "\"Doe, John\" <john@doe.com>");
+        assert_eq!(email.to, "Jane Doe <jane@doe.com>, bob@doe.com");
+        assert_eq!(email.bcc.as_deref(), Some("Alice <alice@doe.com>"));
+        assert_eq!(email.reply_to.as_deref(), Some("support@doe.com"));
+        assert_eq!(email.subject.as_deref(), Some("Hello, world!"));
+        assert_eq!(
+            email.body,
+            Body::html_and_text("<p>Hello! <img src=\"cid:logo\"></p>".to_string(), "Hello!".to_string())
+        );
+
+        let headers: Vec<(&str, &str)> = email
+            .headers
+            .iter()
+            .flatten()
+            .map(|header| (header.name.as_str(), header.value.as_str()))
+            .collect();
+        assert_eq!(
+            headers,
+            vec![
+                ("In-Reply-To", "<previous@doe.com>"),
+                ("List-Unsubscribe", "<https://doe.com/unsubscribe>"),
+                ("X-Campaign", "spring"),
+            ]
+        );
+
+        let attachments = email.attachments.unwrap();
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].name, "logo");
+        assert_eq!(attachments[0].content, "AQID");
+        assert_eq!(attachments[0].content_id.as_deref(), Some("cid:logo"));
+        assert_eq!(attachments[1].name, "notes.txt");
+        assert_eq!(attachments[1].content_type, "text/plain");
+        assert_eq!(attachments[1].content_id, None);
+
+        let message = MessageBuilder::new()
+            .from("john@doe.com")
+            .to("jane@doe.com")
+            .html_body("<p>Hello, <b>world</b>!</p>")
+            .generate_text_body(true);
+        assert_eq!(
+            Email::try_from(message).unwrap().body,
+            Body::html_and_text("<p>Hello, <b>world</b>!</p>".to_string(), "Hello, world!".to_string())
+        );
+    }
+
+    #[test]
+    fn unsupported_messages() {
+        let message = MessageBuilder::new().from("john@doe.com").to("jane@doe.com");
+        assert_eq!(Email::try_from(message).unwrap_err(), MessageConversionError::MissingBody);
+
+        let message = MessageBuilder::new()
+            .from("john@doe.com")
+            .bcc("jane@doe.com")
+            .text_body("Hello!");
+        assert_eq!(Email::try_from(message).unwrap_err(), MessageConversionError::MissingRecipients);
+
+        let message = MessageBuilder::new()
+            .from("john@doe.com")
+            .sender("bounces@doe.com")
+            .text_body("Hello!");
+        assert_eq!(
+            Email::try_from(message).unwrap_err(),
+            MessageConversionError::UnsupportedHeader("Sender".to_string())
+        );
+
+        let message = MessageBuilder::new()
+            .from("john@doe.com")
+            .to("jane@doe.com")
+            .body(MimePart::new(
+                "multipart/signed",
+                vec![
+                    MimePart::new("text/plain", "Hello!"),
+                    MimePart::new("application/pgp-signature", "sig"),
+                ],
+            ));
+        assert!(matches!(
+            Email::try_from(message),
+            Err(MessageConversionError::UnsupportedPart(_))
+        ));
+
+        let calendar = Calendar::new().method(Method::Request).event(
+            Event::new("meeting@doe.com", DateTime::utc(1700042400))
+                .summary("Project review")
+                .organizer(Organizer::new("john@doe.com"))
+                .attendee(Attendee::new("jane@doe.com")),
+        );
+        let message = MessageBuilder::new()
+            .from("john@doe.com")
+            .to("jane@doe.com")
+            .text_body("You are invited.")
+            .calendar(&calendar);
+        assert_eq!(
+            Email::try_from(message).unwrap_err(),
+            MessageConversionError::UnsupportedPart("text/calendar alternative".to_string())
+        );
+    }
+}
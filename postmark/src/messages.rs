@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ApiError, Client, SendRequestInput,
+    client::url_with_query,
+    pagination::{Page, PageStream, paginate},
+};
+
+/// An email sent by the server
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct OutboundMessage {
+    pub tag: Option<String>,
+    #[serde(rename = "MessageID")]
+    pub message_id: String,
+    pub message_stream: Option<String>,
+    #[serde(default)]
+    pub to: Vec<MessageAddress>,
+    #[serde(default)]
+    pub cc: Vec<MessageAddress>,
+    #[serde(default)]
+    pub bcc: Vec<MessageAddress>,
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    pub received_at: String,
+    pub from: String,
+    pub subject: Option<String>,
+    /// The names of the attachments
+    #[serde(default)]
+    pub attachments: Vec<String>,
+    /// `Queued` or `Sent`
+    pub status: String,
+    #[serde(default)]
+    pub track_opens: bool,
+    pub track_links: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub sandboxed: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MessageAddress {
+    pub email: String,
+    pub name: Option<String>,
+}
+
+/// An email sent by the server, with its content and what happened to it
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct OutboundMessageDetails {
+    #[serde(flatten)]
+    pub message: OutboundMessage,
+    pub text_body: Option<String>,
+    pub html_body: Option<String>,
+    /// The raw source of the email
+    pub body: Option<String>,
+    #[serde(default)]
+    pub message_events: Vec<MessageEvent>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MessageEvent {
+    pub recipient: String,
+    /// `Delivered`, `Transient`, `Opened`, `LinkClicked`, `Bounced`, `SubscriptionChanged`...
+    pub r#type: String,
+    pub received_at: String,
+    #[serde(default)]
+    pub details: HashMap<String, serde_json::Value>,
+}
+
+/// Filters for `search_outbound_messages`. Dates use the `2024-01-31T12:00:00` format, in Eastern Time.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SearchOutboundMessagesOptions {
+    /// Number of messages to return. Max 500.
+    pub count: i64,
+    /// Number of messages to skip. `count + offset` can't exceed 10,000.
+    pub offset: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+    #[serde(rename = "fromemail", skip_serializing_if = "Option::is_none")]
+    pub from_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// `queued` or `sent`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(rename = "fromdate", skip_serializing_if = "Option::is_none")]
+    pub from_date: Option<String>,
+    #[serde(rename = "todate", skip_serializing_if = "Option::is_none")]
+    pub to_date: Option<String>,
+    #[serde(rename = "messagestream", skip_serializing_if = "Option::is_none")]
+    pub message_stream: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SearchOutboundMessagesResponse {
+    pub total_count: i64,
+    pub messages: Vec<OutboundMessage>,
+}
+
+impl Client {
+    pub async fn search_outbound_messages(
+        &self,
+        server_token: String,
+        options: SearchOutboundMessagesOptions,
+    ) -> Result<SearchOutboundMessagesResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::GET,
+                url: url_with_query("/messages/outbound", &options)?,
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Stream all the outbound messages matching `options`, fetching them page by page.
+    /// `options.count` and `options.offset` are ignored.
+    /// Postmark only lists the first `MAX_RECORDS` (10,000) messages: narrow down `options`, e.g.
+    /// with `from_date` and `to_date`, to get the others.
+    pub fn outbound_messages_stream(
+        &self,
+        server_token: String,
+        options: SearchOutboundMessagesOptions,
+    ) -> PageStream<'_, OutboundMessage> {
+        return paginate(move |offset, count| {
+            let server_token = server_token.clone();
+            let options = SearchOutboundMessagesOptions {
+                count,
+                offset,
+                ..options.clone()
+            };
+            async move {
+                let res = self.search_outbound_messages(server_token, options).await?;
+                return Ok(Page {
+                    items: res.messages,
+                    total_count: res.total_count,
+                });
+            }
+        });
+    }
+
+    pub async fn get_outbound_message_details(
+        &self,
+        server_token: String,
+        message_id: &str,
+    ) -> Result<OutboundMessageDetails, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::GET,
+                url: format!("/messages/outbound/{message_id}/details"),
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+}
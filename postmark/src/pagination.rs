@@ -0,0 +1,171 @@
+use std::{collections::VecDeque, future::Future, pin::Pin};
+
+use futures_util::{Stream, stream};
+
+use crate::ApiError;
+
+/// The maximum number of records that can be fetched with a single request
+const MAX_PAGE_SIZE: i64 = 500;
+
+/// Postmark rejects requests where `count + offset` is above this limit, so at most this many
+/// records can be listed
+pub const MAX_RECORDS: i64 = 10_000;
+
+/// A stream of records fetched page by page, e.g. with `Client::bounces_stream`.
+/// The stream ends after the first error.
+pub type PageStream<'a, T> = Pin<Box<dyn Stream<Item = Result<T, ApiError>> + Send + 'a>>;
+
+pub(crate) struct Page<T> {
+    pub items: Vec<T>,
+    pub total_count: i64,
+}
+
+struct Paginator<F, T> {
+    fetch_page: F,
+    offset: i64,
+    buffer: VecDeque<T>,
+    done: bool,
+}
+
+/// Stream the records returned by `fetch_page`, which is called with the offset and the count of
+/// the next page until all the records, or the first `MAX_RECORDS`, have been fetched.
+pub(crate) fn paginate<'a, T, F, Fut>(fetch_page: F) -> PageStream<'a, T>
+where
+    T: Send + 'a,
+    F: FnMut(i64, i64) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<Page<T>, ApiError>> + Send + 'a,
+{
+    let paginator = Paginator {
+        fetch_page,
+        offset: 0,
+        buffer: VecDeque::new(),
+        done: false,
+    };
+
+    return Box::pin(stream::unfold(paginator, |mut paginator| async move {
+        loop {
+            if let Some(item) = paginator.buffer.pop_front() {
+                return Some((Ok(item), paginator));
+            }
+            if paginator.done {
+                return None;
+            }
+
+            let count = MAX_PAGE_SIZE.min(MAX_RECORDS - paginator.offset);
+            match (paginator.fetch_page)(paginator.offset, count).await {
+                Ok(page) => {
+                    paginator.offset += page.items.len() as i64;
+                    paginator.done = page.items.is_empty()
+                        || paginator.offset >= page.total_count
+                        || paginator.offset >= MAX_RECORDS;
+                    paginator.buffer.extend(page.items);
+                }
+                Err(err) => {
+                    paginator.done = true;
+                    return Some((Err(err), paginator));
+                }
+            }
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures_util::StreamExt;
+
+    use super::*;
+    use crate::ErrorCode;
+
+    /// Serve `total_count` records, with at most `max_items` per page, and record the requests
+    fn fetch_pages(
+        total_count: i64,
+        max_items: i64,
+        requests: Arc<Mutex<Vec<(i64, i64)>>>,
+    ) -> impl FnMut(i64, i64) -> std::future::Ready<Result<Page<i64>, ApiError>> + Send {
+        return move |offset, count| {
+            requests.lock().unwrap().push((offset, count));
+            let end = (offset + count.min(max_items)).min(total_count);
+            return std::future::ready(Ok(Page {
+                items: (offset..end).collect(),
+                total_count,
+            }));
+        };
+    }
+
+    #[tokio::test]
+    async fn multiple_pages() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let items: Vec<i64> = paginate(fetch_pages(1_200, 500, requests.clone()))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(items, (0..1_200).collect::<Vec<_>>());
+        assert_eq!(*requests.lock().unwrap(), [(0, 500), (500, 500), (1_000, 500)]);
+
+        // the API may return fewer records than requested
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let items: Vec<i64> = paginate(fetch_pages(250, 100, requests.clone()))
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(items, (0..250).collect::<Vec<_>>());
+        assert_eq!(*requests.lock().unwrap(), [(0, 500), (100, 500), (200, 500)]);
+    }
+
+    #[tokio::test]
+    async fn empty_page() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        assert_eq!(paginate(fetch_pages(0, 500, requests.clone())).count().await, 0);
+        assert_eq!(*requests.lock().unwrap(), [(0, 500)]);
+
+        // records deleted while paginating: the total count is stale
+        let mut calls = 0;
+        let stream = paginate(move |offset, _| {
+            calls += 1;
+            let items = if calls == 1 { vec![offset] } else { Vec::new() };
+            return std::future::ready(Ok::<_, ApiError>(Page {
+                items,
+                total_count: 10,
+            }));
+        });
+        assert_eq!(stream.map(Result::unwrap).collect::<Vec<_>>().await, [0]);
+    }
+
+    #[tokio::test]
+    async fn stop_after_error() {
+        let mut calls = 0;
+        let stream = paginate(move |offset, _| {
+            calls += 1;
+            return std::future::ready(if calls == 1 {
+                Ok(Page {
+                    items: vec![offset, offset + 1],
+                    total_count: 10,
+                })
+            } else {
+                Err(ApiError {
+                    error_code: ErrorCode::RequestFailed,
+                    message: "connection reset".to_string(),
+                })
+            });
+        });
+        let results = stream.collect::<Vec<_>>().await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &0);
+        assert_eq!(results[1].as_ref().unwrap(), &1);
+        assert_eq!(results[2].as_ref().unwrap_err().error_code, ErrorCode::RequestFailed);
+    }
+
+    #[tokio::test]
+    async fn stop_at_max_records() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        assert_eq!(
+            paginate(fetch_pages(25_000, 300, requests.clone())).count().await,
+            MAX_RECORDS as usize
+        );
+        let requests = requests.lock().unwrap();
+        assert!(requests.iter().all(|(offset, count)| offset + count <= MAX_RECORDS));
+        assert_eq!(requests.last(), Some(&(9_900, 100)));
+    }
+}
@@ -1,5 +1,25 @@
+mod bounces;
 mod client;
 mod emails;
+mod error;
+#[cfg(feature = "mail_builder")]
+mod message_builder;
+mod messages;
+mod pagination;
+mod suppressions;
+mod templates;
+mod transport;
+mod webhooks;
 
+pub use bounces::*;
 pub use client::*;
 pub use emails::*;
+pub use error::*;
+#[cfg(feature = "mail_builder")]
+pub use message_builder::*;
+pub use messages::*;
+pub use pagination::{MAX_RECORDS, PageStream};
+pub use suppressions::*;
+pub use templates::*;
+pub use transport::*;
+pub use webhooks::*;
@@ -0,0 +1,132 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{ApiError, Client, SendRequestInput, client::url_with_query};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Suppression {
+    pub email_address: String,
+    /// Why sending is suppressed: `HardBounce`, `SpamComplaint` or `ManualSuppression`
+    pub suppression_reason: String,
+    /// Who suppressed the address: `Recipient`, `Customer` or `Admin`
+    pub origin: String,
+    pub created_at: String,
+}
+
+/// Filters for `dump_suppressions`. Dates use the `2024-01-31` format.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DumpSuppressionsOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppression_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    #[serde(rename = "fromdate", skip_serializing_if = "Option::is_none")]
+    pub from_date: Option<String>,
+    #[serde(rename = "todate", skip_serializing_if = "Option::is_none")]
+    pub to_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_address: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DumpSuppressionsResponse {
+    pub suppressions: Vec<Suppression>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct SuppressionRequest {
+    email_address: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct SuppressionsRequest {
+    suppressions: Vec<SuppressionRequest>,
+}
+
+/// The result of the creation or the deletion of a suppression, for each address
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SuppressionResult {
+    pub email_address: String,
+    /// `Suppressed`, `Deleted` or `Failed`
+    pub status: String,
+    /// Why the operation failed, if `status` is `Failed`
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SuppressionsResponse {
+    pub suppressions: Vec<SuppressionResult>,
+}
+
+impl Client {
+    /// List the addresses that emails of the message stream are no longer sent to.
+    pub async fn dump_suppressions(
+        &self,
+        server_token: String,
+        message_stream: &str,
+        options: DumpSuppressionsOptions,
+    ) -> Result<DumpSuppressionsResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::GET,
+                url: url_with_query(&format!("/message-streams/{message_stream}/suppressions/dump"), &options)?,
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Stop sending the emails of the message stream to the given addresses. Up to 50 addresses can be
+    /// suppressed with a single request.
+    pub async fn create_suppressions(
+        &self,
+        server_token: String,
+        message_stream: &str,
+        email_addresses: Vec<String>,
+    ) -> Result<SuppressionsResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::POST,
+                url: format!("/message-streams/{message_stream}/suppressions"),
+                body: Some(suppressions_request(email_addresses)),
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Delete the suppressions of the given addresses. Up to 50 addresses can be reactivated with a single
+    /// request. Addresses suppressed after a spam complaint can't be reactivated.
+    pub async fn delete_suppressions(
+        &self,
+        server_token: String,
+        message_stream: &str,
+        email_addresses: Vec<String>,
+    ) -> Result<SuppressionsResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::POST,
+                url: format!("/message-streams/{message_stream}/suppressions/delete"),
+                body: Some(suppressions_request(email_addresses)),
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+}
+
+fn suppressions_request(email_addresses: Vec<String>) -> SuppressionsRequest {
+    return SuppressionsRequest {
+        suppressions: email_addresses
+            .into_iter()
+            .map(|email_address| SuppressionRequest {
+                email_address,
+            })
+            .collect(),
+    };
+}
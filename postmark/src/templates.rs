@@ -0,0 +1,381 @@
+use std::fmt;
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{ApiError, Client, ErrorCode, SendRequestInput, client::url_with_query};
+
+/// A template is identified either by its numeric ID or by its alias.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TemplateIdentifier {
+    #[serde(rename = "TemplateId")]
+    Id(i64),
+    #[serde(rename = "TemplateAlias")]
+    Alias(String),
+}
+
+impl fmt::Display for TemplateIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            TemplateIdentifier::Id(id) => write!(f, "{id}"),
+            TemplateIdentifier::Alias(alias) => f.write_str(alias),
+        };
+    }
+}
+
+/// Standard templates are used to send emails, while layout templates are shared by standard templates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TemplateType {
+    #[default]
+    Standard,
+    Layout,
+}
+
+impl fmt::Display for TemplateType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            TemplateType::Standard => f.write_str("Standard"),
+            TemplateType::Layout => f.write_str("Layout"),
+        };
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Template {
+    pub template_id: i64,
+    pub name: String,
+    pub alias: Option<String>,
+    pub subject: Option<String>,
+    pub html_body: Option<String>,
+    pub text_body: Option<String>,
+    pub associated_server_id: i64,
+    pub active: bool,
+    pub template_type: TemplateType,
+    /// The alias of the layout template used by this template, if any.
+    pub layout_template: Option<String>,
+}
+
+/// A template as returned when listing templates, without its content.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TemplateSummary {
+    pub template_id: i64,
+    pub name: String,
+    pub alias: Option<String>,
+    pub active: bool,
+    pub template_type: TemplateType,
+    pub layout_template: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListTemplatesOptions {
+    /// Number of templates to return. Max 500.
+    pub count: i64,
+    /// Number of templates to skip.
+    pub offset: i64,
+    /// Filter the templates by type. All the templates are returned by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_type: Option<TemplateType>,
+    /// Filter the templates by the alias of their layout template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_template: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListTemplatesResponse {
+    pub total_count: i64,
+    pub templates: Vec<TemplateSummary>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CreateTemplateRequest {
+    pub name: String,
+
+    /// An optional string you can provide to identify this template. Allowed characters are numbers, ASCII letters,
+    /// and '.', '-', '_' characters, and the string has to start with a letter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    /// The content to use for the Subject when this template is used to send email. Required for standard
+    /// templates, not allowed for layout templates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+
+    /// The content to use for the HtmlBody when this template is used to send email. Required if `text_body` is not
+    /// specified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html_body: Option<String>,
+
+    /// The content to use for the TextBody when this template is used to send email. Required if `html_body` is not
+    /// specified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_body: Option<String>,
+
+    /// Defaults to `Standard`. The type can't be changed after the template is created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_type: Option<TemplateType>,
+
+    /// The alias of the layout template to use for this template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_template: Option<String>,
+}
+
+/// The fields to update. Fields set to `None` are left unchanged.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct EditTemplateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html_body: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_body: Option<String>,
+
+    /// The alias of the layout template to use for this template. Use an empty string to remove the layout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_template: Option<String>,
+}
+
+/// The response to the creation or the update of a template.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TemplateResponse {
+    pub template_id: i64,
+    pub name: String,
+    pub alias: Option<String>,
+    pub active: bool,
+    pub template_type: TemplateType,
+    pub layout_template: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeleteTemplateResponse {
+    pub error_code: ErrorCode,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ValidateTemplateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html_body: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text_body: Option<String>,
+
+    /// The model used to render the content. If not provided, a model is suggested from the content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_render_model: Option<serde_json::Value>,
+
+    /// Whether the style blocks of the HTML body are applied as inline attributes to the rendered content.
+    /// Defaults to `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inline_css_for_html_test_render: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template_type: Option<TemplateType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout_template: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ValidateTemplateResponse {
+    pub all_content_is_valid: bool,
+    pub subject: Option<TemplateValidationResult>,
+    pub html_body: Option<TemplateValidationResult>,
+    pub text_body: Option<TemplateValidationResult>,
+    /// A model that can be used to render the content, inferred from the content.
+    pub suggested_template_model: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TemplateValidationResult {
+    pub content_is_valid: bool,
+    #[serde(default)]
+    pub validation_errors: Vec<TemplateValidationError>,
+    pub rendered_content: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TemplateValidationError {
+    pub message: String,
+    pub line: Option<i64>,
+    pub character_position: Option<i64>,
+}
+
+impl Client {
+    pub async fn list_templates(
+        &self,
+        server_token: String,
+        options: ListTemplatesOptions,
+    ) -> Result<ListTemplatesResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::GET,
+                url: url_with_query("/templates", &options)?,
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    pub async fn get_template(
+        &self,
+        server_token: String,
+        template: &TemplateIdentifier,
+    ) -> Result<Template, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::GET,
+                url: format!("/templates/{template}"),
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    pub async fn create_template(
+        &self,
+        server_token: String,
+        template: CreateTemplateRequest,
+    ) -> Result<TemplateResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::POST,
+                url: "/templates".to_string(),
+                body: Some(template),
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    pub async fn edit_template(
+        &self,
+        server_token: String,
+        template: &TemplateIdentifier,
+        changes: EditTemplateRequest,
+    ) -> Result<TemplateResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::PUT,
+                url: format!("/templates/{template}"),
+                body: Some(changes),
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    pub async fn delete_template(
+        &self,
+        server_token: String,
+        template: &TemplateIdentifier,
+    ) -> Result<DeleteTemplateResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::DELETE,
+                url: format!("/templates/{template}"),
+                body: None::<()>,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Check that the content of a template is valid and render it with a test model, without creating
+    /// a template.
+    pub async fn validate_template(
+        &self,
+        server_token: String,
+        template: ValidateTemplateRequest,
+    ) -> Result<ValidateTemplateResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput {
+                method: Method::POST,
+                url: "/templates/validate".to_string(),
+                body: Some(template),
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TemplateEmail;
+
+    #[test]
+    fn list_templates_query() {
+        let options = ListTemplatesOptions {
+            count: 100,
+            offset: 0,
+            template_type: Some(TemplateType::Layout),
+            layout_template: Some("base & footer".to_string()),
+        };
+        assert_eq!(
+            url_with_query("/templates", &options).unwrap(),
+            "/templates?Count=100&Offset=0&TemplateType=Layout&LayoutTemplate=base+%26+footer"
+        );
+
+        let options = ListTemplatesOptions {
+            count: 10,
+            ..Default::default()
+        };
+        assert_eq!(url_with_query("/templates", &options).unwrap(), "/templates?Count=10&Offset=0");
+    }
+
+    #[test]
+    fn serialize_template_email() {
+        let email = TemplateEmail {
+            template: TemplateIdentifier::Id(42),
+            template_model: serde_json::json!({ "name": "Jane" }),
+            inline_css: None,
+            from: "sender@example.com".to_string(),
+            to: "jane@example.com".to_string(),
+            cc: None,
+            bcc: None,
+            tag: None,
+            reply_to: None,
+            headers: None,
+            track_opens: None,
+            track_links: None,
+            attachments: None,
+            metadata: None,
+            message_stream: None,
+        };
+        let json = serde_json::to_value(&email).unwrap();
+        assert_eq!(json["TemplateId"], 42);
+        assert!(json.get("Template").is_none());
+        assert_eq!(json["TemplateModel"]["name"], "Jane");
+
+        let email = TemplateEmail {
+            template: TemplateIdentifier::Alias("welcome".to_string()),
+            ..email
+        };
+        let json = serde_json::to_value(&email).unwrap();
+        assert_eq!(json["TemplateAlias"], "welcome");
+        assert!(json.get("TemplateId").is_none());
+
+        let parsed: TemplateEmail = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.template, TemplateIdentifier::Alias("welcome".to_string()));
+    }
+}
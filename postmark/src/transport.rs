@@ -0,0 +1,73 @@
+use std::{future::Future, pin::Pin};
+
+use reqwest::{Method, header::HeaderMap};
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+/// Sends the HTTP requests of the `Client`. `ReqwestTransport` is used by default, and tests can use a
+/// local stand-in to not depend on `api.postmarkapp.com`.
+pub trait HttpTransport: Send + Sync {
+    /// Send the request and return the response, whatever its status. An error is returned only if the
+    /// request could not be sent or the response could not be received.
+    fn send(
+        &self,
+        request: HttpRequest,
+    ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>>;
+}
+
+/// The default transport, backed by a `reqwest::Client`
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    pub http_client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(http_client: reqwest::Client) -> ReqwestTransport {
+        return ReqwestTransport {
+            http_client,
+        };
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send(
+        &self,
+        request: HttpRequest,
+    ) -> BoxFuture<'_, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        return Box::pin(async move {
+            let mut req = self
+                .http_client
+                .request(request.method, request.url)
+                .headers(request.headers);
+            if let Some(body) = request.body {
+                req = req.body(body);
+            }
+
+            let res = req.send().await?;
+            let status = res.status().as_u16();
+            let headers = res.headers().clone();
+            let body = res.bytes().await?.to_vec();
+
+            return Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            });
+        });
+    }
+}
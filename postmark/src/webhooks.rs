@@ -0,0 +1,551 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+
+use crate::{Bounce, Header};
+
+/// A webhook sent by Postmark. The kind of webhook is identified by its `RecordType` field.
+///
+/// Inbound webhooks don't have a `RecordType` field: payloads without `RecordType` are parsed as
+/// `Webhook::Inbound`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "RecordType")]
+pub enum Webhook {
+    Bounce(BounceWebhook),
+    Delivery(DeliveryWebhook),
+    Open(OpenWebhook),
+    Click(ClickWebhook),
+    SpamComplaint(SpamComplaintWebhook),
+    SubscriptionChange(SubscriptionChangeWebhook),
+    Inbound(InboundWebhook),
+}
+
+impl<'de> Deserialize<'de> for Webhook {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut payload = serde_json::Map::deserialize(deserializer)?;
+        let record_type = match payload.remove("RecordType") {
+            Some(serde_json::Value::String(record_type)) => Some(record_type),
+            Some(_) => return Err(D::Error::custom("RecordType is not a string")),
+            None => None,
+        };
+        let payload = serde_json::Value::Object(payload);
+
+        let webhook = match record_type.as_deref() {
+            Some("Bounce") => serde_json::from_value(payload).map(Webhook::Bounce),
+            Some("Delivery") => serde_json::from_value(payload).map(Webhook::Delivery),
+            Some("Open") => serde_json::from_value(payload).map(Webhook::Open),
+            Some("Click") => serde_json::from_value(payload).map(Webhook::Click),
+            Some("SpamComplaint") => serde_json::from_value(payload).map(Webhook::SpamComplaint),
+            Some("SubscriptionChange") => serde_json::from_value(payload).map(Webhook::SubscriptionChange),
+            Some("Inbound") | None => serde_json::from_value(payload).map(Webhook::Inbound),
+            Some(record_type) => return Err(D::Error::custom(format!("unknown RecordType: {record_type}"))),
+        };
+
+        return webhook.map_err(D::Error::custom);
+    }
+}
+
+/// A bounce, as returned by the bounces API, with the metadata of the bounced email
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BounceWebhook {
+    #[serde(flatten)]
+    pub bounce: Bounce,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeliveryWebhook {
+    #[serde(rename = "ServerID")]
+    pub server_id: Option<i64>,
+    pub message_stream: Option<String>,
+    #[serde(rename = "MessageID")]
+    pub message_id: String,
+    pub recipient: String,
+    pub tag: Option<String>,
+    pub delivered_at: String,
+    pub details: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct OpenWebhook {
+    pub message_stream: Option<String>,
+    /// Whether this is the first time the recipient opened the email
+    #[serde(default)]
+    pub first_open: bool,
+    pub client: Option<UserAgentDetails>,
+    #[serde(rename = "OS")]
+    pub os: Option<UserAgentDetails>,
+    pub platform: Option<String>,
+    pub user_agent: Option<String>,
+    pub read_seconds: Option<i64>,
+    pub geo: Option<Geolocation>,
+    #[serde(rename = "MessageID")]
+    pub message_id: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    pub received_at: String,
+    pub tag: Option<String>,
+    pub recipient: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ClickWebhook {
+    pub message_stream: Option<String>,
+    /// Where the link was, e.g. `HTML` or `Text`
+    pub click_location: Option<String>,
+    pub client: Option<UserAgentDetails>,
+    #[serde(rename = "OS")]
+    pub os: Option<UserAgentDetails>,
+    pub platform: Option<String>,
+    pub user_agent: Option<String>,
+    pub original_link: String,
+    pub geo: Option<Geolocation>,
+    #[serde(rename = "MessageID")]
+    pub message_id: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    pub received_at: String,
+    pub tag: Option<String>,
+    pub recipient: String,
+}
+
+/// Spam complaints have the same fields as bounces, with the `SpamComplaint` type
+pub type SpamComplaintWebhook = BounceWebhook;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SubscriptionChangeWebhook {
+    #[serde(rename = "MessageID")]
+    pub message_id: String,
+    #[serde(rename = "ServerID")]
+    pub server_id: Option<i64>,
+    pub message_stream: Option<String>,
+    pub changed_at: String,
+    pub recipient: String,
+    /// What triggered the change, e.g. `Recipient`, `Customer` or `Admin`
+    pub origin: Option<String>,
+    /// Whether emails are no longer sent to the recipient
+    pub suppress_sending: bool,
+    /// Why sending is suppressed, e.g. `HardBounce`, `SpamComplaint` or `ManualSuppression`
+    pub suppression_reason: Option<String>,
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// The email client or operating system used to open an email or click a link
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct UserAgentDetails {
+    pub name: Option<String>,
+    pub company: Option<String>,
+    pub family: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Geolocation {
+    #[serde(rename = "CountryISOCode")]
+    pub country_iso_code: Option<String>,
+    pub country: Option<String>,
+    #[serde(rename = "RegionISOCode")]
+    pub region_iso_code: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub zip: Option<String>,
+    pub coords: Option<String>,
+    #[serde(rename = "IP")]
+    pub ip: Option<String>,
+}
+
+/// An email received by an inbound server
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct InboundWebhook {
+    pub from_name: Option<String>,
+    pub message_stream: Option<String>,
+    pub from: String,
+    pub from_full: Option<InboundAddress>,
+    pub to: Option<String>,
+    #[serde(default)]
+    pub to_full: Vec<InboundAddress>,
+    pub cc: Option<String>,
+    #[serde(default)]
+    pub cc_full: Vec<InboundAddress>,
+    pub bcc: Option<String>,
+    #[serde(default)]
+    pub bcc_full: Vec<InboundAddress>,
+    pub original_recipient: Option<String>,
+    pub subject: Option<String>,
+    #[serde(rename = "MessageID")]
+    pub message_id: String,
+    pub reply_to: Option<String>,
+    pub mailbox_hash: Option<String>,
+    pub date: Option<String>,
+    pub text_body: Option<String>,
+    pub html_body: Option<String>,
+    /// The text of the reply, without the quoted text of the previous emails
+    pub stripped_text_reply: Option<String>,
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub headers: Vec<Header>,
+    #[serde(default)]
+    pub attachments: Vec<InboundAttachment>,
+}
+
+impl InboundWebhook {
+    /// Get the value of the first header with the given name. Header names are case-insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        return self
+            .headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str());
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct InboundAddress {
+    pub email: String,
+    #[serde(default)]
+    pub name: String,
+    pub mailbox_hash: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct InboundAttachment {
+    pub name: String,
+    /// The content of the attachment, encoded in base64. Use `decode_content` to get the raw content.
+    pub content: String,
+    pub content_type: String,
+    pub content_length: Option<i64>,
+    #[serde(rename = "ContentID")]
+    pub content_id: Option<String>,
+}
+
+impl InboundAttachment {
+    /// Decode the base64-encoded content of the attachment.
+    pub fn decode_content(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        // Postmark may wrap the base64 content on multiple lines
+        let content: Vec<u8> = self
+            .content
+            .bytes()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .collect();
+        return base64::decode(&content);
+    }
+}
+
+/// Check the `Authorization` header of a webhook request against the basic-auth credentials configured
+/// for the webhook in Postmark.
+///
+/// The credentials are compared in constant time to not leak them through timing attacks.
+pub fn check_webhook_basic_auth(authorization_header: Option<&str>, username: &str, password: &str) -> bool {
+    let Some(authorization_header) = authorization_header else {
+        return false;
+    };
+    let Some((scheme, credentials)) = authorization_header.trim().split_once(' ') else {
+        return false;
+    };
+    if !scheme.eq_ignore_ascii_case("basic") {
+        return false;
+    }
+    let Ok(credentials) = base64::decode(credentials.trim().as_bytes()) else {
+        return false;
+    };
+    let Some(separator) = credentials.iter().position(|byte| *byte == b':') else {
+        return false;
+    };
+    let (received_username, received_password) = (&credentials[..separator], &credentials[separator + 1..]);
+
+    // always compare both the username and the password to not leak which one is wrong
+    let username_matches = constant_time_eq::constant_time_eq(received_username, username.as_bytes());
+    let password_matches = constant_time_eq::constant_time_eq(received_password, password.as_bytes());
+    return username_matches & password_matches;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_AGENT: &str = r#""UserAgent": "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_7_5) AppleWebKit/537.36",
+        "OS": { "Name": "OS X 10.7 Lion", "Family": "OS X 10", "Company": "Apple Computer, Inc." },
+        "Client": { "Name": "Chrome 35.0.1916.153", "Family": "Chrome", "Company": "Google" },
+        "Geo": { "IP": "188.2.95.4", "City": "Novi Sad", "Country": "Serbia", "CountryISOCode": "RS",
+            "Region": "Autonomna Pokrajina Vojvodina", "RegionISOCode": "VO", "Zip": "21000",
+            "Coords": "45.2517,19.8369" }"#;
+
+    fn bounce_payload(record_type: &str, bounce_type: &str, type_code: i64) -> String {
+        return format!(
+            r#"{{
+                "RecordType": "{record_type}",
+                "MessageStream": "outbound",
+                "ID": 4323372036854775807,
+                "Type": "{bounce_type}",
+                "TypeCode": {type_code},
+                "Name": "Hard bounce",
+                "Tag": "Test",
+                "MessageID": "883953f4-6105-42a2-a16a-77a8eac79483",
+                "Metadata": {{ "a_key": "a_value", "b_key": "b_value" }},
+                "ServerID": 23,
+                "Description": "The server was unable to deliver your message (ex: unknown user, mailbox not found).",
+                "Details": "Test bounce details",
+                "Email": "john@example.com",
+                "From": "sender@example.com",
+                "BouncedAt": "2019-11-05T16:33:54.9070259Z",
+                "DumpAvailable": true,
+                "Inactive": true,
+                "CanActivate": true,
+                "Subject": "Test subject",
+                "Content": "Test content"
+            }}"#
+        );
+    }
+
+    fn parse(payload: &str) -> Webhook {
+        return serde_json::from_str(payload).unwrap();
+    }
+
+    #[test]
+    fn parse_bounce_and_spam_complaint() {
+        let webhook = parse(&bounce_payload("Bounce", "HardBounce", 1));
+        let Webhook::Bounce(bounce) = &webhook else {
+            panic!("expected a bounce");
+        };
+        assert_eq!(bounce.bounce.id, 4323372036854775807);
+        assert_eq!(bounce.bounce.r#type, "HardBounce");
+        assert_eq!(bounce.bounce.email, "john@example.com");
+        assert!(bounce.bounce.inactive);
+        assert_eq!(bounce.metadata["a_key"], "a_value");
+
+        // the bounce fields are flattened back next to the metadata
+        let json = serde_json::to_value(&webhook).unwrap();
+        assert_eq!(json["RecordType"], "Bounce");
+        assert_eq!(json["Email"], "john@example.com");
+        assert_eq!(json["Metadata"]["a_key"], "a_value");
+
+        let Webhook::SpamComplaint(complaint) = parse(&bounce_payload("SpamComplaint", "SpamComplaint", 512)) else {
+            panic!("expected a spam complaint");
+        };
+        assert_eq!(complaint.bounce.type_code, 512);
+        assert_eq!(complaint.bounce.server_id, Some(23));
+        assert_eq!(complaint.metadata["a_key"], "a_value");
+    }
+
+    #[test]
+    fn parse_delivery() {
+        let Webhook::Delivery(delivery) = parse(
+            r#"{
+                "RecordType": "Delivery",
+                "ServerID": 23,
+                "MessageStream": "outbound",
+                "MessageID": "00000000-0000-0000-0000-000000000000",
+                "Recipient": "john@example.com",
+                "Tag": "welcome-email",
+                "DeliveredAt": "2021-02-21T16:34:52Z",
+                "Details": "Test delivery webhook details",
+                "Metadata": { "a_key": "a_value", "b_key": "b_value" }
+            }"#,
+        ) else {
+            panic!("expected a delivery");
+        };
+        assert_eq!(delivery.recipient, "john@example.com");
+        assert_eq!(delivery.delivered_at, "2021-02-21T16:34:52Z");
+        assert_eq!(delivery.tag.as_deref(), Some("welcome-email"));
+    }
+
+    #[test]
+    fn parse_open_and_click() {
+        let Webhook::Open(open) = parse(&format!(
+            r#"{{
+                "RecordType": "Open",
+                "MessageStream": "outbound",
+                "Metadata": {{ "example": "value" }},
+                "FirstOpen": true,
+                "Recipient": "john@example.com",
+                "MessageID": "00000000-0000-0000-0000-000000000000",
+                "ReceivedAt": "2019-11-05T16:33:54.9070259Z",
+                "Platform": "WebMail",
+                "ReadSeconds": 5,
+                "Tag": "welcome-email",
+                {USER_AGENT}
+            }}"#
+        )) else {
+            panic!("expected an open");
+        };
+        assert!(open.first_open);
+        assert_eq!(open.read_seconds, Some(5));
+        assert_eq!(open.os.unwrap().family.as_deref(), Some("OS X 10"));
+        assert_eq!(open.geo.unwrap().country_iso_code.as_deref(), Some("RS"));
+
+        let Webhook::Click(click) = parse(&format!(
+            r#"{{
+                "RecordType": "Click",
+                "MessageStream": "outbound",
+                "Metadata": {{ "example": "value" }},
+                "Recipient": "john@example.com",
+                "MessageID": "00000000-0000-0000-0000-000000000000",
+                "ReceivedAt": "2019-11-05T16:33:54.9070259Z",
+                "Platform": "Desktop",
+                "ClickLocation": "HTML",
+                "OriginalLink": "https://example.com",
+                "Tag": "welcome-email",
+                {USER_AGENT}
+            }}"#
+        )) else {
+            panic!("expected a click");
+        };
+        assert_eq!(click.original_link, "https://example.com");
+        assert_eq!(click.click_location.as_deref(), Some("HTML"));
+        assert_eq!(click.client.unwrap().name.as_deref(), Some("Chrome 35.0.1916.153"));
+    }
+
+    #[test]
+    fn parse_subscription_change() {
+        let Webhook::SubscriptionChange(change) = parse(
+            r#"{
+                "RecordType": "SubscriptionChange",
+                "MessageID": "00000000-0000-0000-0000-000000000000",
+                "ServerID": 23,
+                "MessageStream": "bulk",
+                "ChangedAt": "2020-02-01T10:53:34.416071Z",
+                "Recipient": "bounced-address@example.com",
+                "Origin": "Recipient",
+                "SuppressSending": true,
+                "SuppressionReason": "HardBounce",
+                "Tag": "my-tag",
+                "Metadata": { "example": "value" }
+            }"#,
+        ) else {
+            panic!("expected a subscription change");
+        };
+        assert!(change.suppress_sending);
+        assert_eq!(change.suppression_reason.as_deref(), Some("HardBounce"));
+        assert_eq!(change.message_stream.as_deref(), Some("bulk"));
+    }
+
+    #[test]
+    fn parse_inbound() {
+        let payload = r#"{
+            "FromName": "Postmarkapp Support",
+            "MessageStream": "inbound",
+            "From": "support@postmarkapp.com",
+            "FromFull": { "Email": "support@postmarkapp.com", "Name": "Postmarkapp Support", "MailboxHash": "" },
+            "To": "\"Firstname Lastname\" <yourhash+SampleHash@inbound.postmarkapp.com>",
+            "ToFull": [
+                { "Email": "yourhash+SampleHash@inbound.postmarkapp.com", "Name": "Firstname Lastname",
+                  "MailboxHash": "SampleHash" }
+            ],
+            "Cc": "\"First Cc\" <firstcc@postmarkapp.com>",
+            "CcFull": [{ "Email": "firstcc@postmarkapp.com", "Name": "First Cc", "MailboxHash": "" }],
+            "OriginalRecipient": "yourhash+SampleHash@inbound.postmarkapp.com",
+            "Subject": "Test subject",
+            "MessageID": "73e6d360-66eb-11e1-8e72-a8904824019b",
+            "ReplyTo": "replyto@postmarkapp.com",
+            "MailboxHash": "SampleHash",
+            "Date": "Fri, 1 Aug 2014 16:45:32 -04:00",
+            "TextBody": "This is a test text body.",
+            "HtmlBody": "<html><body><p>This is a test html body.</p></body></html>",
+            "StrippedTextReply": "This is the reply text",
+            "Tag": "TestTag",
+            "Headers": [
+                { "Name": "X-Header-Test", "Value": "" },
+                { "Name": "X-Spam-Status", "Value": "No" }
+            ],
+            "Attachments": [
+                { "Name": "test.txt", "Content": "VGhpcyBpcyBhdHRhY2htZW50IGNvbnRlbnRzLCBiYXNlLTY0IGVuY29kZWQu",
+                  "ContentType": "text/plain", "ContentLength": 45 }
+            ]
+        }"#;
+
+        for payload in [
+            payload.to_string(),
+            payload.replacen('{', r#"{ "RecordType": "Inbound","#, 1),
+        ] {
+            let Webhook::Inbound(inbound) = parse(&payload) else {
+                panic!("expected an inbound email");
+            };
+            assert_eq!(inbound.from, "support@postmarkapp.com");
+            assert_eq!(inbound.to_full[0].mailbox_hash.as_deref(), Some("SampleHash"));
+            assert_eq!(inbound.cc_full[0].name, "First Cc");
+            assert!(inbound.bcc_full.is_empty());
+            assert_eq!(inbound.header("x-spam-status"), Some("No"));
+            assert_eq!(
+                inbound.attachments[0].decode_content().unwrap(),
+                b"This is attachment contents, base-64 encoded."
+            );
+        }
+
+        assert!(serde_json::from_str::<Webhook>(r#"{ "RecordType": "Unknown" }"#).is_err());
+    }
+
+    #[test]
+    fn decode_wrapped_attachment() {
+        let attachment = InboundAttachment {
+            name: "test.txt".to_string(),
+            content: "VGhpcyBpcyBhdHRhY2htZW50IGNv\r\nbnRlbnRzLCBiYXNlLTY0IGVu\r\nY29kZWQu\r\n".to_string(),
+            content_type: "text/plain".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            attachment.decode_content().unwrap(),
+            b"This is attachment contents, base-64 encoded."
+        );
+
+        let attachment = InboundAttachment {
+            content: "not base64!".to_string(),
+            ..attachment
+        };
+        assert!(attachment.decode_content().is_err());
+    }
+
+    #[test]
+    fn check_basic_auth() {
+        let header = |credentials: &[u8]| format!("Basic {}", base64::encode(credentials));
+
+        assert!(check_webhook_basic_auth(
+            Some(&header(b"postmark:s3cret")),
+            "postmark",
+            "s3cret"
+        ));
+        assert!(check_webhook_basic_auth(
+            Some(&header(b"postmark:s3:cret")),
+            "postmark",
+            "s3:cret"
+        ));
+        assert!(check_webhook_basic_auth(
+            Some(&header(b"postmark:s3cret").replace("Basic", "basic")),
+            "postmark",
+            "s3cret"
+        ));
+
+        assert!(!check_webhook_basic_auth(
+            Some(&header(b"postmark:wrong")),
+            "postmark",
+            "s3cret"
+        ));
+        assert!(!check_webhook_basic_auth(Some(&header(b"other:s3cret")), "postmark", "s3cret"));
+        assert!(!check_webhook_basic_auth(
+            Some(&header(b"postmark:s3cret").replace("Basic", "Bearer")),
+            "postmark",
+            "s3cret"
+        ));
+        assert!(!check_webhook_basic_auth(Some("Basic not*base64"), "postmark", "s3cret"));
+        assert!(!check_webhook_basic_auth(
+            Some(&header(b"postmarks3cret")),
+            "postmark",
+            "s3cret"
+        ));
+        assert!(!check_webhook_basic_auth(Some("Basic"), "postmark", "s3cret"));
+        assert!(!check_webhook_basic_auth(None, "postmark", "s3cret"));
+    }
+}